@@ -0,0 +1,349 @@
+use crate::*;
+use clap::Parser as ClapParser;
+use submit_referendum::{
+	deliver_output, generate_calls, parse_dispatch, parse_output, parse_track,
+};
+
+/// Build a treasury spend on Asset Hub and generate the calls needed to submit it as a referendum
+/// on the smallest spender track that may approve it.
+#[derive(Debug, ClapParser)]
+pub struct SpendArgs {
+	/// Network whose treasury pays out. `polkadot` or `kusama`.
+	#[clap(long = "network", short)]
+	network: String,
+
+	/// The asset to pay out: `native` (DOT or KSM), `usdt`, `usdc`, or the ID of an asset in
+	/// Asset Hub's `Assets` pallet. Defaults to `native`.
+	#[clap(long = "asset", default_value = "native")]
+	asset: String,
+
+	/// The number of decimals of an asset given by its ID.
+	#[clap(long = "decimals")]
+	decimals: Option<u8>,
+
+	/// The amount to pay out, in whole tokens of the asset, e.g. `1000` or `1,250.5`.
+	#[clap(long = "amount")]
+	amount: String,
+
+	/// The account to pay, as an SS58 address or a hex-encoded 32-byte account ID.
+	#[clap(long = "beneficiary")]
+	beneficiary: String,
+
+	/// Optional: The block from which the spend can be paid out. Defaults to immediately.
+	#[clap(long = "valid-from")]
+	valid_from: Option<u32>,
+
+	/// The value of one token of a non-native asset in DOT or KSM, as in the `AssetRate` pallet,
+	/// e.g. `0.25`. Used to pick the track.
+	#[clap(long = "asset-rate")]
+	asset_rate: Option<String>,
+
+	/// Submit on this track instead of the smallest spender track that may approve the spend.
+	#[clap(long = "track", short)]
+	track: Option<String>,
+
+	/// Optional: Enact at a particular block number.
+	#[clap(long = "at")]
+	at: Option<u32>,
+
+	/// Optional: Enact after a given number of blocks.
+	#[clap(long = "after")]
+	after: Option<u32>,
+
+	/// Do not print batch calls. Defaults to false.
+	#[clap(long = "no-batch")]
+	no_batch: bool,
+
+	/// Form of output. `AppsUiLink`, `CallData`, or `Json`. Defaults to Apps UI.
+	#[clap(long = "output")]
+	output: Option<String>,
+
+	/// Use light client endpoints instead of RPC for PAPI links.
+	#[clap(long = "light-client")]
+	light_client: bool,
+}
+
+/// The index of the `Assets` pallet on both Asset Hubs.
+pub const ASSETS_PALLET: u8 = 50;
+
+// The units in which the `Spender` origins in the runtimes' `governance/origins.rs` are defined.
+const POLKADOT_GRAND: u128 = 1_000 * 10_000_000_000;
+const KUSAMA_QUID: u128 = 1_000_000_000_000 / 30;
+const KUSAMA_GRAND: u128 = 1_000 * KUSAMA_QUID;
+
+/// The asset that a treasury spend pays out.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SpendAsset {
+	/// The network's native token, DOT or KSM.
+	Native,
+	/// An asset in Asset Hub's `Assets` pallet.
+	Asset { id: u128, symbol: String, decimals: u8 },
+}
+
+impl SpendAsset {
+	/// Parse a user-provided asset on the Asset Hub of `network`: `native`, `usdt`, `usdc`, or an
+	/// asset ID, in which case `decimals` must be given.
+	pub fn parse(asset: &str, decimals: Option<u8>, network: &Network) -> Result<Self, Error> {
+		let known = |id: u128, symbol: &str, known_decimals: u8| match decimals {
+			Some(d) if d != known_decimals => Err(Error::InvalidInput(format!(
+				"{symbol} has {known_decimals} decimals, not {d}."
+			))),
+			_ =>
+				Ok(SpendAsset::Asset { id, symbol: String::from(symbol), decimals: known_decimals }),
+		};
+		match (asset.to_ascii_lowercase().as_str(), network.relay()) {
+			("native", _) => Ok(SpendAsset::Native),
+			("usdt", _) => known(1984, "USDT", 6),
+			("usdc", Network::Polkadot) => known(1337, "USDC", 6),
+			(id, _) => {
+				let id = id.parse::<u128>().map_err(|_| {
+					Error::InvalidInput(format!(
+						"`--asset` must be `native`, `usdt`, `usdc` (Polkadot only), or an asset ID, not \
+						`{asset}`."
+					))
+				})?;
+				let decimals = decimals.ok_or_else(|| {
+					Error::InvalidInput(String::from("Pass `--decimals` for an asset given by ID."))
+				})?;
+				Ok(SpendAsset::Asset { id, symbol: format!("asset {id}"), decimals })
+			},
+		}
+	}
+
+	/// The symbol and number of decimals of the asset on the Asset Hub of `network`.
+	pub fn token(&self, network: &Network) -> (String, u8) {
+		match &self {
+			SpendAsset::Native => {
+				let (symbol, decimals) = network.token();
+				(String::from(symbol), decimals)
+			},
+			SpendAsset::Asset { symbol, decimals, .. } => (symbol.clone(), *decimals),
+		}
+	}
+}
+
+/// The sub-command's "main" function.
+pub async fn build_spend(prefs: SpendArgs) -> Result<(), Error> {
+	let network = match Network::from_name(&prefs.network) {
+		Ok(network @ (Network::Polkadot | Network::Kusama)) => network,
+		_ =>
+			return Err(Error::UnsupportedNetwork(String::from(
+				"`network` must be `polkadot` or `kusama`.",
+			))),
+	};
+	let (native_symbol, native_decimals) = network.token();
+
+	let asset = SpendAsset::parse(&prefs.asset, prefs.decimals, &network)?;
+	let (symbol, decimals) = asset.token(&network);
+	let amount = parse_amount(&prefs.amount, decimals)?;
+	let beneficiary = parse_account(&prefs.beneficiary, &network)?;
+
+	// What the spend is worth in the native token, which is what the spender origins are limited
+	// by.
+	let native_value = match (&asset, prefs.asset_rate) {
+		(SpendAsset::Native, _) => Some(amount),
+		(SpendAsset::Asset { .. }, Some(rate)) => {
+			let rate = parse_amount(&rate, native_decimals)?;
+			let value = amount.checked_mul(rate).map(|value| value / 10u128.pow(decimals as u32));
+			Some(value.ok_or_else(|| {
+				Error::InvalidInput(String::from(
+					"The spend is too large to value in the native token.",
+				))
+			})?)
+		},
+		(SpendAsset::Asset { .. }, None) => None,
+	};
+
+	let track = match (prefs.track, native_value) {
+		(Some(track), _) => {
+			let track = parse_track(&prefs.network, &track)?;
+			let limit = spender_tracks(&network)?
+				.into_iter()
+				.find(|(t, _)| t.name() == track.name())
+				.map(|(_, limit)| limit);
+			if let (Some(limit), Some(value)) = (limit, native_value) {
+				if value > limit {
+					return Err(Error::UnsupportedTrack(format!(
+						"The {} track may only spend up to {} {native_symbol}.",
+						track.name(),
+						format_amount(limit, native_decimals)
+					)));
+				}
+			}
+			track
+		},
+		(None, Some(value)) => spender_track(&network, value)?,
+		(None, None) => return Err(Error::InvalidInput(String::from(
+			"Pass `--asset-rate` to value the spend in the native token, or choose a track with \
+				`--track`.",
+		))),
+	};
+
+	let spend = CallInfo::from_runtime_call(spend_call(
+		&network,
+		&asset,
+		amount,
+		beneficiary,
+		prefs.valid_from,
+	)?);
+
+	let output = parse_output(prefs.output)?;
+	if !matches!(output, Output::Json) {
+		println!(
+			"\nSpend {} {symbol} to 0x{}",
+			format_amount(amount, decimals),
+			hex::encode(beneficiary)
+		);
+		if let (SpendAsset::Asset { .. }, Some(value)) = (&asset, native_value) {
+			println!("Worth {} {native_symbol}", format_amount(value, native_decimals));
+		}
+		println!("\nTreasury spend call:\n0x{}\n", hex::encode(&spend.encoded));
+	}
+	let dispatch = parse_dispatch(prefs.at, prefs.after, &output)?;
+	if !matches!(output, Output::Json) {
+		let track = list_tracks::track_for(&network, &track)?;
+		list_tracks::print_track(&network, &track, Some(&dispatch));
+	}
+
+	let proposal_details =
+		ProposalDetails::builder(format!("0x{}", hex::encode(&spend.encoded)), track)
+			.dispatch(dispatch)
+			.output(output)
+			.print_batch(!prefs.no_batch)
+			.use_light_client(prefs.light_client)
+			.build()?;
+	let calls = generate_calls(&proposal_details).await?;
+	deliver_output(proposal_details, calls)?;
+	Ok(())
+}
+
+/// The spender tracks of the Relay Chain `network`, from least to most privileged, with the most
+/// that each may spend, in the smallest unit of the native token.
+pub fn spender_tracks(network: &Network) -> Result<Vec<(NetworkTrack, u128)>, Error> {
+	use KusamaAssetHubOpenGovOrigin as K;
+	use PolkadotAssetHubOpenGovOrigin as P;
+	match network {
+		Network::Polkadot => Ok(vec![
+			(NetworkTrack::Polkadot(P::SmallTipper), POLKADOT_GRAND / 4),
+			(NetworkTrack::Polkadot(P::BigTipper), POLKADOT_GRAND),
+			(NetworkTrack::Polkadot(P::SmallSpender), 10 * POLKADOT_GRAND),
+			(NetworkTrack::Polkadot(P::MediumSpender), 100 * POLKADOT_GRAND),
+			(NetworkTrack::Polkadot(P::BigSpender), 1_000 * POLKADOT_GRAND),
+			(NetworkTrack::Polkadot(P::Treasurer), 10_000 * POLKADOT_GRAND),
+		]),
+		Network::Kusama => Ok(vec![
+			(NetworkTrack::Kusama(K::SmallTipper), 250 * KUSAMA_QUID),
+			(NetworkTrack::Kusama(K::BigTipper), KUSAMA_GRAND),
+			(NetworkTrack::Kusama(K::SmallSpender), 10 * KUSAMA_GRAND),
+			(NetworkTrack::Kusama(K::MediumSpender), 100 * KUSAMA_GRAND),
+			(NetworkTrack::Kusama(K::BigSpender), 1_000 * KUSAMA_GRAND),
+			(NetworkTrack::Kusama(K::Treasurer), 10_000 * KUSAMA_GRAND),
+		]),
+		_ => Err(Error::UnsupportedNetwork(String::from(
+			"`network` must be `polkadot` or `kusama`.",
+		))),
+	}
+}
+
+/// The least privileged track on `network` that may approve a spend worth `native_value` of the
+/// native token. Spends larger than any spender track may approve go to `Root`.
+pub fn spender_track(network: &Network, native_value: u128) -> Result<NetworkTrack, Error> {
+	let track = spender_tracks(network)?.into_iter().find(|(_, limit)| native_value <= *limit);
+	Ok(match (track, network) {
+		(Some((track, _)), _) => track,
+		(None, Network::Kusama) => NetworkTrack::KusamaRoot,
+		(None, _) => NetworkTrack::PolkadotRoot,
+	})
+}
+
+/// A `Treasury::spend` of `amount` of `asset` (in its smallest unit) to `beneficiary`, on the Asset
+/// Hub of the Relay Chain `network`.
+pub fn spend_call(
+	network: &Network,
+	asset: &SpendAsset,
+	amount: u128,
+	beneficiary: [u8; 32],
+	valid_from: Option<u32>,
+) -> Result<NetworkRuntimeCall, Error> {
+	match network {
+		Network::Polkadot => {
+			use polkadot_asset_hub::runtime_types::{
+				pallet_treasury::pallet::Call as TreasuryCall,
+				parachains_common::pay::VersionedLocatableAccount,
+				polkadot_runtime_common::impls::VersionedLocatableAsset,
+				staging_xcm::v5::{
+					asset::AssetId,
+					junction::Junction::{AccountId32, GeneralIndex, PalletInstance},
+					junctions::Junctions::{Here, X1, X2},
+					location::Location,
+				},
+			};
+			// Locations are relative to Asset Hub, where the treasury pays out.
+			let asset_id = match asset {
+				SpendAsset::Native => Location { parents: 1, interior: Here },
+				SpendAsset::Asset { id, .. } => Location {
+					parents: 0,
+					interior: X2([PalletInstance(ASSETS_PALLET), GeneralIndex(*id)]),
+				},
+			};
+			Ok(NetworkRuntimeCall::PolkadotAssetHub(PolkadotAssetHubRuntimeCall::Treasury(
+				TreasuryCall::spend {
+					asset_kind: Box::new(VersionedLocatableAsset::V5 {
+						location: Location { parents: 0, interior: Here },
+						asset_id: AssetId(asset_id),
+					}),
+					amount,
+					beneficiary: Box::new(VersionedLocatableAccount::V5 {
+						location: Location { parents: 0, interior: Here },
+						account_id: Location {
+							parents: 0,
+							interior: X1([AccountId32 { network: None, id: beneficiary }]),
+						},
+					}),
+					valid_from,
+				},
+			)))
+		},
+		Network::Kusama => {
+			use kusama_asset_hub::runtime_types::{
+				pallet_treasury::pallet::Call as TreasuryCall,
+				parachains_common::pay::VersionedLocatableAccount,
+				polkadot_runtime_common::impls::VersionedLocatableAsset,
+				staging_xcm::v5::{
+					asset::AssetId,
+					junction::Junction::{AccountId32, GeneralIndex, PalletInstance},
+					junctions::Junctions::{Here, X1, X2},
+					location::Location,
+				},
+			};
+			// Locations are relative to Asset Hub, where the treasury pays out.
+			let asset_id = match asset {
+				SpendAsset::Native => Location { parents: 1, interior: Here },
+				SpendAsset::Asset { id, .. } => Location {
+					parents: 0,
+					interior: X2([PalletInstance(ASSETS_PALLET), GeneralIndex(*id)]),
+				},
+			};
+			Ok(NetworkRuntimeCall::KusamaAssetHub(KusamaAssetHubRuntimeCall::Treasury(
+				TreasuryCall::spend {
+					asset_kind: Box::new(VersionedLocatableAsset::V5 {
+						location: Location { parents: 0, interior: Here },
+						asset_id: AssetId(asset_id),
+					}),
+					amount,
+					beneficiary: Box::new(VersionedLocatableAccount::V5 {
+						location: Location { parents: 0, interior: Here },
+						account_id: Location {
+							parents: 0,
+							interior: X1([AccountId32 { network: None, id: beneficiary }]),
+						},
+					}),
+					valid_from,
+				},
+			)))
+		},
+		_ => Err(Error::UnsupportedNetwork(String::from(
+			"`network` must be `polkadot` or `kusama`.",
+		))),
+	}
+}
@@ -1,195 +1,205 @@
 use crate::*;
 use clap::Parser as ClapParser;
+use dynamic::MetadataOverrides;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
+/// The checksum file that is used, if present in the upgrade directory, when `--checksums` is not
+/// given.
+pub const DEFAULT_CHECKSUM_FILE: &str = "checksums.txt";
+
 /// Generate a single call that will upgrade all system chains in a given network.
 #[derive(Debug, ClapParser)]
-pub(crate) struct UpgradeArgs {
+pub struct UpgradeArgs {
 	/// Network on which to submit the referendum. `polkadot` or `kusama`.
 	#[clap(long = "network", short)]
-	pub(crate) network: String,
+	pub network: String,
 
 	/// Only include the runtimes explicitly specified.
 	#[clap(long = "only")]
-	pub(crate) only: bool,
+	pub only: bool,
 
 	/// Use local WASM files instead of downloading from GitHub. Files are assumed to already be in
 	/// the upgrade directory.
 	#[clap(long = "local")]
-	pub(crate) local: bool,
+	pub local: bool,
+
+	/// Use the Wasm file at a given path for a chain instead of the release's, e.g.
+	/// `--wasm asset-hub=path/to/runtime.wasm`. Chains are `relay`, `asset-hub`, `bridge-hub`,
+	/// `collectives`, `encointer`, `people`, or `coretime`. These files are never downloaded. Can
+	/// be repeated.
+	#[clap(long = "wasm", value_name = "CHAIN=PATH")]
+	pub wasm: Vec<String>,
 
-	/// Skip sanity checks on the downloaded runtime blobs (e.g. their file size).
+	/// Skip sanity checks on the runtime blobs (e.g. the file size of downloaded blobs, and the
+	/// embedded `spec_name` and `spec_version` of every blob).
 	#[clap(long = "no-runtime-checks")]
-	pub(crate) no_runtime_checks: bool,
+	pub no_runtime_checks: bool,
+
+	/// Path or URL of a checksum file for the release's runtimes, with lines of the form
+	/// `<hex digest>  <file name>`. Digests are SHA-256 unless prefixed with `blake2_256:`. If not
+	/// provided, `checksums.txt` in the upgrade directory is used if it exists.
+	#[clap(long = "checksums")]
+	pub checksums: Option<String>,
+
+	/// Encode calls with the metadata of a newer runtime instead of the bundled metadata. Either a
+	/// runtime Wasm blob or a SCALE-encoded metadata file. The network is taken from its
+	/// `spec_name`. Can be repeated, once per network.
+	#[clap(long = "metadata")]
+	pub metadata: Vec<String>,
 
 	/// The Fellowship release version. Should be semver and correspond to the release published.
 	#[clap(long = "relay-version")]
-	pub(crate) relay_version: Option<String>,
+	pub relay_version: Option<String>,
 
 	/// Optional. The runtime version of Asset Hub to which to upgrade. If not provided, it will use
 	/// the Relay Chain's version.
 	#[clap(long = "asset-hub")]
-	pub(crate) asset_hub: Option<String>,
+	pub asset_hub: Option<String>,
 
 	/// Optional. The runtime version of Bridge Hub to which to upgrade. If not provided, it will use
 	/// the Relay Chain's version.
 	#[clap(long = "bridge-hub")]
-	pub(crate) bridge_hub: Option<String>,
+	pub bridge_hub: Option<String>,
 
 	/// Optional. The runtime version of Collectives to which to upgrade. If not provided, it will
 	/// use the Relay Chain's version.
 	#[clap(long = "collectives")]
-	pub(crate) collectives: Option<String>,
+	pub collectives: Option<String>,
 
 	/// Optional. The runtime version of Encointer to which to upgrade. If not provided, it will use
 	/// the Relay Chain's version.
 	#[clap(long = "encointer")]
-	pub(crate) encointer: Option<String>,
+	pub encointer: Option<String>,
 
 	/// Optional. The runtime version of People to which to upgrade. If not provided, it will use
 	/// the Relay Chain's version.
 	#[clap(long = "people")]
-	pub(crate) people: Option<String>,
+	pub people: Option<String>,
 
 	/// Optional. The runtime version of Coretime to which to upgrade. If not provided, it will use
 	/// the Relay Chain's version.
 	#[clap(long = "coretime")]
-	pub(crate) coretime: Option<String>,
+	pub coretime: Option<String>,
 
 	/// Name of the file to which to write the output. If not provided, a default will be
 	/// constructed.
 	#[clap(long = "filename")]
-	pub(crate) filename: Option<String>,
+	pub filename: Option<String>,
 
 	/// Some additional call that you want executed on the Relay Chain along with the upgrade.
 	#[clap(long = "additional")]
-	pub(crate) additional: Option<String>,
+	pub additional: Option<String>,
 }
 
-// The sub-command's "main" function.
-pub(crate) async fn build_upgrade(prefs: UpgradeArgs) {
+/// The sub-command's "main" function.
+pub async fn build_upgrade(prefs: UpgradeArgs) -> Result<(), Error> {
 	// 0. Find out what to do.
 	let use_local = prefs.local;
-	let upgrade_details = parse_inputs(prefs);
+	let checksums = prefs.checksums.clone();
+	let upgrade_details = parse_inputs(prefs)?;
 
 	// 1. Download all the Wasm files needed from the release pages (unless using local files).
 	if use_local {
 		println!("\nUsing local WASM files from {}\n", upgrade_details.directory);
 	} else {
-		download_runtimes(&upgrade_details).await;
+		download_runtimes(&upgrade_details).await?;
+	}
+	if !upgrade_details.no_runtime_checks {
+		check_runtime_versions(&upgrade_details)?;
 	}
 
-	// 2. Construct the `authorize_upgrade` call on each chain.
-	let authorization_calls = generate_authorize_upgrade_calls(&upgrade_details);
+	// 2. Compare the Wasm files with the release's published checksums, if there are any.
+	if let Some(checksums) = load_checksums(checksums, &upgrade_details).await? {
+		verify_checksums(&upgrade_details, &checksums)?;
+	}
+
+	// 3. Construct the `authorize_upgrade` call on each chain.
+	let authorization_calls = generate_authorize_upgrade_calls(&upgrade_details)?;
 
-	// 3. Construct a `force_batch` call with everything.
-	let batch = construct_batch(&upgrade_details, authorization_calls).await;
+	// 4. Construct a `force_batch` call with everything.
+	let batch = construct_batch(&upgrade_details, authorization_calls.clone()).await?;
 
-	// 4. Write this call as a file that can then be passed to `submit_referendum`.
-	write_batch(&upgrade_details, batch);
+	// 5. Write a manifest of everything that went into the batch, for reviewers to check.
+	write_manifest(&build_manifest(&upgrade_details, &authorization_calls, &batch)?)?;
+
+	// 6. Write this call as a file that can then be passed to `submit_referendum`.
+	write_batch(&upgrade_details, batch)
 }
 
-fn chain_version(chain: Option<String>, default: Option<String>, only: bool) -> Option<String> {
+fn chain_version(
+	chain: Option<String>,
+	default: Option<String>,
+	only: bool,
+) -> Result<Option<String>, Error> {
 	// if the user specified a version for this particular chain, use it
 	if let Some(v) = chain {
-		Some(String::from(v.trim_start_matches('v')))
+		Ok(Some(String::from(v.trim_start_matches('v'))))
 	} else {
 		// if the user only wants to upgrade specific chains, and have not specified this one, then
 		// return None so that it will not be added to the batch of upgrades
 		if only {
-			None
+			Ok(None)
 		// otherwise, use the default version
+		} else if default.is_some() {
+			Ok(default)
 		} else {
-			assert!(default.is_some(), "no version specified");
-			default
+			Err(Error::InvalidInput(String::from(
+				"No version specified. Use `--relay-version` or a version for each chain.",
+			)))
 		}
 	}
 }
 
 // Parse the CLI inputs and return a typed struct with all the details needed.
-pub(crate) fn parse_inputs(prefs: UpgradeArgs) -> UpgradeDetails {
-	let mut networks = Vec::new();
+pub(crate) fn parse_inputs(prefs: UpgradeArgs) -> Result<UpgradeDetails, Error> {
 	let only = prefs.only;
 
-	if !only {
-		assert!(
-			prefs.relay_version.is_some(),
-			"relay-version must be specified unless using --only"
-		);
+	if !only && prefs.relay_version.is_none() {
+		return Err(Error::InvalidInput(String::from(
+			"`--relay-version` must be specified unless using `--only`.",
+		)));
 	}
-	let relay_version = chain_version(prefs.relay_version, None, only);
-	let asset_hub_version = chain_version(prefs.asset_hub, relay_version.clone(), only);
-	let bridge_hub_version = chain_version(prefs.bridge_hub, relay_version.clone(), only);
-	let people_version = chain_version(prefs.people, relay_version.clone(), only);
-	let coretime_version = chain_version(prefs.coretime, relay_version.clone(), only);
-	let encointer_version = chain_version(prefs.encointer, relay_version.clone(), only);
-	let collectives_version = chain_version(prefs.collectives, relay_version.clone(), only);
-
-	let relay = match prefs.network.to_ascii_lowercase().as_str() {
-		"polkadot" => {
-			if let Some(v) = relay_version.clone() {
-				networks.push(VersionedNetwork { network: Network::Polkadot, version: v });
-			}
-			if let Some(v) = asset_hub_version.clone() {
-				networks.push(VersionedNetwork { network: Network::PolkadotAssetHub, version: v });
-			}
-			if let Some(v) = collectives_version.clone() {
-				networks
-					.push(VersionedNetwork { network: Network::PolkadotCollectives, version: v });
-			}
-			if let Some(v) = bridge_hub_version.clone() {
-				networks.push(VersionedNetwork { network: Network::PolkadotBridgeHub, version: v });
-			}
-			if let Some(v) = people_version.clone() {
-				networks.push(VersionedNetwork { network: Network::PolkadotPeople, version: v });
-			}
-			if let Some(v) = coretime_version.clone() {
-				networks.push(VersionedNetwork { network: Network::PolkadotCoretime, version: v });
-			}
-			Network::Polkadot
-		},
-		"kusama" => {
-			if let Some(v) = relay_version.clone() {
-				networks.push(VersionedNetwork { network: Network::Kusama, version: v });
-			}
-			if let Some(v) = asset_hub_version.clone() {
-				networks.push(VersionedNetwork { network: Network::KusamaAssetHub, version: v });
-			}
-			if let Some(v) = encointer_version.clone() {
-				networks.push(VersionedNetwork { network: Network::KusamaEncointer, version: v });
-			}
-			if let Some(v) = bridge_hub_version.clone() {
-				networks.push(VersionedNetwork { network: Network::KusamaBridgeHub, version: v });
-			}
-			if let Some(v) = people_version.clone() {
-				networks.push(VersionedNetwork { network: Network::KusamaPeople, version: v });
-			}
-			if let Some(v) = coretime_version.clone() {
-				networks.push(VersionedNetwork { network: Network::KusamaCoretime, version: v });
-			}
-			Network::Kusama
-		},
-		_ => panic!("`network` must be `polkadot` or `kusama`"),
+	let relay_version = chain_version(prefs.relay_version, None, only)?;
+	let asset_hub_version = chain_version(prefs.asset_hub, relay_version.clone(), only)?;
+	let bridge_hub_version = chain_version(prefs.bridge_hub, relay_version.clone(), only)?;
+	let people_version = chain_version(prefs.people, relay_version.clone(), only)?;
+	let coretime_version = chain_version(prefs.coretime, relay_version.clone(), only)?;
+	let encointer_version = chain_version(prefs.encointer, relay_version.clone(), only)?;
+	let collectives_version = chain_version(prefs.collectives, relay_version.clone(), only)?;
+
+	// Each chain to upgrade, in the order they will be batched.
+	let (relay, chains) = match prefs.network.to_ascii_lowercase().as_str() {
+		"polkadot" => (
+			Network::Polkadot,
+			vec![
+				(Network::Polkadot, relay_version.clone()),
+				(Network::PolkadotAssetHub, asset_hub_version.clone()),
+				(Network::PolkadotCollectives, collectives_version.clone()),
+				(Network::PolkadotBridgeHub, bridge_hub_version.clone()),
+				(Network::PolkadotPeople, people_version.clone()),
+				(Network::PolkadotCoretime, coretime_version.clone()),
+			],
+		),
+		"kusama" => (
+			Network::Kusama,
+			vec![
+				(Network::Kusama, relay_version.clone()),
+				(Network::KusamaAssetHub, asset_hub_version.clone()),
+				(Network::KusamaEncointer, encointer_version.clone()),
+				(Network::KusamaBridgeHub, bridge_hub_version.clone()),
+				(Network::KusamaPeople, people_version.clone()),
+				(Network::KusamaCoretime, coretime_version.clone()),
+			],
+		),
+		_ =>
+			return Err(Error::UnsupportedNetwork(String::from(
+				"`network` must be `polkadot` or `kusama`.",
+			))),
 	};
 
-	let additional = match prefs.additional {
-		Some(c) => {
-			let additional_bytes = get_proposal_bytes(c.clone());
-			match relay {
-				// This match isn't as intuitive post-ahm, as these are AH calls.
-				Network::Polkadot =>
-					Some(CallInfo::from_bytes(&additional_bytes, Network::PolkadotAssetHub)),
-				Network::Kusama =>
-					Some(CallInfo::from_bytes(&additional_bytes, Network::KusamaAssetHub)),
-				_ => panic!("`network` must be `polkadot` or `kusama`"),
-			}
-		},
-		None => None,
-	};
-
-	let no_runtime_checks = prefs.no_runtime_checks;
-
 	// Get a version from one of the args. (This still feels dirty.)
 	let version = relay_version.clone().unwrap_or(asset_hub_version.unwrap_or(
 		bridge_hub_version.unwrap_or(encointer_version.unwrap_or(collectives_version.unwrap_or(
@@ -199,42 +209,131 @@ pub(crate) fn parse_inputs(prefs: UpgradeArgs) -> UpgradeDetails {
 
 	// Set up a directory to store information fetched/written during this program.
 	let directory = format!("./upgrade-{}-{}/", &prefs.network, &version);
-	let output_file = if let Some(user_filename) = prefs.filename {
-		format!("{directory}{user_filename}")
-	} else {
+	let output_file = prefs.filename.unwrap_or_else(|| {
 		let network = &prefs.network;
-		format!("{directory}{network}-{version}.call")
-	};
+		format!("{network}-{version}.call")
+	});
+
+	let mut builder = UpgradeDetails::builder(relay.clone(), directory)
+		.output_file(output_file)
+		.no_runtime_checks(prefs.no_runtime_checks)
+		.metadata(MetadataOverrides::load(&prefs.metadata)?);
+	for (network, version) in chains {
+		if let Some(v) = version {
+			builder = builder.runtime(network, v);
+		}
+	}
+	for wasm in &prefs.wasm {
+		let (chain, path) = wasm.split_once('=').ok_or_else(|| {
+			Error::InvalidInput(format!("`--wasm {wasm}` should be in the form `<chain>=<path>`."))
+		})?;
+		builder = builder.wasm(chain_from_name(&relay, chain)?, path);
+	}
+	if let Some(c) = prefs.additional {
+		let additional_bytes = get_proposal_bytes(c)?;
+		// This match isn't as intuitive post-ahm, as these are AH calls.
+		let network = match relay {
+			Network::Polkadot => Network::PolkadotAssetHub,
+			_ => Network::KusamaAssetHub,
+		};
+		builder = builder.additional(CallInfo::from_bytes(&additional_bytes, network));
+	}
+	let upgrade_details = builder.build()?;
 
-	make_version_directory(directory.as_str());
+	make_version_directory(upgrade_details.directory.as_str())?;
 
-	UpgradeDetails { relay, networks, directory, output_file, additional, no_runtime_checks }
+	Ok(upgrade_details)
+}
+
+// Resolve a chain name given to `--wasm`. Either the short name of a chain on `relay`, e.g.
+// `asset-hub`, or a full network name, e.g. `asset-hub-polkadot`.
+fn chain_from_name(relay: &Network, name: &str) -> Result<Network, Error> {
+	let short = name.to_ascii_lowercase().replace(['-', '_', ' '], "");
+	if short == "relay" {
+		return Ok(relay.clone());
+	}
+	let relay_name = format!("{relay:?}").to_ascii_lowercase();
+	Network::from_name(&format!("{short}{relay_name}"))
+		.or_else(|_| Network::from_name(name))
+		.ok()
+		.filter(|network| network.relay() == *relay)
+		.ok_or_else(|| Error::UnsupportedNetwork(format!("`{name}` is not a chain on {relay:?}.")))
 }
 
 // Create a directory into which to place runtime blobs and the final call data.
-fn make_version_directory(dir_name: &str) {
+fn make_version_directory(dir_name: &str) -> Result<(), Error> {
 	if !Path::new(dir_name).is_dir() {
-		fs::create_dir_all(dir_name).expect("it makes a dir");
+		fs::create_dir_all(dir_name)
+			.map_err(|source| Error::WriteFile { path: dir_name.to_string(), source })?;
 	}
+	Ok(())
 }
 
 // Convert a semver version (e.g. "1.2.3") to an integer runtime version (e.g. 1002003).
-fn semver_to_intver(semver: &str) -> String {
+fn semver_to_intver(semver: &str) -> Result<String, Error> {
 	// M.m.p => M_mmm_ppp
 	let points =
 		semver.bytes().enumerate().filter(|(_, b)| *b == b'.').map(|(i, _)| i).collect::<Vec<_>>();
 
-	assert!(points.len() == 2, "not semver");
+	if points.len() != 2 {
+		return Err(Error::InvalidInput(format!(
+			"`{semver}` is not a semver version. Versions should be in the form `1.2.3`."
+		)));
+	}
 
 	let major = &semver[..points[0]];
 	let minor = &semver[points[0] + 1..points[1]];
 	let patch = &semver[points[1] + 1..];
 
-	format!("{major}{minor:0>3}{patch:0>3}")
+	Ok(format!("{major}{minor:0>3}{patch:0>3}"))
+}
+
+/// The name of a runtime Wasm blob as published in a Fellowship release.
+pub fn runtime_file_name(chain: &VersionedNetwork) -> Result<String, Error> {
+	let chain_name = match chain.network {
+		Network::Kusama => "kusama",
+		Network::Polkadot => "polkadot",
+		Network::KusamaAssetHub => "asset-hub-kusama",
+		Network::KusamaBridgeHub => "bridge-hub-kusama",
+		Network::KusamaPeople => "people-kusama",
+		Network::KusamaCoretime => "coretime-kusama",
+		Network::KusamaEncointer => "encointer-kusama",
+		Network::PolkadotAssetHub => "asset-hub-polkadot",
+		Network::PolkadotCollectives => "collectives-polkadot",
+		Network::PolkadotBridgeHub => "bridge-hub-polkadot",
+		Network::PolkadotPeople => "people-polkadot",
+		Network::PolkadotCoretime => "coretime-polkadot",
+	};
+	let runtime_version = semver_to_intver(&chain.version)?;
+	Ok(format!("{chain_name}_runtime-v{runtime_version}.compact.compressed.wasm"))
+}
+
+/// The path of the Wasm blob to authorize for `chain`: the file given with `--wasm`, if any, or the
+/// release's file name in the upgrade directory.
+pub fn runtime_path(
+	upgrade_details: &UpgradeDetails,
+	chain: &VersionedNetwork,
+) -> Result<String, Error> {
+	Ok(match upgrade_details.wasm_paths.iter().find(|(network, _)| *network == chain.network) {
+		Some((_, path)) => path.clone(),
+		None => format!("{}{}", upgrade_details.directory, runtime_file_name(chain)?),
+	})
+}
+
+// The file name at the end of `path`.
+fn file_name_of(path: &str) -> String {
+	Path::new(path).file_name().map_or(path.to_string(), |f| f.to_string_lossy().into_owned())
+}
+
+/// The URL of a runtime Wasm blob on the Fellowship release page.
+pub fn runtime_download_url(chain: &VersionedNetwork) -> Result<String, Error> {
+	let version = &chain.version;
+	let fname = runtime_file_name(chain)?;
+	Ok(format!("https://github.com/polkadot-fellows/runtimes/releases/download/v{version}/{fname}"))
 }
 
-// Fetch all the runtime Wasm blobs from a Fellowship release.
-async fn download_runtimes(upgrade_details: &UpgradeDetails) {
+/// Fetch all the runtime Wasm blobs from a Fellowship release.
+pub async fn download_runtimes(upgrade_details: &UpgradeDetails) -> Result<(), Error> {
 	// Relay Form
 	// https://github.com/polkadot-fellows/runtimes/releases/download/v1.0.0/polkadot_runtime-v1000000.compact.compressed.wasm
 	//
@@ -243,347 +342,494 @@ async fn download_runtimes(upgrade_details: &UpgradeDetails) {
 
 	println!("\nDownloading runtimes.\n");
 	for chain in &upgrade_details.networks {
-		let chain_name = match chain.network {
-			Network::Kusama => "kusama",
-			Network::Polkadot => "polkadot",
-			Network::KusamaAssetHub => "asset-hub-kusama",
-			Network::KusamaBridgeHub => "bridge-hub-kusama",
-			Network::KusamaPeople => "people-kusama",
-			Network::KusamaCoretime => "coretime-kusama",
-			Network::KusamaEncointer => "encointer-kusama",
-			Network::PolkadotAssetHub => "asset-hub-polkadot",
-			Network::PolkadotCollectives => "collectives-polkadot",
-			Network::PolkadotBridgeHub => "bridge-hub-polkadot",
-			Network::PolkadotPeople => "people-polkadot",
-			Network::PolkadotCoretime => "coretime-polkadot",
-		};
-		let runtime_version = semver_to_intver(&chain.version);
-		let fname = format!("{chain_name}_runtime-v{runtime_version}.compact.compressed.wasm");
-
-		let version = &chain.version;
-		let download_url = format!(
-			"https://github.com/polkadot-fellows/runtimes/releases/download/v{version}/{fname}"
-		);
+		if upgrade_details.wasm_paths.iter().any(|(network, _)| *network == chain.network) {
+			println!("Using {} for {:?}", runtime_path(upgrade_details, chain)?, chain.network);
+			continue;
+		}
+		let fname = runtime_file_name(chain)?;
+		let download_url = runtime_download_url(chain)?;
 
-		let download_url = download_url.as_str();
 		let directory = &upgrade_details.directory;
 		let path_name = format!("{directory}{fname}");
 		println!("Downloading... {fname}");
-		let response = reqwest::get(download_url).await.expect("we need files to work");
+		let download_error = |reason: String| Error::Download { url: download_url.clone(), reason };
+		let response =
+			reqwest::get(&download_url).await.map_err(|e| download_error(e.to_string()))?;
 
 		let status = response.status();
-		assert!(status.is_success(), "Failed to download {}: HTTP {}", fname, status);
+		if !status.is_success() {
+			return Err(download_error(format!("HTTP {status}")));
+		}
 
-		let runtime = response.bytes().await.expect("need bytes");
+		let runtime = response.bytes().await.map_err(|e| download_error(e.to_string()))?;
 
 		if !upgrade_details.no_runtime_checks {
-			// Substrate zstd-compressed blob magic (`sp_maybe_compressed_blob::ZSTD_PREFIX`).
-			const ZSTD_PREFIX: [u8; 8] = [82, 188, 83, 118, 70, 219, 142, 5];
-			assert!(
-				runtime.starts_with(&ZSTD_PREFIX),
-				"Downloaded {} is not a zstd-compressed Substrate runtime blob. \
-				 Pass --no-runtime-checks to skip.",
-				fname,
-			);
+			if !runtime.starts_with(&sp_maybe_compressed_blob::ZSTD_PREFIX) {
+				return Err(Error::RuntimeCheck {
+					file: fname,
+					reason: String::from("is not a zstd-compressed Substrate runtime blob"),
+				});
+			}
 
 			let size = runtime.len();
-			assert!(
-				(100 * 1024..=10 * 1024 * 1024).contains(&size),
-				"Downloaded {} is {} bytes; expected between 100 KiB and 10 MiB. \
-				 Pass --no-runtime-checks to skip.",
-				fname,
-				size,
-			);
+			if !(100 * 1024..=10 * 1024 * 1024).contains(&size) {
+				return Err(Error::RuntimeCheck {
+					file: fname,
+					reason: format!("is {size} bytes; expected between 100 KiB and 10 MiB"),
+				});
+			}
 		}
 
 		// todo: we could actually just hash the file, mutate UpgradeDetails, and not write it.
 		// saving it may be more convenient anyway though, since someone needs to upload it after
 		// the referendum enacts.
-		fs::write(path_name, runtime).expect("we can write");
+		write_file(&path_name, runtime)?;
+	}
+	Ok(())
+}
+
+/// Check the `RuntimeVersion` of every runtime to authorize, whether downloaded, local, or given
+/// with `--wasm`.
+pub fn check_runtime_versions(upgrade_details: &UpgradeDetails) -> Result<(), Error> {
+	for chain in &upgrade_details.networks {
+		let path = runtime_path(upgrade_details, chain)?;
+		let runtime = read_file(&path)?;
+		check_runtime_version(chain, &file_name_of(&path), &runtime)?;
+	}
+	Ok(())
+}
+
+/// Check that the `RuntimeVersion` embedded in `runtime` matches the network and version of
+/// `chain`, to catch mislabelled or swapped release assets before authorizing them.
+pub fn check_runtime_version(
+	chain: &VersionedNetwork,
+	fname: &str,
+	runtime: &[u8],
+) -> Result<(), Error> {
+	let check_error = |reason: String| Error::RuntimeCheck { file: String::from(fname), reason };
+	let version = wasm::runtime_version(runtime).map_err(check_error)?;
+
+	let expected_name = chain.network.spec_name();
+	if version.spec_name != expected_name {
+		return Err(check_error(format!(
+			"has spec_name `{}`; expected `{expected_name}` for {:?}",
+			version.spec_name, chain.network
+		)));
+	}
+
+	let expected_version = semver_to_intver(&chain.version)?;
+	if version.spec_version.to_string() != expected_version {
+		return Err(check_error(format!(
+			"has spec_version {}; expected {expected_version} for v{}",
+			version.spec_version, chain.version
+		)));
+	}
+
+	println!(
+		"Verified {fname}: {} spec_version {}, transaction_version {}",
+		version.spec_name, version.spec_version, version.transaction_version
+	);
+	Ok(())
+}
+
+/// Digests published for a release's runtimes, keyed by file name.
+#[derive(Debug, Default)]
+pub struct Checksums {
+	pub sha256: HashMap<String, [u8; 32]>,
+	pub blake2_256: HashMap<String, [u8; 32]>,
+}
+
+impl Checksums {
+	/// Parse a checksum file. Each line is `<hex digest>  <file name>`, as written by `sha256sum`.
+	/// Digests prefixed with `blake2_256:` are Blake2-256 and all others SHA-256 (an optional
+	/// `sha256:` prefix is accepted). Blank lines and lines starting with `#` are ignored.
+	pub fn parse(contents: &str) -> Result<Checksums, Error> {
+		let mut checksums = Checksums::default();
+		for (number, line) in contents.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+			let malformed = |reason: &str| {
+				Error::InvalidInput(format!(
+					"Line {} of the checksum file is malformed ({reason}): {line}",
+					number + 1
+				))
+			};
+
+			let (digest, name) =
+				line.split_once(char::is_whitespace).ok_or_else(|| malformed("no file name"))?;
+			// `sha256sum` marks files read in binary mode with a `*`. Only the file name matters.
+			let name = name.trim().trim_start_matches('*');
+			let name = name.rsplit('/').next().unwrap_or(name).to_string();
+
+			let (map, digest) = if let Some(digest) = digest.strip_prefix("blake2_256:") {
+				(&mut checksums.blake2_256, digest)
+			} else {
+				(&mut checksums.sha256, digest.trim_start_matches("sha256:"))
+			};
+			let digest = hex::decode(digest.trim_start_matches("0x"))
+				.map_err(|_| malformed("digest is not hex"))?
+				.try_into()
+				.map_err(|_| malformed("digest is not 32 bytes"))?;
+			map.insert(name, digest);
+		}
+		Ok(checksums)
+	}
+}
+
+// Fetch or read the checksum file from `source`, which may be a URL or a path. Without a source,
+// fall back to `DEFAULT_CHECKSUM_FILE` in the upgrade directory.
+async fn load_checksums(
+	source: Option<String>,
+	upgrade_details: &UpgradeDetails,
+) -> Result<Option<Checksums>, Error> {
+	let source = match source {
+		Some(source) => source,
+		None => {
+			let default = format!("{}{DEFAULT_CHECKSUM_FILE}", upgrade_details.directory);
+			if !Path::new(&default).is_file() {
+				println!("\nNo checksum file provided. Skipping checksum verification.");
+				return Ok(None);
+			}
+			default
+		},
+	};
+
+	println!("\nVerifying runtimes against checksums in {source}\n");
+	let contents = if source.starts_with("http://") || source.starts_with("https://") {
+		let download_error = |reason: String| Error::Download { url: source.clone(), reason };
+		let response = reqwest::get(&source).await.map_err(|e| download_error(e.to_string()))?;
+		let status = response.status();
+		if !status.is_success() {
+			return Err(download_error(format!("HTTP {status}")));
+		}
+		response.text().await.map_err(|e| download_error(e.to_string()))?
+	} else {
+		String::from_utf8(read_file(&source)?).map_err(|_| {
+			Error::InvalidInput(format!("The checksum file {source} is not valid UTF-8."))
+		})?
+	};
+	Checksums::parse(&contents).map(Some)
+}
+
+/// Check each runtime in the upgrade directory against `checksums`. Every runtime must have at
+/// least one digest, and every digest given for it must match.
+pub fn verify_checksums(
+	upgrade_details: &UpgradeDetails,
+	checksums: &Checksums,
+) -> Result<(), Error> {
+	for chain in &upgrade_details.networks {
+		let path = runtime_path(upgrade_details, chain)?;
+		let runtime = read_file(&path)?;
+		verify_checksum(&file_name_of(&path), &runtime, checksums)?;
 	}
+	Ok(())
 }
 
-// Generate the `authorize_upgrade` calls that will need to execute on each parachain.
-fn generate_authorize_upgrade_calls(upgrade_details: &UpgradeDetails) -> Vec<CallInfo> {
+/// Check a single runtime blob, called `fname` in the checksum file, against `checksums`.
+pub fn verify_checksum(fname: &str, runtime: &[u8], checksums: &Checksums) -> Result<(), Error> {
+	let checksum_error = |reason: String| Error::Checksum { file: String::from(fname), reason };
+	let expected = [
+		("sha256", checksums.sha256.get(fname), <[u8; 32]>::from(Sha256::digest(runtime))),
+		("blake2_256", checksums.blake2_256.get(fname), blake2_256(runtime)),
+	];
+	if expected.iter().all(|(_, digest, _)| digest.is_none()) {
+		return Err(checksum_error(String::from("has no entry in the checksum file")));
+	}
+
+	let mut verified = Vec::new();
+	for (algorithm, digest, actual) in expected {
+		let Some(digest) = digest else { continue };
+		if *digest != actual {
+			return Err(checksum_error(format!(
+				"has {algorithm} 0x{}, but the checksum file expects 0x{}",
+				hex::encode(actual),
+				hex::encode(digest)
+			)));
+		}
+		verified.push(algorithm);
+	}
+	println!("Checksum OK: {fname} ({})", verified.join(", "));
+	Ok(())
+}
+
+/// Generate the `authorize_upgrade` calls that will need to execute on each parachain.
+pub fn generate_authorize_upgrade_calls(
+	upgrade_details: &UpgradeDetails,
+) -> Result<Vec<CallInfo>, Error> {
 	println!("\nGenerating parachain authorization calls. The runtime hashes are logged if you would like to verify them with srtool.\n");
 	let mut authorization_calls = Vec::new();
 	for chain in &upgrade_details.networks {
-		let runtime_version = semver_to_intver(&chain.version);
 		match chain.network {
 			Network::Kusama => {
 				use kusama_relay::runtime_types::frame_system::pallet::Call as SystemCall;
-				let path = format!(
-					"{}kusama_runtime-v{}.compact.compressed.wasm",
-					upgrade_details.directory, runtime_version
-				);
-				let runtime = fs::read(path).expect("Should give a valid file path");
+				let path = runtime_path(upgrade_details, chain)?;
+				let runtime = read_file(&path)?;
 				let runtime_hash = blake2_256(&runtime);
 				println!("Kusama Relay Chain Runtime Hash: 0x{}", hex::encode(runtime_hash));
 
-				let call = CallInfo::from_runtime_call(NetworkRuntimeCall::Kusama(
+				let call = upgrade_details.metadata.call_info(NetworkRuntimeCall::Kusama(
 					KusamaRuntimeCall::System(SystemCall::authorize_upgrade {
 						code_hash: H256(runtime_hash),
 					}),
-				));
+				))?;
 				authorization_calls.push(call);
 			},
 			Network::KusamaAssetHub => {
 				use kusama_asset_hub::runtime_types::frame_system::pallet::Call;
-				let path = format!(
-					"{}asset-hub-kusama_runtime-v{}.compact.compressed.wasm",
-					upgrade_details.directory, runtime_version
-				);
-				let runtime = fs::read(path).expect("Should give a valid file path");
+				let path = runtime_path(upgrade_details, chain)?;
+				let runtime = read_file(&path)?;
 				let runtime_hash = blake2_256(&runtime);
 				println!("Kusama Asset Hub Runtime Hash:   0x{}", hex::encode(runtime_hash));
 
-				let call = CallInfo::from_runtime_call(NetworkRuntimeCall::KusamaAssetHub(
-					KusamaAssetHubRuntimeCall::System(Call::authorize_upgrade {
-						code_hash: H256(runtime_hash),
-					}),
-				));
+				let call = upgrade_details.metadata.call_info(
+					NetworkRuntimeCall::KusamaAssetHub(KusamaAssetHubRuntimeCall::System(
+						Call::authorize_upgrade { code_hash: H256(runtime_hash) },
+					)),
+				)?;
 				authorization_calls.push(call);
 			},
 			Network::KusamaBridgeHub => {
 				use kusama_bridge_hub::runtime_types::frame_system::pallet::Call;
-				let path = format!(
-					"{}bridge-hub-kusama_runtime-v{}.compact.compressed.wasm",
-					upgrade_details.directory, runtime_version
-				);
-				let runtime = fs::read(path).expect("Should give a valid file path");
+				let path = runtime_path(upgrade_details, chain)?;
+				let runtime = read_file(&path)?;
 				let runtime_hash = blake2_256(&runtime);
 				println!("Kusama Bridge Hub Runtime Hash:  0x{}", hex::encode(runtime_hash));
 
-				let call = CallInfo::from_runtime_call(NetworkRuntimeCall::KusamaBridgeHub(
-					KusamaBridgeHubRuntimeCall::System(Call::authorize_upgrade {
-						code_hash: H256(runtime_hash),
-					}),
-				));
+				let call = upgrade_details.metadata.call_info(
+					NetworkRuntimeCall::KusamaBridgeHub(KusamaBridgeHubRuntimeCall::System(
+						Call::authorize_upgrade { code_hash: H256(runtime_hash) },
+					)),
+				)?;
 				authorization_calls.push(call);
 			},
 			Network::KusamaPeople => {
 				use kusama_people::runtime_types::frame_system::pallet::Call;
-				let path = format!(
-					"{}people-kusama_runtime-v{}.compact.compressed.wasm",
-					upgrade_details.directory, runtime_version
-				);
-				let runtime = fs::read(path).expect("Should give a valid file path");
+				let path = runtime_path(upgrade_details, chain)?;
+				let runtime = read_file(&path)?;
 				let runtime_hash = blake2_256(&runtime);
 				println!("Kusama People Runtime Hash:      0x{}", hex::encode(runtime_hash));
 
-				let call = CallInfo::from_runtime_call(NetworkRuntimeCall::KusamaPeople(
+				let call = upgrade_details.metadata.call_info(NetworkRuntimeCall::KusamaPeople(
 					KusamaPeopleRuntimeCall::System(Call::authorize_upgrade {
 						code_hash: H256(runtime_hash),
 					}),
-				));
+				))?;
 				authorization_calls.push(call);
 			},
 			Network::KusamaCoretime => {
 				use kusama_coretime::runtime_types::frame_system::pallet::Call;
-				let path = format!(
-					"{}coretime-kusama_runtime-v{}.compact.compressed.wasm",
-					upgrade_details.directory, runtime_version
-				);
-				let runtime = fs::read(path).expect("Should give a valid file path");
+				let path = runtime_path(upgrade_details, chain)?;
+				let runtime = read_file(&path)?;
 				let runtime_hash = blake2_256(&runtime);
 				println!("Kusama Coretime Runtime Hash:    0x{}", hex::encode(runtime_hash));
 
-				let call = CallInfo::from_runtime_call(NetworkRuntimeCall::KusamaCoretime(
-					KusamaCoretimeRuntimeCall::System(Call::authorize_upgrade {
-						code_hash: H256(runtime_hash),
-					}),
-				));
+				let call = upgrade_details.metadata.call_info(
+					NetworkRuntimeCall::KusamaCoretime(KusamaCoretimeRuntimeCall::System(
+						Call::authorize_upgrade { code_hash: H256(runtime_hash) },
+					)),
+				)?;
 				authorization_calls.push(call);
 			},
 			Network::KusamaEncointer => {
 				use kusama_encointer::runtime_types::frame_system::pallet::Call;
-				let path = format!(
-					"{}encointer-kusama_runtime-v{}.compact.compressed.wasm",
-					upgrade_details.directory, runtime_version
-				);
-				let runtime = fs::read(path).expect("Should give a valid file path");
+				let path = runtime_path(upgrade_details, chain)?;
+				let runtime = read_file(&path)?;
 				let runtime_hash = blake2_256(&runtime);
 				println!("Kusama Encointer Runtime Hash:   0x{}", hex::encode(runtime_hash));
 
-				let call = CallInfo::from_runtime_call(NetworkRuntimeCall::KusamaEncointer(
-					KusamaEncointerRuntimeCall::System(Call::authorize_upgrade {
-						code_hash: H256(runtime_hash),
-					}),
-				));
+				let call = upgrade_details.metadata.call_info(
+					NetworkRuntimeCall::KusamaEncointer(KusamaEncointerRuntimeCall::System(
+						Call::authorize_upgrade { code_hash: H256(runtime_hash) },
+					)),
+				)?;
 				authorization_calls.push(call);
 			},
 			Network::Polkadot => {
 				use polkadot_relay::runtime_types::frame_system::pallet::Call;
-				let path = format!(
-					"{}polkadot_runtime-v{}.compact.compressed.wasm",
-					upgrade_details.directory, runtime_version
-				);
-				let runtime = fs::read(path).expect("Should give a valid file path");
+				let path = runtime_path(upgrade_details, chain)?;
+				let runtime = read_file(&path)?;
 				let runtime_hash = blake2_256(&runtime);
 				println!("Polkadot Relay Chain Runtime Hash: 0x{}", hex::encode(runtime_hash));
 
-				let call = CallInfo::from_runtime_call(NetworkRuntimeCall::Polkadot(
+				let call = upgrade_details.metadata.call_info(NetworkRuntimeCall::Polkadot(
 					PolkadotRuntimeCall::System(Call::authorize_upgrade {
 						code_hash: H256(runtime_hash),
 					}),
-				));
+				))?;
 				authorization_calls.push(call);
 			},
 			Network::PolkadotAssetHub => {
 				use polkadot_asset_hub::runtime_types::frame_system::pallet::Call;
-				let path = format!(
-					"{}asset-hub-polkadot_runtime-v{}.compact.compressed.wasm",
-					upgrade_details.directory, runtime_version
-				);
-				let runtime = fs::read(path).expect("Should give a valid file path");
+				let path = runtime_path(upgrade_details, chain)?;
+				let runtime = read_file(&path)?;
 				let runtime_hash = blake2_256(&runtime);
 				println!("Polkadot Asset Hub Runtime Hash:   0x{}", hex::encode(runtime_hash));
 
-				let call = CallInfo::from_runtime_call(NetworkRuntimeCall::PolkadotAssetHub(
-					PolkadotAssetHubRuntimeCall::System(Call::authorize_upgrade {
-						code_hash: H256(runtime_hash),
-					}),
-				));
+				let call = upgrade_details.metadata.call_info(
+					NetworkRuntimeCall::PolkadotAssetHub(PolkadotAssetHubRuntimeCall::System(
+						Call::authorize_upgrade { code_hash: H256(runtime_hash) },
+					)),
+				)?;
 				authorization_calls.push(call);
 			},
 			Network::PolkadotCollectives => {
 				use polkadot_collectives::runtime_types::frame_system::pallet::Call;
-				let path = format!(
-					"{}collectives-polkadot_runtime-v{}.compact.compressed.wasm",
-					upgrade_details.directory, runtime_version
-				);
-				let runtime = fs::read(path).expect("Should give a valid file path");
+				let path = runtime_path(upgrade_details, chain)?;
+				let runtime = read_file(&path)?;
 				let runtime_hash = blake2_256(&runtime);
 				println!("Polkadot Collectives Runtime Hash: 0x{}", hex::encode(runtime_hash));
 
-				let call = CallInfo::from_runtime_call(NetworkRuntimeCall::PolkadotCollectives(
-					CollectivesRuntimeCall::System(Call::authorize_upgrade {
-						code_hash: H256(runtime_hash),
-					}),
-				));
+				let call = upgrade_details.metadata.call_info(
+					NetworkRuntimeCall::PolkadotCollectives(CollectivesRuntimeCall::System(
+						Call::authorize_upgrade { code_hash: H256(runtime_hash) },
+					)),
+				)?;
 				authorization_calls.push(call);
 			},
 			Network::PolkadotBridgeHub => {
 				use polkadot_bridge_hub::runtime_types::frame_system::pallet::Call;
-				let path = format!(
-					"{}bridge-hub-polkadot_runtime-v{}.compact.compressed.wasm",
-					upgrade_details.directory, runtime_version
-				);
-				let runtime = fs::read(path).expect("Should give a valid file path");
+				let path = runtime_path(upgrade_details, chain)?;
+				let runtime = read_file(&path)?;
 				let runtime_hash = blake2_256(&runtime);
 				println!("Polkadot Bridge Hub Runtime Hash:  0x{}", hex::encode(runtime_hash));
 
-				let call = CallInfo::from_runtime_call(NetworkRuntimeCall::PolkadotBridgeHub(
-					PolkadotBridgeHubRuntimeCall::System(Call::authorize_upgrade {
-						code_hash: H256(runtime_hash),
-					}),
-				));
+				let call = upgrade_details.metadata.call_info(
+					NetworkRuntimeCall::PolkadotBridgeHub(PolkadotBridgeHubRuntimeCall::System(
+						Call::authorize_upgrade { code_hash: H256(runtime_hash) },
+					)),
+				)?;
 				authorization_calls.push(call);
 			},
 			Network::PolkadotPeople => {
 				use polkadot_people::runtime_types::frame_system::pallet::Call;
-				let path = format!(
-					"{}people-polkadot_runtime-v{}.compact.compressed.wasm",
-					upgrade_details.directory, runtime_version
-				);
-				let runtime = fs::read(path).expect("Should give a valid file path");
+				let path = runtime_path(upgrade_details, chain)?;
+				let runtime = read_file(&path)?;
 				let runtime_hash = blake2_256(&runtime);
 				println!("Polkadot People Runtime Hash:      0x{}", hex::encode(runtime_hash));
 
-				let call = CallInfo::from_runtime_call(NetworkRuntimeCall::PolkadotPeople(
-					PolkadotPeopleRuntimeCall::System(Call::authorize_upgrade {
-						code_hash: H256(runtime_hash),
-					}),
-				));
+				let call = upgrade_details.metadata.call_info(
+					NetworkRuntimeCall::PolkadotPeople(PolkadotPeopleRuntimeCall::System(
+						Call::authorize_upgrade { code_hash: H256(runtime_hash) },
+					)),
+				)?;
 				authorization_calls.push(call);
 			},
 			Network::PolkadotCoretime => {
 				use polkadot_coretime::runtime_types::frame_system::pallet::Call;
-				let path = format!(
-					"{}coretime-polkadot_runtime-v{}.compact.compressed.wasm",
-					upgrade_details.directory, runtime_version
-				);
-				let runtime = fs::read(path).expect("Should give a valid file path");
+				let path = runtime_path(upgrade_details, chain)?;
+				let runtime = read_file(&path)?;
 				let runtime_hash = blake2_256(&runtime);
 				println!("Polkadot Coretime Runtime Hash:    0x{}", hex::encode(runtime_hash));
 
-				let call = CallInfo::from_runtime_call(NetworkRuntimeCall::PolkadotCoretime(
-					PolkadotCoretimeRuntimeCall::System(Call::authorize_upgrade {
-						code_hash: H256(runtime_hash),
-					}),
-				));
+				let call = upgrade_details.metadata.call_info(
+					NetworkRuntimeCall::PolkadotCoretime(PolkadotCoretimeRuntimeCall::System(
+						Call::authorize_upgrade { code_hash: H256(runtime_hash) },
+					)),
+				)?;
 				authorization_calls.push(call);
 			},
 		};
 	}
-	authorization_calls
+	Ok(authorization_calls)
 }
 
-// Take the parachain authorization calls and the Relay Chain call, and batch them into one call
-// that can be executed on the Relay Chain. The call returned here is the proposal to put to
-// referendum.
-async fn construct_batch(upgrade_details: &UpgradeDetails, calls: Vec<CallInfo>) -> CallInfo {
+/// Take the parachain authorization calls and the Relay Chain call, and batch them into one call
+/// that can be executed on the Relay Chain. The call returned here is the proposal to put to
+/// referendum.
+pub async fn construct_batch(
+	upgrade_details: &UpgradeDetails,
+	calls: Vec<CallInfo>,
+) -> Result<CallInfo, Error> {
 	println!("\nBatching calls.");
+	let (additional, metadata) = (upgrade_details.additional.clone(), &upgrade_details.metadata);
 	match upgrade_details.relay {
-		Network::Kusama => construct_kusama_batch(calls, upgrade_details.additional.clone()).await,
-		Network::Polkadot =>
-			construct_polkadot_batch(calls, upgrade_details.additional.clone()).await,
-		_ => panic!("Not a Relay Chain"),
+		Network::Kusama => construct_kusama_batch(calls, additional, metadata).await,
+		Network::Polkadot => construct_polkadot_batch(calls, additional, metadata).await,
+		_ => Err(not_a_relay_chain(&upgrade_details.relay)),
 	}
 }
 
 // Construct the batch needed on Kusama.
-async fn construct_kusama_batch(calls: Vec<CallInfo>, additional: Option<CallInfo>) -> CallInfo {
+async fn construct_kusama_batch(
+	calls: Vec<CallInfo>,
+	additional: Option<CallInfo>,
+	metadata: &MetadataOverrides,
+) -> Result<CallInfo, Error> {
 	use kusama_asset_hub::runtime_types::pallet_utility::pallet::Call as UtilityCall;
 
 	let mut batch_calls = Vec::new();
 	for auth in calls {
-		dbg!(&auth.network);
 		if matches!(auth.network, Network::KusamaAssetHub) {
-			batch_calls.push(auth.get_kusama_asset_hub_call().expect("We just constructed this"));
+			batch_calls.push(bundled_call(metadata, &auth, CallInfo::get_kusama_asset_hub_call)?);
 		} else {
 			let send_auth = send_as_superuser_kusama(&auth).await;
 			batch_calls.push(send_auth);
 		}
 	}
 	if let Some(a) = additional {
-		batch_calls.push(a.get_kusama_asset_hub_call().expect("kusama call"))
+		batch_calls.push(bundled_call(metadata, &a, CallInfo::get_kusama_asset_hub_call)?)
 	}
-	match &batch_calls.len() {
-		0 => panic!("no calls"),
-		1 =>
-			CallInfo::from_runtime_call(NetworkRuntimeCall::KusamaAssetHub(batch_calls[0].clone())),
-		_ => CallInfo::from_runtime_call(NetworkRuntimeCall::KusamaAssetHub(
+	Ok(match &batch_calls.len() {
+		0 => return Err(nothing_to_upgrade()),
+		1 => metadata.call_info(NetworkRuntimeCall::KusamaAssetHub(batch_calls[0].clone()))?,
+		_ => metadata.call_info(NetworkRuntimeCall::KusamaAssetHub(
 			KusamaAssetHubRuntimeCall::Utility(UtilityCall::force_batch { calls: batch_calls }),
-		)),
-	}
+		))?,
+	})
 }
 
 // Construct the batch needed on Polkadot.
-async fn construct_polkadot_batch(calls: Vec<CallInfo>, additional: Option<CallInfo>) -> CallInfo {
+async fn construct_polkadot_batch(
+	calls: Vec<CallInfo>,
+	additional: Option<CallInfo>,
+	metadata: &MetadataOverrides,
+) -> Result<CallInfo, Error> {
 	use polkadot_asset_hub::runtime_types::pallet_utility::pallet::Call as UtilityCall;
 
 	let mut batch_calls = Vec::new();
 	for auth in calls {
 		if matches!(auth.network, Network::PolkadotAssetHub) {
-			batch_calls.push(auth.get_polkadot_asset_hub_call().expect("We just constructed this"));
+			batch_calls.push(bundled_call(metadata, &auth, CallInfo::get_polkadot_asset_hub_call)?);
 		} else {
 			let send_auth = send_as_superuser_polkadot(&auth).await;
 			batch_calls.push(send_auth);
 		}
 	}
 	if let Some(a) = additional {
-		batch_calls.push(a.get_polkadot_asset_hub_call().expect("polkadot call"))
+		batch_calls.push(bundled_call(metadata, &a, CallInfo::get_polkadot_asset_hub_call)?)
 	}
-	match &batch_calls.len() {
-		0 => panic!("no calls"),
-		1 => CallInfo::from_runtime_call(NetworkRuntimeCall::PolkadotAssetHub(
-			batch_calls[0].clone(),
-		)),
-		_ => CallInfo::from_runtime_call(NetworkRuntimeCall::PolkadotAssetHub(
+	Ok(match &batch_calls.len() {
+		0 => return Err(nothing_to_upgrade()),
+		1 => metadata.call_info(NetworkRuntimeCall::PolkadotAssetHub(batch_calls[0].clone()))?,
+		_ => metadata.call_info(NetworkRuntimeCall::PolkadotAssetHub(
 			PolkadotAssetHubRuntimeCall::Utility(UtilityCall::force_batch { calls: batch_calls }),
-		)),
-	}
+		))?,
+	})
+}
+
+// Map `call` back to the bundled metadata and decode it with `decode`. A call that no longer
+// decodes was transcoded by a `--metadata` that does not match the bundled types.
+fn bundled_call<T>(
+	metadata: &MetadataOverrides,
+	call: &CallInfo,
+	decode: impl FnOnce(&CallInfo) -> Result<T, Error>,
+) -> Result<T, Error> {
+	decode(&metadata.to_bundled(call)?).map_err(|error| Error::Metadata {
+		network: call.network.clone(),
+		reason: error.to_string(),
+	})
+}
+
+// The error for an upgrade of `network`, which is not a Relay Chain.
+fn not_a_relay_chain(network: &Network) -> Error {
+	Error::UnsupportedNetwork(format!("{network:?} is not a Relay Chain."))
+}
+
+// The error for an upgrade with no calls to batch.
+fn nothing_to_upgrade() -> Error {
+	Error::InvalidInput(String::from("Nothing to upgrade. Specify at least one runtime version."))
 }
 
 // Take a call, which includes its intended destination, and wrap it in XCM instructions to `send`
@@ -658,21 +904,120 @@ async fn send_as_superuser_polkadot(auth: &CallInfo) -> PolkadotAssetHubRuntimeC
 	})
 }
 
+/// A record of everything that went into an upgrade batch. Written next to the call data so that
+/// release reviewers can diff and archive it.
+#[derive(Debug, serde::Serialize)]
+pub struct UpgradeManifest {
+	/// Where the manifest is written. Not part of the document itself.
+	#[serde(skip)]
+	pub path: String,
+	/// The Relay Network for this upgrade, Polkadot or Kusama.
+	pub relay: Network,
+	/// Each runtime being upgraded, in batch order.
+	pub runtimes: Vec<RuntimeManifest>,
+	/// The additional call included in the batch, if any.
+	pub additional: Option<String>,
+	/// The final batch call.
+	pub batch: BatchManifest,
+}
+
+/// The details of a single runtime in an `UpgradeManifest`.
+#[derive(Debug, serde::Serialize)]
+pub struct RuntimeManifest {
+	pub network: Network,
+	pub version: String,
+	pub file_name: String,
+	/// The blake2_256 hash of the Wasm blob, as authorized on chain.
+	pub code_hash: String,
+	pub file_size: u64,
+	/// Where the blob was published, unless it was given with `--wasm`.
+	pub download_url: Option<String>,
+	/// The encoded `authorize_upgrade` call, as it executes on `network`.
+	pub authorize_upgrade: String,
+}
+
+/// The details of the final batch call in an `UpgradeManifest`.
+#[derive(Debug, serde::Serialize)]
+pub struct BatchManifest {
+	pub call_file: String,
+	pub call_hash: String,
+	pub length: u32,
+}
+
+/// Collect the manifest for an upgrade. `authorization_calls` must be in the same order as
+/// `upgrade_details.networks`, as returned by `generate_authorize_upgrade_calls`.
+pub fn build_manifest(
+	upgrade_details: &UpgradeDetails,
+	authorization_calls: &[CallInfo],
+	batch: &CallInfo,
+) -> Result<UpgradeManifest, Error> {
+	let runtimes = upgrade_details
+		.networks
+		.iter()
+		.zip(authorization_calls)
+		.map(|(chain, auth)| {
+			let path = runtime_path(upgrade_details, chain)?;
+			let runtime = read_file(&path)?;
+			// Blobs given with `--wasm` are not from the release.
+			let from_release =
+				!upgrade_details.wasm_paths.iter().any(|(network, _)| *network == chain.network);
+			Ok(RuntimeManifest {
+				network: chain.network.clone(),
+				version: chain.version.clone(),
+				code_hash: format!("0x{}", hex::encode(blake2_256(&runtime))),
+				file_size: runtime.len() as u64,
+				download_url: from_release.then(|| runtime_download_url(chain)).transpose()?,
+				authorize_upgrade: format!("0x{}", hex::encode(&auth.encoded)),
+				file_name: file_name_of(&path),
+			})
+		})
+		.collect::<Result<Vec<_>, Error>>()?;
+
+	// Sits next to the call data, e.g. `polkadot-1.2.0.call` and `polkadot-1.2.0.manifest.json`.
+	let output_file = upgrade_details.output_file.as_str();
+	let path =
+		format!("{}.manifest.json", output_file.strip_suffix(".call").unwrap_or(output_file));
+
+	Ok(UpgradeManifest {
+		path,
+		relay: upgrade_details.relay.clone(),
+		runtimes,
+		additional: upgrade_details
+			.additional
+			.as_ref()
+			.map(|a| format!("0x{}", hex::encode(&a.encoded))),
+		batch: BatchManifest {
+			call_file: upgrade_details.output_file.clone(),
+			call_hash: format!("0x{}", hex::encode(batch.hash)),
+			length: batch.length,
+		},
+	})
+}
+
+// Write the manifest to disk as JSON.
+fn write_manifest(manifest: &UpgradeManifest) -> Result<(), Error> {
+	let json = serde_json::to_string_pretty(manifest).expect("it serializes");
+	write_file(&manifest.path, json)?;
+	println!("\nThe upgrade manifest was written to {}", manifest.path);
+	Ok(())
+}
+
 // Write the call needed to disk and provide instructions to the user about how to propose it.
-fn write_batch(upgrade_details: &UpgradeDetails, batch: CallInfo) {
+fn write_batch(upgrade_details: &UpgradeDetails, batch: CallInfo) -> Result<(), Error> {
 	let fname = upgrade_details.output_file.as_str();
 	let mut info_to_write = "0x".to_owned();
 	info_to_write.push_str(hex::encode(batch.encoded).as_str());
-	fs::write(fname, info_to_write).expect("it should write");
+	write_file(fname, info_to_write)?;
 
 	println!("\nSuccess! The call data was written to {fname}");
 	println!("To submit this as a referendum in OpenGov, run:");
 	let network = match upgrade_details.relay {
 		Network::Kusama => "kusama",
 		Network::Polkadot => "polkadot",
-		_ => panic!("not a relay network"),
+		_ => return Err(not_a_relay_chain(&upgrade_details.relay)),
 	};
 	println!("\nopengov-cli submit-referendum \\");
 	println!("    --proposal \"{fname}\" \\");
 	println!("    --network \"{network}\" --track <\"root\" or \"whitelistedcaller\">");
+	Ok(())
 }
@@ -0,0 +1,469 @@
+use crate::*;
+use clap::Parser as ClapParser;
+use parity_scale_codec::DecodeAll;
+
+/// Decode call data against the bundled metadata and print it as a human-readable tree.
+#[derive(Debug, ClapParser)]
+pub struct DecodeArgs {
+	/// The network whose runtime the call is for, e.g. `polkadot-asset-hub` or `kusama`.
+	#[clap(long = "network", short)]
+	network: String,
+
+	/// The encoded call. This can either be the call data itself, e.g. "0x0102...", or a file path
+	/// that contains the data, e.g. "./my_proposal.call".
+	call: String,
+
+	/// Also decode the calls nested in preimages, inline proposals, and XCM `Transact`
+	/// instructions, following each `Transact` to the chain on which it will execute.
+	#[clap(long = "recursive", short)]
+	recursive: bool,
+}
+
+/// The sub-command's "main" function.
+pub fn decode(prefs: DecodeArgs) -> Result<(), Error> {
+	let network = Network::from_name(&prefs.network).map_err(|_| {
+		Error::UnsupportedNetwork(String::from(
+			"Networks should be in the form `polkadot-asset-hub`.",
+		))
+	})?;
+	let call_bytes = get_proposal_bytes(prefs.call)?;
+	let call_info = CallInfo::from_bytes(&call_bytes, network.clone());
+
+	let call = decode_runtime_call(&call_bytes, &network)
+		.map_err(|source| Error::Decode { network: network.clone(), source })?;
+
+	println!("\nNetwork:   {network:?}");
+	println!("Call hash: 0x{}", hex::encode(call_info.hash));
+	println!("Length:    {} bytes\n", call_info.length);
+	if prefs.recursive {
+		println!("{}", format_call_recursive(&call));
+	} else {
+		println!("{}", format_call(&call));
+	}
+	Ok(())
+}
+
+/// Decode some `bytes` into the `RuntimeCall` of `network`. All of the bytes must be consumed.
+pub fn decode_runtime_call(
+	bytes: &[u8],
+	network: &Network,
+) -> Result<NetworkRuntimeCall, parity_scale_codec::Error> {
+	let input = &mut &bytes[..];
+	let call = match network {
+		Network::Kusama => NetworkRuntimeCall::Kusama(KusamaRuntimeCall::decode_all(input)?),
+		Network::KusamaAssetHub =>
+			NetworkRuntimeCall::KusamaAssetHub(KusamaAssetHubRuntimeCall::decode_all(input)?),
+		Network::KusamaBridgeHub =>
+			NetworkRuntimeCall::KusamaBridgeHub(KusamaBridgeHubRuntimeCall::decode_all(input)?),
+		Network::KusamaPeople =>
+			NetworkRuntimeCall::KusamaPeople(KusamaPeopleRuntimeCall::decode_all(input)?),
+		Network::KusamaCoretime =>
+			NetworkRuntimeCall::KusamaCoretime(KusamaCoretimeRuntimeCall::decode_all(input)?),
+		Network::KusamaEncointer =>
+			NetworkRuntimeCall::KusamaEncointer(KusamaEncointerRuntimeCall::decode_all(input)?),
+		Network::Polkadot => NetworkRuntimeCall::Polkadot(PolkadotRuntimeCall::decode_all(input)?),
+		Network::PolkadotAssetHub =>
+			NetworkRuntimeCall::PolkadotAssetHub(PolkadotAssetHubRuntimeCall::decode_all(input)?),
+		Network::PolkadotCollectives =>
+			NetworkRuntimeCall::PolkadotCollectives(CollectivesRuntimeCall::decode_all(input)?),
+		Network::PolkadotBridgeHub =>
+			NetworkRuntimeCall::PolkadotBridgeHub(PolkadotBridgeHubRuntimeCall::decode_all(input)?),
+		Network::PolkadotPeople =>
+			NetworkRuntimeCall::PolkadotPeople(PolkadotPeopleRuntimeCall::decode_all(input)?),
+		Network::PolkadotCoretime =>
+			NetworkRuntimeCall::PolkadotCoretime(PolkadotCoretimeRuntimeCall::decode_all(input)?),
+	};
+	Ok(call)
+}
+
+/// Render a decoded call as an indented tree of pallets, calls, and arguments.
+pub fn format_call(call: &NetworkRuntimeCall) -> String {
+	let tree = match call {
+		NetworkRuntimeCall::Kusama(c) => format!("{c:#?}"),
+		NetworkRuntimeCall::KusamaAssetHub(c) => format!("{c:#?}"),
+		NetworkRuntimeCall::KusamaBridgeHub(c) => format!("{c:#?}"),
+		NetworkRuntimeCall::KusamaPeople(c) => format!("{c:#?}"),
+		NetworkRuntimeCall::KusamaCoretime(c) => format!("{c:#?}"),
+		NetworkRuntimeCall::KusamaEncointer(c) => format!("{c:#?}"),
+		NetworkRuntimeCall::Polkadot(c) => format!("{c:#?}"),
+		NetworkRuntimeCall::PolkadotAssetHub(c) => format!("{c:#?}"),
+		NetworkRuntimeCall::PolkadotCollectives(c) => format!("{c:#?}"),
+		NetworkRuntimeCall::PolkadotBridgeHub(c) => format!("{c:#?}"),
+		NetworkRuntimeCall::PolkadotPeople(c) => format!("{c:#?}"),
+		NetworkRuntimeCall::PolkadotCoretime(c) => format!("{c:#?}"),
+	};
+	compact_byte_lists(&tree)
+}
+
+// The pretty `Debug` output puts every element of a byte vector (remarks, preimages, `Transact`
+// payloads, hashes) on its own line. Collapse any list made up only of byte-sized integers into a
+// single hex string so that the tree stays readable.
+pub(crate) fn compact_byte_lists(tree: &str) -> String {
+	let lines: Vec<&str> = tree.lines().collect();
+	let mut output = Vec::with_capacity(lines.len());
+	let mut i = 0;
+	while i < lines.len() {
+		let line = lines[i];
+		if line.ends_with('[') {
+			let mut bytes = Vec::new();
+			let mut j = i + 1;
+			while j < lines.len() {
+				match lines[j].trim().trim_end_matches(',').parse::<u8>() {
+					Ok(b) => bytes.push(b),
+					Err(_) => break,
+				}
+				j += 1;
+			}
+			let closes = j < lines.len() && lines[j].trim().trim_end_matches(',') == "]";
+			if !bytes.is_empty() && closes {
+				let prefix = line.trim_end_matches('[');
+				let suffix = if lines[j].trim().ends_with(',') { "," } else { "" };
+				output.push(format!("{prefix}0x{}{suffix}", hex::encode(&bytes)));
+				i = j + 1;
+				continue;
+			}
+		}
+		output.push(line.to_string());
+		i += 1;
+	}
+	output.join("\n")
+}
+
+// A call nested inside another call, e.g. in a preimage, an inline proposal, or an XCM `Transact`.
+struct NestedCall {
+	// The network on which the nested call executes, if it could be determined.
+	network: Option<Network>,
+	// Where the call was found, for display.
+	label: String,
+	// The encoded call.
+	bytes: Vec<u8>,
+}
+
+/// Render a decoded call like `format_call`, followed by every call nested within it. Nested calls
+/// are decoded with the runtime types of the chain they execute on and rendered recursively.
+pub fn format_call_recursive(call: &NetworkRuntimeCall) -> String {
+	let mut tree = format_call(call);
+	for nested in nested_calls(call) {
+		let rendered = match &nested.network {
+			Some(network) => match decode_runtime_call(&nested.bytes, network) {
+				Ok(inner) =>
+					format!("{} on {network:?}:\n{}", nested.label, format_call_recursive(&inner)),
+				Err(_) => format!(
+					"{} on {network:?} (does not decode as a call):\n0x{}",
+					nested.label,
+					hex::encode(&nested.bytes)
+				),
+			},
+			None => format!(
+				"{} on an unknown destination:\n0x{}",
+				nested.label,
+				hex::encode(&nested.bytes)
+			),
+		};
+		tree.push_str("\n\n");
+		let indented: Vec<String> = rendered.lines().map(|line| format!("    {line}")).collect();
+		tree.push_str(&indented.join("\n"));
+	}
+	tree
+}
+
+/// Every call nested within `call`, at any depth, decoded with the runtime types of the chain it
+/// executes on, in the order that `format_call_recursive` renders them. Nested calls that do not
+/// decode, or whose chain is unknown, are skipped.
+pub fn nested_calls_recursive(call: &NetworkRuntimeCall) -> Vec<NetworkRuntimeCall> {
+	let mut calls = Vec::new();
+	for nested in nested_calls(call) {
+		let network = match &nested.network {
+			Some(network) => network,
+			None => continue,
+		};
+		if let Ok(inner) = decode_runtime_call(&nested.bytes, network) {
+			let deeper = nested_calls_recursive(&inner);
+			calls.push(inner);
+			calls.extend(deeper);
+		}
+	}
+	calls
+}
+
+// Find the calls nested directly within `call`. Only the chains on which this tool constructs
+// calls (the Relay Chains, Asset Hubs, and Collectives) are walked.
+fn nested_calls(call: &NetworkRuntimeCall) -> Vec<NestedCall> {
+	let mut nested = Vec::new();
+	match call {
+		NetworkRuntimeCall::Kusama(c) => kusama_nested_calls(c, &mut nested),
+		NetworkRuntimeCall::KusamaAssetHub(c) => kusama_asset_hub_nested_calls(c, &mut nested),
+		NetworkRuntimeCall::Polkadot(c) => polkadot_nested_calls(c, &mut nested),
+		NetworkRuntimeCall::PolkadotAssetHub(c) => polkadot_asset_hub_nested_calls(c, &mut nested),
+		NetworkRuntimeCall::PolkadotCollectives(c) => collectives_nested_calls(c, &mut nested),
+		_ => {},
+	}
+	nested
+}
+
+fn kusama_nested_calls(call: &KusamaRuntimeCall, nested: &mut Vec<NestedCall>) {
+	use kusama_relay::runtime_types::{
+		frame_support::traits::preimages::Bounded::Inline,
+		pallet_preimage::pallet::Call as PreimageCall,
+		pallet_referenda::pallet::Call as ReferendaCall,
+		pallet_utility::pallet::Call as UtilityCall,
+		pallet_whitelist::pallet::Call as WhitelistCall, pallet_xcm::pallet::Call as XcmCall,
+	};
+	match call {
+		KusamaRuntimeCall::Utility(
+			UtilityCall::batch { calls }
+			| UtilityCall::batch_all { calls }
+			| UtilityCall::force_batch { calls },
+		) => calls.iter().for_each(|c| kusama_nested_calls(c, nested)),
+		KusamaRuntimeCall::Whitelist(WhitelistCall::dispatch_whitelisted_call_with_preimage {
+			call,
+		}) => kusama_nested_calls(call, nested),
+		KusamaRuntimeCall::Preimage(PreimageCall::note_preimage { bytes }) =>
+			nested.push(NestedCall {
+				network: Some(Network::Kusama),
+				label: String::from("Preimage"),
+				bytes: bytes.clone(),
+			}),
+		KusamaRuntimeCall::FellowshipReferenda(ReferendaCall::submit {
+			proposal: Inline(inline),
+			..
+		}) => nested.push(NestedCall {
+			network: Some(Network::Kusama),
+			label: String::from("Inline proposal"),
+			bytes: inline.0.clone(),
+		}),
+		KusamaRuntimeCall::XcmPallet(XcmCall::send { dest, message }) =>
+			xcm_nested_calls(&Network::Kusama, &dest.encode(), &message.encode(), nested),
+		_ => {},
+	}
+}
+
+fn kusama_asset_hub_nested_calls(call: &KusamaAssetHubRuntimeCall, nested: &mut Vec<NestedCall>) {
+	use kusama_asset_hub::runtime_types::{
+		frame_support::traits::preimages::Bounded::Inline,
+		pallet_preimage::pallet::Call as PreimageCall,
+		pallet_referenda::pallet::Call as ReferendaCall,
+		pallet_utility::pallet::Call as UtilityCall,
+		pallet_whitelist::pallet::Call as WhitelistCall, pallet_xcm::pallet::Call as XcmCall,
+	};
+	match call {
+		KusamaAssetHubRuntimeCall::Utility(
+			UtilityCall::batch { calls }
+			| UtilityCall::batch_all { calls }
+			| UtilityCall::force_batch { calls },
+		) => calls.iter().for_each(|c| kusama_asset_hub_nested_calls(c, nested)),
+		KusamaAssetHubRuntimeCall::Whitelist(
+			WhitelistCall::dispatch_whitelisted_call_with_preimage { call },
+		) => kusama_asset_hub_nested_calls(call, nested),
+		KusamaAssetHubRuntimeCall::Preimage(PreimageCall::note_preimage { bytes }) =>
+			nested.push(NestedCall {
+				network: Some(Network::KusamaAssetHub),
+				label: String::from("Preimage"),
+				bytes: bytes.clone(),
+			}),
+		KusamaAssetHubRuntimeCall::Referenda(ReferendaCall::submit {
+			proposal: Inline(inline),
+			..
+		}) => nested.push(NestedCall {
+			network: Some(Network::KusamaAssetHub),
+			label: String::from("Inline proposal"),
+			bytes: inline.0.clone(),
+		}),
+		KusamaAssetHubRuntimeCall::PolkadotXcm(XcmCall::send { dest, message }) =>
+			xcm_nested_calls(&Network::KusamaAssetHub, &dest.encode(), &message.encode(), nested),
+		_ => {},
+	}
+}
+
+fn polkadot_nested_calls(call: &PolkadotRuntimeCall, nested: &mut Vec<NestedCall>) {
+	use polkadot_relay::runtime_types::{
+		frame_support::traits::preimages::Bounded::Inline,
+		pallet_preimage::pallet::Call as PreimageCall,
+		pallet_referenda::pallet::Call as ReferendaCall,
+		pallet_utility::pallet::Call as UtilityCall,
+		pallet_whitelist::pallet::Call as WhitelistCall, pallet_xcm::pallet::Call as XcmCall,
+	};
+	match call {
+		PolkadotRuntimeCall::Utility(
+			UtilityCall::batch { calls }
+			| UtilityCall::batch_all { calls }
+			| UtilityCall::force_batch { calls },
+		) => calls.iter().for_each(|c| polkadot_nested_calls(c, nested)),
+		PolkadotRuntimeCall::Whitelist(
+			WhitelistCall::dispatch_whitelisted_call_with_preimage { call },
+		) => polkadot_nested_calls(call, nested),
+		PolkadotRuntimeCall::Preimage(PreimageCall::note_preimage { bytes }) =>
+			nested.push(NestedCall {
+				network: Some(Network::Polkadot),
+				label: String::from("Preimage"),
+				bytes: bytes.clone(),
+			}),
+		PolkadotRuntimeCall::Referenda(ReferendaCall::submit {
+			proposal: Inline(inline), ..
+		}) => nested.push(NestedCall {
+			network: Some(Network::Polkadot),
+			label: String::from("Inline proposal"),
+			bytes: inline.0.clone(),
+		}),
+		PolkadotRuntimeCall::XcmPallet(XcmCall::send { dest, message }) =>
+			xcm_nested_calls(&Network::Polkadot, &dest.encode(), &message.encode(), nested),
+		_ => {},
+	}
+}
+
+fn polkadot_asset_hub_nested_calls(
+	call: &PolkadotAssetHubRuntimeCall,
+	nested: &mut Vec<NestedCall>,
+) {
+	use polkadot_asset_hub::runtime_types::{
+		frame_support::traits::preimages::Bounded::Inline,
+		pallet_preimage::pallet::Call as PreimageCall,
+		pallet_referenda::pallet::Call as ReferendaCall,
+		pallet_utility::pallet::Call as UtilityCall,
+		pallet_whitelist::pallet::Call as WhitelistCall, pallet_xcm::pallet::Call as XcmCall,
+	};
+	match call {
+		PolkadotAssetHubRuntimeCall::Utility(
+			UtilityCall::batch { calls }
+			| UtilityCall::batch_all { calls }
+			| UtilityCall::force_batch { calls },
+		) => calls.iter().for_each(|c| polkadot_asset_hub_nested_calls(c, nested)),
+		PolkadotAssetHubRuntimeCall::Whitelist(
+			WhitelistCall::dispatch_whitelisted_call_with_preimage { call },
+		) => polkadot_asset_hub_nested_calls(call, nested),
+		PolkadotAssetHubRuntimeCall::Preimage(PreimageCall::note_preimage { bytes }) => nested
+			.push(NestedCall {
+				network: Some(Network::PolkadotAssetHub),
+				label: String::from("Preimage"),
+				bytes: bytes.clone(),
+			}),
+		PolkadotAssetHubRuntimeCall::Referenda(ReferendaCall::submit {
+			proposal: Inline(inline),
+			..
+		}) => nested.push(NestedCall {
+			network: Some(Network::PolkadotAssetHub),
+			label: String::from("Inline proposal"),
+			bytes: inline.0.clone(),
+		}),
+		PolkadotAssetHubRuntimeCall::PolkadotXcm(XcmCall::send { dest, message }) =>
+			xcm_nested_calls(&Network::PolkadotAssetHub, &dest.encode(), &message.encode(), nested),
+		_ => {},
+	}
+}
+
+fn collectives_nested_calls(call: &CollectivesRuntimeCall, nested: &mut Vec<NestedCall>) {
+	use polkadot_collectives::runtime_types::{
+		frame_support::traits::preimages::Bounded::Inline,
+		pallet_preimage::pallet::Call as PreimageCall,
+		pallet_referenda::pallet::Call as ReferendaCall,
+		pallet_utility::pallet::Call as UtilityCall, pallet_xcm::pallet::Call as XcmCall,
+	};
+	match call {
+		CollectivesRuntimeCall::Utility(
+			UtilityCall::batch { calls }
+			| UtilityCall::batch_all { calls }
+			| UtilityCall::force_batch { calls },
+		) => calls.iter().for_each(|c| collectives_nested_calls(c, nested)),
+		CollectivesRuntimeCall::Preimage(PreimageCall::note_preimage { bytes }) =>
+			nested.push(NestedCall {
+				network: Some(Network::PolkadotCollectives),
+				label: String::from("Preimage"),
+				bytes: bytes.clone(),
+			}),
+		CollectivesRuntimeCall::FellowshipReferenda(ReferendaCall::submit {
+			proposal: Inline(inline),
+			..
+		}) => nested.push(NestedCall {
+			network: Some(Network::PolkadotCollectives),
+			label: String::from("Inline proposal"),
+			bytes: inline.0.clone(),
+		}),
+		CollectivesRuntimeCall::PolkadotXcm(XcmCall::send { dest, message }) => xcm_nested_calls(
+			&Network::PolkadotCollectives,
+			&dest.encode(),
+			&message.encode(),
+			nested,
+		),
+		_ => {},
+	}
+}
+
+// Find the `Transact` payloads in an XCM `send` from `origin`. Every runtime encodes XCM v5 the
+// same way, so the destination and message are re-decoded into one set of types to walk them.
+fn xcm_nested_calls(origin: &Network, dest: &[u8], message: &[u8], nested: &mut Vec<NestedCall>) {
+	use polkadot_asset_hub::runtime_types::xcm::{VersionedLocation, VersionedXcm};
+
+	let destination =
+		match <VersionedLocation as parity_scale_codec::Decode>::decode(&mut &dest[..]) {
+			Ok(VersionedLocation::V5(location)) => resolve_destination(origin, &location),
+			_ => None,
+		};
+	match <VersionedXcm as parity_scale_codec::Decode>::decode(&mut &message[..]) {
+		Ok(VersionedXcm::V5(xcm)) => xcm_transacts(destination, &xcm, nested),
+		_ => nested.push(NestedCall {
+			network: None,
+			label: String::from("XCM message (only XCM v5 is followed)"),
+			bytes: message.to_vec(),
+		}),
+	}
+}
+
+// Walk the instructions of an XCM program executing on `location`, following nested programs.
+fn xcm_transacts(
+	location: Option<Network>,
+	xcm: &polkadot_asset_hub::runtime_types::staging_xcm::v5::Xcm,
+	nested: &mut Vec<NestedCall>,
+) {
+	use polkadot_asset_hub::runtime_types::staging_xcm::v5::Instruction;
+
+	for instruction in &xcm.0 {
+		match instruction {
+			Instruction::Transact { origin_kind, call, .. } => nested.push(NestedCall {
+				network: location.clone(),
+				label: format!("XCM Transact ({origin_kind:?} origin)"),
+				bytes: call.encoded.clone(),
+			}),
+			Instruction::SetAppendix(inner)
+			| Instruction::SetErrorHandler(inner)
+			| Instruction::ExecuteWithOrigin { xcm: inner, .. } =>
+				xcm_transacts(location.clone(), inner, nested),
+			Instruction::InitiateTransfer { destination, remote_xcm, .. } => {
+				let remote = location.as_ref().and_then(|l| resolve_destination(l, destination));
+				xcm_transacts(remote, remote_xcm, nested)
+			},
+			_ => {},
+		}
+	}
+}
+
+// Resolve an XCM `Location`, relative to `origin`, to one of the networks this tool knows about.
+pub(crate) fn resolve_destination(
+	origin: &Network,
+	location: &polkadot_asset_hub::runtime_types::staging_xcm::v5::location::Location,
+) -> Option<Network> {
+	use polkadot_asset_hub::runtime_types::staging_xcm::v5::{
+		junction::{
+			Junction::{GlobalConsensus, Parachain},
+			NetworkId,
+		},
+		junctions::Junctions::{Here, X1, X2},
+	};
+
+	let is_relay = origin.get_para_id().is_err();
+	let consensus_relay = |network: &NetworkId| match network {
+		NetworkId::Kusama => Some(Network::Kusama),
+		NetworkId::Polkadot => Some(Network::Polkadot),
+		_ => None,
+	};
+	match (location.parents, &location.interior) {
+		(0, Here) => Some(origin.clone()),
+		(0, X1([Parachain(para_id)])) if is_relay => Network::from_para_id(origin, *para_id).ok(),
+		(1, Here) if !is_relay => Some(origin.relay()),
+		(1, X1([Parachain(para_id)])) if !is_relay =>
+			Network::from_para_id(&origin.relay(), *para_id).ok(),
+		(1 | 2, X1([GlobalConsensus(network)])) => consensus_relay(network),
+		(1 | 2, X2([GlobalConsensus(network), Parachain(para_id)])) =>
+			consensus_relay(network).and_then(|relay| Network::from_para_id(&relay, *para_id).ok()),
+		_ => None,
+	}
+}
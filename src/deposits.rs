@@ -0,0 +1,230 @@
+use crate::*;
+use parity_scale_codec::Decode;
+
+/// A deposit that is held from the account that submits one of the calls generated by this tool.
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct Deposit {
+	/// The network on which the deposit is held.
+	pub network: Network,
+	/// What the deposit is for, e.g. `Submission deposit`.
+	pub reason: String,
+	/// The deposit, in the smallest unit of the network's native token.
+	pub amount: u128,
+}
+
+// The track of the Fellowship that whitelists calls, in both Fellowships.
+const FELLOWS_TRACK: &str = "fellows";
+
+/// The deposits that the referenda in `calls` require, from the bundled metadata: a deposit for
+/// each preimage, proportional to its length, then the submission and decision deposits of each
+/// referendum. The decision deposit may be placed by any account.
+pub fn referendum_deposits(
+	track: &NetworkTrack,
+	calls: &PossibleCallsToSubmit,
+) -> Result<Vec<Deposit>, Error> {
+	let mut deposits = Vec::new();
+	let submissions =
+		[&calls.fellowship_referendum_submission, &calls.public_referendum_submission];
+	for (network, pallet, preimage_len) in submissions.into_iter().flatten().filter_map(submission)
+	{
+		let metadata = bundled_metadata(&network)?;
+		if let Some(len) = preimage_len {
+			let (base, per_byte) = preimage_deposit(&network)?;
+			deposits.push(Deposit {
+				network: network.clone(),
+				reason: format!("Preimage deposit ({len} bytes)"),
+				amount: base.saturating_add(per_byte.saturating_mul(len as u128)),
+			});
+		}
+		deposits.push(Deposit {
+			network: network.clone(),
+			reason: String::from("Submission deposit"),
+			amount: constant(&network, &metadata, pallet, "SubmissionDeposit")?,
+		});
+		let decision_deposit = match network {
+			Network::KusamaAssetHub | Network::PolkadotAssetHub =>
+				list_tracks::track_for(&track.network(), track)?.decision_deposit,
+			_ => fellowship_decision_deposit(&network, &metadata)?,
+		};
+		deposits.push(Deposit {
+			network,
+			reason: String::from("Decision deposit"),
+			amount: decision_deposit,
+		});
+	}
+	Ok(deposits)
+}
+
+/// Print `deposits` and their total on each network, in whole tokens and in the smallest unit.
+pub fn print_deposits(deposits: &[Deposit]) {
+	let mut networks: Vec<&Network> = Vec::new();
+	for deposit in deposits {
+		if !networks.contains(&&deposit.network) {
+			networks.push(&deposit.network);
+		}
+	}
+	for network in networks {
+		let (symbol, decimals) = network.token();
+		let amount = |amount: u128| {
+			format!(
+				"{} {symbol} ({} planck)",
+				format_amount(amount, decimals),
+				format_amount(amount, 0)
+			)
+		};
+		println!("\nDeposits on {network:?}:");
+		let on_network = deposits.iter().filter(|deposit| deposit.network == *network);
+		for deposit in on_network.clone() {
+			println!("  {:<30} {}", format!("{}:", deposit.reason), amount(deposit.amount));
+		}
+		let total = on_network.fold(0u128, |total, deposit| total.saturating_add(deposit.amount));
+		println!("  {:<30} {}", "Total:", amount(total));
+	}
+}
+
+/// The base and per-byte deposit for noting a preimage on `network`.
+///
+/// The `Preimage` pallet does not expose its deposits as constants, but the runtimes price them
+/// with the same `deposit(items, bytes)` function as the `Multisig` pallet's: a preimage holds
+/// `deposit(2, 64)` plus `deposit(0, 1)` per byte, while `DepositBase` is `deposit(1, 88)` and
+/// `DepositFactor` is `deposit(0, 32)`. So the prices of an item and of a byte are recovered from
+/// the latter.
+pub fn preimage_deposit(network: &Network) -> Result<(u128, u128), Error> {
+	let metadata = bundled_metadata(network)?;
+	let base = constant(network, &metadata, "Multisig", "DepositBase")?;
+	let factor = constant(network, &metadata, "Multisig", "DepositFactor")?;
+	let per_byte = factor / 32;
+	let per_item = base.saturating_sub(per_byte * 88);
+	Ok((per_item * 2 + per_byte * 64, per_byte))
+}
+
+// The network, `Referenda` pallet, and preimage length, if the proposal is not inline, of a
+// referendum submitted by `call`.
+fn submission(call: &NetworkRuntimeCall) -> Option<(Network, &'static str, Option<u32>)> {
+	use kusama_asset_hub::runtime_types::{
+		frame_support::traits::preimages::Bounded as KusamaAssetHubBounded,
+		pallet_referenda::pallet::Call as KusamaAssetHubReferendaCall,
+	};
+	use kusama_relay::runtime_types::{
+		frame_support::traits::preimages::Bounded as KusamaBounded,
+		pallet_referenda::pallet::Call as KusamaReferendaCall,
+	};
+	use polkadot_asset_hub::runtime_types::{
+		frame_support::traits::preimages::Bounded as PolkadotAssetHubBounded,
+		pallet_referenda::pallet::Call as PolkadotAssetHubReferendaCall,
+	};
+	use polkadot_collectives::runtime_types::{
+		frame_support::traits::preimages::Bounded as CollectivesBounded,
+		pallet_referenda::pallet::Call as CollectivesReferendaCall,
+	};
+
+	match call {
+		NetworkRuntimeCall::KusamaAssetHub(KusamaAssetHubRuntimeCall::Referenda(
+			KusamaAssetHubReferendaCall::submit { proposal, .. },
+		)) => {
+			let len = match proposal {
+				KusamaAssetHubBounded::Lookup { len, .. } => Some(*len),
+				_ => None,
+			};
+			Some((Network::KusamaAssetHub, "Referenda", len))
+		},
+		NetworkRuntimeCall::PolkadotAssetHub(PolkadotAssetHubRuntimeCall::Referenda(
+			PolkadotAssetHubReferendaCall::submit { proposal, .. },
+		)) => {
+			let len = match proposal {
+				PolkadotAssetHubBounded::Lookup { len, .. } => Some(*len),
+				_ => None,
+			};
+			Some((Network::PolkadotAssetHub, "Referenda", len))
+		},
+		NetworkRuntimeCall::Kusama(KusamaRuntimeCall::FellowshipReferenda(
+			KusamaReferendaCall::submit { proposal, .. },
+		)) => {
+			let len = match proposal {
+				KusamaBounded::Lookup { len, .. } => Some(*len),
+				_ => None,
+			};
+			Some((Network::Kusama, "FellowshipReferenda", len))
+		},
+		NetworkRuntimeCall::PolkadotCollectives(CollectivesRuntimeCall::FellowshipReferenda(
+			CollectivesReferendaCall::submit { proposal, .. },
+		)) => {
+			let len = match proposal {
+				CollectivesBounded::Lookup { len, .. } => Some(*len),
+				_ => None,
+			};
+			Some((Network::PolkadotCollectives, "FellowshipReferenda", len))
+		},
+		_ => None,
+	}
+}
+
+// The decision deposit of the `FellowshipReferenda` track on which this tool submits whitelisting
+// referenda, on the Kusama Relay Chain or Polkadot Collectives.
+fn fellowship_decision_deposit(
+	network: &Network,
+	metadata: &subxt::Metadata,
+) -> Result<u128, Error> {
+	let tracks = constant_bytes(network, metadata, "FellowshipReferenda", "Tracks")?;
+	let undecodable = |e: parity_scale_codec::Error| {
+		invalid(network, format!("has `Tracks` that do not decode: {e}"))
+	};
+	// Names are padded with zeros to a fixed length.
+	let is_fellows = |name: &str| name.trim_end_matches('\0') == FELLOWS_TRACK;
+	let deposit = match network {
+		Network::Kusama => {
+			use kusama_relay::runtime_types::pallet_referenda::types::TrackDetails;
+			Vec::<(u16, TrackDetails<u128, u32, String>)>::decode(&mut &tracks[..])
+				.map_err(undecodable)?
+				.into_iter()
+				.find(|(_, details)| is_fellows(&details.name))
+				.map(|(_, details)| details.decision_deposit)
+		},
+		Network::PolkadotCollectives => {
+			use polkadot_collectives::runtime_types::pallet_referenda::types::TrackDetails;
+			Vec::<(u16, TrackDetails<u128, u32, String>)>::decode(&mut &tracks[..])
+				.map_err(undecodable)?
+				.into_iter()
+				.find(|(_, details)| is_fellows(&details.name))
+				.map(|(_, details)| details.decision_deposit)
+		},
+		_ => None,
+	};
+	deposit.ok_or_else(|| invalid(network, format!("has no `{FELLOWS_TRACK}` Fellowship track")))
+}
+
+// The bundled metadata of `network`.
+pub(crate) fn bundled_metadata(network: &Network) -> Result<subxt::Metadata, Error> {
+	wasm::decode_metadata(network.bundled_metadata()).map_err(|reason| invalid(network, reason))
+}
+
+// The `u128` constant `pallet::name` in the `metadata` of `network`.
+fn constant(
+	network: &Network,
+	metadata: &subxt::Metadata,
+	pallet: &str,
+	name: &str,
+) -> Result<u128, Error> {
+	let value = constant_bytes(network, metadata, pallet, name)?;
+	u128::decode(&mut &value[..])
+		.map_err(|e| invalid(network, format!("has `{pallet}::{name}` that does not decode: {e}")))
+}
+
+// The encoded constant `pallet::name` in the `metadata` of `network`.
+pub(crate) fn constant_bytes(
+	network: &Network,
+	metadata: &subxt::Metadata,
+	pallet: &str,
+	name: &str,
+) -> Result<Vec<u8>, Error> {
+	metadata
+		.pallet_by_name(pallet)
+		.and_then(|p| p.constant_by_name(name))
+		.map(|constant| constant.value().to_vec())
+		.ok_or_else(|| invalid(network, format!("has no `{pallet}::{name}` constant")))
+}
+
+// An error for bundled metadata of `network` that is not as expected.
+fn invalid(network: &Network, reason: String) -> Error {
+	Error::InvalidRuntime { file: format!("The bundled {network:?} metadata"), reason }
+}
@@ -0,0 +1,156 @@
+use crate::*;
+use subxt::ext::scale_value::{self, ValueDef};
+use subxt::tx::Payload as _;
+use subxt::Metadata;
+
+/// Metadata to encode calls with instead of the bundled `metadata/*.scale` that the static types
+/// were generated from, per network.
+///
+/// Calls are still built with the static types. For a network with an override, their encoding
+/// is then decoded into subxt's dynamic `Value`s and re-encoded against the override, matching
+/// pallets, calls, fields, and variants by name. Pallets or calls that moved in a runtime upgrade
+/// are thereby encoded at their new indices without rebuilding the binary.
+#[derive(Clone, Default)]
+pub struct MetadataOverrides {
+	overrides: Vec<Override>,
+}
+
+#[derive(Clone)]
+struct Override {
+	network: Network,
+	bundled: Metadata,
+	metadata: Metadata,
+}
+
+impl MetadataOverrides {
+	/// Load each of `paths`, a runtime Wasm blob or a SCALE-encoded metadata file, as the metadata
+	/// of the network named by its `spec_name`.
+	pub fn load(paths: &[String]) -> Result<Self, Error> {
+		let mut overrides = Self::default();
+		for path in paths {
+			let metadata = metadata_diff::load_metadata(path)?;
+			let network = wasm::metadata_runtime_version(&metadata)
+				.and_then(|version| Network::from_spec_name(&version.spec_name).ok())
+				.ok_or_else(|| {
+					Error::UnsupportedNetwork(format!(
+						"Could not tell which network the metadata in {path} is for."
+					))
+				})?;
+			println!("Encoding {network:?} calls with the metadata in {path}");
+			overrides.insert(network, metadata)?;
+		}
+		Ok(overrides)
+	}
+
+	/// Encode calls for `network` with `metadata`. Replaces any previous override for `network`.
+	pub fn insert(&mut self, network: Network, metadata: Metadata) -> Result<(), Error> {
+		let bundled = wasm::decode_metadata(network.bundled_metadata()).map_err(|reason| {
+			Error::InvalidRuntime { file: format!("The bundled {network:?} metadata"), reason }
+		})?;
+		self.overrides.retain(|o| o.network != network);
+		self.overrides.push(Override { network, bundled, metadata });
+		Ok(())
+	}
+
+	/// Whether there are no overrides, i.e. all calls use the static encoding.
+	pub fn is_empty(&self) -> bool {
+		self.overrides.is_empty()
+	}
+
+	/// The on-chain encoding of `call`.
+	pub fn encode(&self, call: &NetworkRuntimeCall) -> Result<Vec<u8>, Error> {
+		let network = call.network();
+		let encoded = call.encoded();
+		match self.get(&network) {
+			Some(o) => transcode_call(&encoded, &o.bundled, &o.metadata)
+				.map_err(|reason| Error::Metadata { network, reason }),
+			None => Ok(encoded),
+		}
+	}
+
+	/// The `CallInfo` of `call`, with its on-chain encoding and hash.
+	pub fn call_info(&self, call: NetworkRuntimeCall) -> Result<CallInfo, Error> {
+		let network = call.network();
+		Ok(CallInfo::from_bytes(&self.encode(&call)?, network))
+	}
+
+	/// Re-encode `call`, which has its on-chain encoding (e.g. user input or the output of
+	/// `call_info`), with the bundled metadata so that it decodes into the static types.
+	pub fn to_bundled(&self, call: &CallInfo) -> Result<CallInfo, Error> {
+		match self.get(&call.network) {
+			Some(o) => {
+				let encoded = transcode_call(&call.encoded, &o.metadata, &o.bundled)
+					.map_err(|reason| Error::Metadata { network: call.network.clone(), reason })?;
+				Ok(CallInfo::from_bytes(&encoded, call.network.clone()))
+			},
+			None => Ok(call.clone()),
+		}
+	}
+
+	/// The on-chain encoding of `origin`, an `OriginCaller` of `network` encoded with the static
+	/// types.
+	pub fn encode_origin(&self, network: &Network, origin: &[u8]) -> Result<Vec<u8>, Error> {
+		match self.get(network) {
+			Some(o) => transcode_origin(origin, &o.bundled, &o.metadata)
+				.map_err(|reason| Error::Metadata { network: network.clone(), reason }),
+			None => Ok(origin.to_vec()),
+		}
+	}
+
+	fn get(&self, network: &Network) -> Option<&Override> {
+		self.overrides.iter().find(|o| o.network == *network)
+	}
+}
+
+/// Re-encode `call`, a `RuntimeCall` encoded with the metadata `from`, as the same call in the
+/// metadata `to`. Pallets, calls, fields, and variants are matched by name.
+pub fn transcode_call(call: &[u8], from: &Metadata, to: &Metadata) -> Result<Vec<u8>, String> {
+	let input = &mut &call[..];
+	let value =
+		scale_value::scale::decode_as_type(input, from.outer_enums().call_enum_ty(), from.types())
+			.map_err(|e| format!("the call does not decode: {e}"))?;
+	if !input.is_empty() {
+		return Err(format!("the call has {} trailing bytes", input.len()));
+	}
+
+	// `RuntimeCall` is an enum of pallets, each of which is an enum of calls.
+	let ValueDef::Variant(pallet) = value.value else {
+		return Err(String::from("the call is not a `RuntimeCall`"));
+	};
+	let Some(ValueDef::Variant(call)) = pallet.values.into_values().next().map(|v| v.value) else {
+		return Err(format!("the `{}` call is not a pallet call", pallet.name));
+	};
+
+	let payload = subxt::dynamic::tx(
+		pallet.name.as_str(),
+		call.name.as_str(),
+		call.values.map_context(|_| ()),
+	);
+	payload.encode_call_data(to).map_err(|e| format!("`{}.{}`: {e}", pallet.name, call.name))
+}
+
+/// Re-encode `origin`, an `OriginCaller` encoded with the metadata `from`, as the same origin in
+/// the metadata `to`. Variants and fields are matched by name.
+pub fn transcode_origin(origin: &[u8], from: &Metadata, to: &Metadata) -> Result<Vec<u8>, String> {
+	let input = &mut &origin[..];
+	let value = scale_value::scale::decode_as_type(input, origin_caller_ty(from)?, from.types())
+		.map_err(|e| format!("the origin does not decode: {e}"))?;
+	if !input.is_empty() {
+		return Err(format!("the origin has {} trailing bytes", input.len()));
+	}
+	let mut encoded = Vec::new();
+	scale_value::scale::encode_as_type(&value, origin_caller_ty(to)?, to.types(), &mut encoded)
+		.map_err(|e| format!("the origin `{value}` does not encode: {e}"))?;
+	Ok(encoded)
+}
+
+// The type ID of the runtime's `OriginCaller`, the origin with which calls are dispatched.
+fn origin_caller_ty(metadata: &Metadata) -> Result<u32, String> {
+	metadata
+		.types()
+		.types
+		.iter()
+		.find(|ty| ty.ty.path.segments.last().map(String::as_str) == Some("OriginCaller"))
+		.map(|ty| ty.id)
+		.ok_or_else(|| String::from("the metadata has no `OriginCaller` type"))
+}
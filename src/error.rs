@@ -0,0 +1,101 @@
+use crate::Network;
+use std::fmt;
+
+/// Everything that can go wrong when running a command. Each variant exits with its own code (see
+/// `exit_code`) so that scripts can branch on the kind of failure.
+#[derive(Debug)]
+pub enum Error {
+	/// Some call data provided by the user is not valid hex.
+	InvalidHex(hex::FromHexError),
+	/// A file provided by the user (or expected in the upgrade directory) could not be read.
+	UnreadableFile { path: String, source: std::io::Error },
+	/// The network is not one that this command supports.
+	UnsupportedNetwork(String),
+	/// The track does not exist on the chosen network.
+	UnsupportedTrack(String),
+	/// Any other invalid argument or combination of arguments.
+	InvalidInput(String),
+	/// A runtime could not be downloaded from the release page.
+	Download { url: String, reason: String },
+	/// A runtime blob failed its sanity checks.
+	RuntimeCheck { file: String, reason: String },
+	/// Call data does not decode as a call on the given network.
+	Decode { network: Network, source: parity_scale_codec::Error },
+	/// Output could not be written to disk.
+	WriteFile { path: String, source: std::io::Error },
+	/// A runtime blob does not match the checksum published for it.
+	Checksum { file: String, reason: String },
+	/// A runtime blob could not be decompressed, executed, or inspected.
+	InvalidRuntime { file: String, reason: String },
+	/// A call could not be encoded with the metadata given by `--metadata`.
+	Metadata { network: Network, reason: String },
+	/// A node could not be reached or queried over RPC.
+	Rpc { url: String, reason: String },
+	/// A Fellowship referendum does not whitelist the expected proposal.
+	WhitelistMismatch(String),
+	/// A submitted extrinsic failed, or its events could not be read.
+	Extrinsic { network: Network, reason: String },
+	/// A simulated call failed to dispatch, or could not be simulated.
+	Simulation { network: Network, reason: String },
+}
+
+impl Error {
+	/// The process exit code for this error. `1` is left for panics and `2` for `clap`'s own
+	/// argument errors.
+	pub fn exit_code(&self) -> i32 {
+		match self {
+			Error::InvalidInput(_) => 3,
+			Error::UnsupportedNetwork(_) => 4,
+			Error::UnsupportedTrack(_) => 5,
+			Error::InvalidHex(_) => 6,
+			Error::UnreadableFile { .. } => 7,
+			Error::Decode { .. } => 8,
+			Error::Download { .. } => 9,
+			Error::RuntimeCheck { .. } => 10,
+			Error::WriteFile { .. } => 11,
+			Error::Checksum { .. } => 12,
+			Error::InvalidRuntime { .. } => 13,
+			Error::Metadata { .. } => 14,
+			Error::Rpc { .. } => 15,
+			Error::WhitelistMismatch(_) => 16,
+			Error::Extrinsic { .. } => 17,
+			Error::Simulation { .. } => 18,
+		}
+	}
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Error::InvalidHex(e) => write!(f, "Invalid hex in call data: {e}"),
+			Error::UnreadableFile { path, source } => write!(f, "Could not read {path}: {source}"),
+			Error::UnsupportedNetwork(message) => write!(f, "Unsupported network! {message}"),
+			Error::UnsupportedTrack(message) => write!(f, "Unsupported track! {message}"),
+			Error::InvalidInput(message) => write!(f, "{message}"),
+			Error::Download { url, reason } => write!(f, "Failed to download {url}: {reason}"),
+			Error::RuntimeCheck { file, reason } =>
+				write!(f, "{file} {reason}. Pass --no-runtime-checks to skip."),
+			Error::Decode { network, source } =>
+				write!(f, "Call data does not decode as a {network:?} call: {source}"),
+			Error::WriteFile { path, source } => write!(f, "Could not write {path}: {source}"),
+			Error::Checksum { file, reason } => write!(f, "Checksum mismatch! {file} {reason}."),
+			Error::InvalidRuntime { file, reason } => write!(f, "{file} {reason}."),
+			Error::Metadata { network, reason } =>
+				write!(f, "Could not encode a {network:?} call with the given metadata: {reason}"),
+			Error::Rpc { url, reason } => write!(f, "RPC request to {url} failed: {reason}"),
+			Error::WhitelistMismatch(message) => write!(f, "Whitelist check failed! {message}"),
+			Error::Extrinsic { network, reason } =>
+				write!(f, "Extrinsic on {network:?} failed: {reason}"),
+			Error::Simulation { network, reason } =>
+				write!(f, "Simulation on {network:?} failed: {reason}"),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl From<hex::FromHexError> for Error {
+	fn from(e: hex::FromHexError) -> Self {
+		Error::InvalidHex(e)
+	}
+}
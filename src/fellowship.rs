@@ -0,0 +1,188 @@
+use crate::*;
+use clap::Parser as ClapParser;
+use decode::{decode_runtime_call, nested_calls_recursive};
+use submit_referendum::{parse_output, print_output, JsonStep};
+
+/// Build the calls that follow a Fellowship referendum to whitelist a proposal.
+#[derive(Debug, ClapParser)]
+pub struct FellowshipArgs {
+	#[clap(subcommand)]
+	command: FellowshipCommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum FellowshipCommand {
+	Vote(VoteArgs),
+	CheckWhitelist(CheckWhitelistArgs),
+}
+
+/// Build the call for a Fellow to vote on a Fellowship referendum.
+#[derive(Debug, ClapParser)]
+pub struct VoteArgs {
+	/// Which Fellowship the referendum is in. `kusama` (on the Relay Chain) or `polkadot` (on
+	/// Collectives).
+	#[clap(long = "fellowship")]
+	fellowship: String,
+
+	/// The index of the Fellowship referendum.
+	#[clap(long = "ref-index")]
+	ref_index: u32,
+
+	/// Vote for the referendum.
+	#[clap(long = "aye", conflicts_with = "nay", required_unless_present = "nay")]
+	aye: bool,
+
+	/// Vote against the referendum.
+	#[clap(long = "nay")]
+	nay: bool,
+
+	/// Form of output. `AppsUiLink`, `CallData`, or `Json`. Defaults to Apps UI.
+	#[clap(long = "output")]
+	output: Option<String>,
+
+	/// Use light client endpoints instead of RPC for PAPI links.
+	#[clap(long = "light-client")]
+	light_client: bool,
+}
+
+/// Check that a Fellowship referendum would whitelist a proposal.
+#[derive(Debug, ClapParser)]
+pub struct CheckWhitelistArgs {
+	/// Which Fellowship the referendum is in. `kusama` (on the Relay Chain) or `polkadot` (on
+	/// Collectives).
+	#[clap(long = "fellowship")]
+	fellowship: String,
+
+	/// The Fellowship referendum's call: its `FellowshipReferenda::submit` (with an inline
+	/// proposal), the batch that contains it, or the call that it proposes. Either the call data
+	/// itself or a file path that contains it.
+	#[clap(long = "referendum")]
+	referendum: String,
+
+	/// The proposal that should be whitelisted. Either the call data itself or a file path that
+	/// contains it.
+	#[clap(long = "proposal", short)]
+	proposal: String,
+}
+
+/// The sub-command's "main" function.
+pub fn fellowship(prefs: FellowshipArgs) -> Result<(), Error> {
+	match prefs.command {
+		FellowshipCommand::Vote(prefs) => vote(prefs),
+		FellowshipCommand::CheckWhitelist(prefs) => check_whitelist(prefs),
+	}
+}
+
+// Print the call to vote on a Fellowship referendum.
+fn vote(prefs: VoteArgs) -> Result<(), Error> {
+	let network = parse_fellowship(&prefs.fellowship)?;
+	let output = parse_output(prefs.output)?;
+	let call = vote_call(&network, prefs.ref_index, prefs.aye)?;
+	let call_info = CallInfo::from_runtime_call(call);
+	match output {
+		Output::Json => {
+			let step = JsonStep::from_call("fellowship_vote", &call_info);
+			let document = serde_json::json!({ "steps": [step] });
+			println!("{}", serde_json::to_string_pretty(&document).expect("it serializes"));
+		},
+		_ => {
+			let vote = if prefs.aye { "aye" } else { "nay" };
+			println!("\nVote {vote} on Fellowship referendum {} on {network:?}:", prefs.ref_index);
+			print_output(&output, &call_info, prefs.light_client)?;
+		},
+	}
+	Ok(())
+}
+
+// Check that the Fellowship referendum whitelists the hash of the proposal.
+fn check_whitelist(prefs: CheckWhitelistArgs) -> Result<(), Error> {
+	let network = parse_fellowship(&prefs.fellowship)?;
+	let referendum = get_proposal_bytes(prefs.referendum)?;
+	let referendum = decode_runtime_call(&referendum, &network)
+		.map_err(|source| Error::Decode { network: network.clone(), source })?;
+	let proposal_hash = blake2_256(&get_proposal_bytes(prefs.proposal)?);
+
+	println!("\nProposal hash: 0x{}", hex::encode(proposal_hash));
+	let whitelisted = whitelisted_hashes(&referendum);
+	for (network, hash) in &whitelisted {
+		let matches = if *hash == proposal_hash { "matches" } else { "does not match" };
+		println!("Whitelists on {network:?}: 0x{} ({matches})", hex::encode(hash));
+	}
+	if whitelisted.is_empty() {
+		return Err(Error::WhitelistMismatch(String::from(
+			"The Fellowship referendum does not whitelist any call.",
+		)));
+	}
+	if !whitelisted.iter().any(|(_, hash)| *hash == proposal_hash) {
+		return Err(Error::WhitelistMismatch(String::from(
+			"The Fellowship referendum does not whitelist the proposal.",
+		)));
+	}
+	println!("\nThe Fellowship referendum whitelists the proposal.");
+	Ok(())
+}
+
+// The network of the Fellowship called `name`: the Kusama Relay Chain or Polkadot Collectives.
+fn parse_fellowship(name: &str) -> Result<Network, Error> {
+	match name.to_ascii_lowercase().as_str() {
+		"kusama" => Ok(Network::Kusama),
+		"polkadot" => Ok(Network::PolkadotCollectives),
+		_ =>
+			Err(Error::InvalidInput(String::from("`--fellowship` must be `kusama` or `polkadot`."))),
+	}
+}
+
+/// The call to vote `aye` (or nay) on the referendum `poll` of the Fellowship on `network`, the
+/// Kusama Relay Chain or Polkadot Collectives.
+pub fn vote_call(network: &Network, poll: u32, aye: bool) -> Result<NetworkRuntimeCall, Error> {
+	use kusama_relay::runtime_types::pallet_ranked_collective::pallet::Call as KusamaCollectiveCall;
+	use polkadot_collectives::runtime_types::pallet_ranked_collective::pallet::Call as CollectivesCollectiveCall;
+
+	match network {
+		Network::Kusama => Ok(NetworkRuntimeCall::Kusama(KusamaRuntimeCall::FellowshipCollective(
+			KusamaCollectiveCall::vote { poll, aye },
+		))),
+		Network::PolkadotCollectives => Ok(NetworkRuntimeCall::PolkadotCollectives(
+			CollectivesRuntimeCall::FellowshipCollective(CollectivesCollectiveCall::vote {
+				poll,
+				aye,
+			}),
+		)),
+		_ => Err(Error::UnsupportedNetwork(String::from(
+			"The Fellowships are on the Kusama Relay Chain and Polkadot Collectives.",
+		))),
+	}
+}
+
+/// The network and call hash of every `Whitelist::whitelist_call` in `call` or nested within it,
+/// e.g. in the inline proposal of a Fellowship referendum and the XCM that it sends.
+pub fn whitelisted_hashes(call: &NetworkRuntimeCall) -> Vec<(Network, [u8; 32])> {
+	let mut hashes: Vec<(Network, [u8; 32])> = whitelisted_hash(call).into_iter().collect();
+	hashes.extend(nested_calls_recursive(call).iter().filter_map(whitelisted_hash));
+	hashes
+}
+
+// The network and call hash, if `call` is a `Whitelist::whitelist_call`.
+fn whitelisted_hash(call: &NetworkRuntimeCall) -> Option<(Network, [u8; 32])> {
+	use kusama_asset_hub::runtime_types::pallet_whitelist::pallet::Call as KusamaAssetHubWhitelistCall;
+	use kusama_relay::runtime_types::pallet_whitelist::pallet::Call as KusamaWhitelistCall;
+	use polkadot_asset_hub::runtime_types::pallet_whitelist::pallet::Call as PolkadotAssetHubWhitelistCall;
+	use polkadot_relay::runtime_types::pallet_whitelist::pallet::Call as PolkadotWhitelistCall;
+
+	let call_hash = match call {
+		NetworkRuntimeCall::KusamaAssetHub(KusamaAssetHubRuntimeCall::Whitelist(
+			KusamaAssetHubWhitelistCall::whitelist_call { call_hash },
+		))
+		| NetworkRuntimeCall::Kusama(KusamaRuntimeCall::Whitelist(
+			KusamaWhitelistCall::whitelist_call { call_hash },
+		))
+		| NetworkRuntimeCall::PolkadotAssetHub(PolkadotAssetHubRuntimeCall::Whitelist(
+			PolkadotAssetHubWhitelistCall::whitelist_call { call_hash },
+		))
+		| NetworkRuntimeCall::Polkadot(PolkadotRuntimeCall::Whitelist(
+			PolkadotWhitelistCall::whitelist_call { call_hash },
+		)) => call_hash,
+		_ => return None,
+	};
+	Some((call.network(), call_hash.0))
+}
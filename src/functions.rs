@@ -1,15 +1,110 @@
+use crate::{Error, Network};
+use sp_core::crypto::{AccountId32, Ss58Codec};
 use std::fs;
 
-// Check what the user entered for the proposal. If it is just call data, return it back. Otherwise,
-// we expect a path to a file that contains the call data. Read that in and return it.
-pub(crate) fn get_proposal_bytes(proposal: String) -> Vec<u8> {
+/// Check what the user entered for the proposal. If it is just call data, return it back.
+/// Otherwise, we expect a path to a file that contains the call data. Read that in and return it.
+pub fn get_proposal_bytes(proposal: String) -> Result<Vec<u8>, Error> {
 	let proposal = proposal.as_str();
 	if proposal.starts_with("0x") {
 		// This is just call data
-		hex::decode(proposal.trim_start_matches("0x")).expect("Valid proposal")
+		Ok(hex::decode(proposal.trim_start_matches("0x"))?)
 	} else {
 		// This is a file path
-		let contents = fs::read_to_string(proposal).expect("Should give a valid file path");
-		hex::decode(contents.as_str().trim().trim_start_matches("0x")).expect("Valid proposal")
+		let contents = fs::read_to_string(proposal)
+			.map_err(|source| Error::UnreadableFile { path: proposal.to_string(), source })?;
+		Ok(hex::decode(contents.as_str().trim().trim_start_matches("0x"))?)
+	}
+}
+
+/// Parse a user-provided account, either an SS58 address or a hex-encoded account ID, into an
+/// account ID. SS58 addresses must use the prefix of `network` or the generic Substrate prefix.
+pub fn parse_account(account: &str, network: &Network) -> Result<[u8; 32], Error> {
+	if let Some(account_id) = account.strip_prefix("0x") {
+		return hex::decode(account_id)?.try_into().map_err(|_| {
+			Error::InvalidInput(format!(
+				"The hex account `{account}` must be a 32-byte account ID."
+			))
+		});
+	}
+	let (account_id, format) = AccountId32::from_ss58check_with_version(account).map_err(|e| {
+		Error::InvalidInput(format!("`{account}` is not a valid SS58 address: {e:?}."))
+	})?;
+	let prefix = match network.relay() {
+		Network::Kusama => 2,
+		_ => 0,
+	};
+	if format.prefix() != prefix && format.prefix() != 42 {
+		return Err(Error::InvalidInput(format!(
+			"`{account}` is an address with SS58 prefix {}, but {:?} uses {prefix}.",
+			format.prefix(),
+			network.relay()
+		)));
+	}
+	Ok(account_id.into())
+}
+
+// Read a file, e.g. a runtime blob, into memory.
+pub(crate) fn read_file(path: &str) -> Result<Vec<u8>, Error> {
+	fs::read(path).map_err(|source| Error::UnreadableFile { path: path.to_string(), source })
+}
+
+// Write some output to disk.
+pub(crate) fn write_file(path: &str, contents: impl AsRef<[u8]>) -> Result<(), Error> {
+	fs::write(path, contents).map_err(|source| Error::WriteFile { path: path.to_string(), source })
+}
+
+// Parse a user-provided amount in whole tokens, e.g. `1,000.5`, into the smallest unit of a token
+// with `decimals` decimal places.
+pub(crate) fn parse_amount(amount: &str, decimals: u8) -> Result<u128, Error> {
+	let invalid =
+		|reason: &str| Error::InvalidInput(format!("Invalid amount `{amount}`: {reason}."));
+	let digits = amount.trim().replace([',', '_'], "");
+	let (whole, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+	if whole.is_empty() && fraction.is_empty() {
+		return Err(invalid("it is empty"));
+	}
+	if !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+		return Err(invalid("it should be a decimal number, e.g. `1000.5`"));
+	}
+	if fraction.len() > decimals as usize {
+		return Err(invalid(&format!("the token only has {decimals} decimal places")));
+	}
+
+	let unit = 10u128.pow(decimals as u32);
+	let whole = match whole {
+		"" => 0,
+		_ => whole.parse::<u128>().map_err(|_| invalid("it is too large"))?,
+	};
+	let fraction = match fraction {
+		"" => 0,
+		_ =>
+			fraction.parse::<u128>().map_err(|_| invalid("it is too large"))?
+				* 10u128.pow((decimals as usize - fraction.len()) as u32),
+	};
+	whole
+		.checked_mul(unit)
+		.and_then(|whole| whole.checked_add(fraction))
+		.ok_or_else(|| invalid("it is too large"))
+}
+
+// Format an `amount` in the smallest unit of a token with `decimals` decimal places as whole tokens,
+// e.g. `1,000.5`.
+pub(crate) fn format_amount(amount: u128, decimals: u8) -> String {
+	let unit = 10u128.pow(decimals as u32);
+	let whole = (amount / unit).to_string();
+	let mut grouped = String::new();
+	for (i, digit) in whole.chars().enumerate() {
+		if i > 0 && (whole.len() - i) % 3 == 0 {
+			grouped.push(',');
+		}
+		grouped.push(digit);
+	}
+	let fraction = format!("{:0width$}", amount % unit, width = decimals as usize);
+	let fraction = fraction.trim_end_matches('0');
+	if fraction.is_empty() {
+		grouped
+	} else {
+		format!("{grouped}.{fraction}")
 	}
 }
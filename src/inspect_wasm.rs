@@ -0,0 +1,105 @@
+use crate::*;
+use clap::Parser as ClapParser;
+use metadata_diff::{pallet_index_changes, PalletIndexChange};
+
+/// Report the versions, hashes, and pallets of a runtime Wasm blob.
+#[derive(Debug, ClapParser)]
+pub struct InspectWasmArgs {
+	/// The runtime blob, compressed or not, e.g.
+	/// "./asset-hub-polkadot_runtime-v1002000.compact.compressed.wasm".
+	file: String,
+
+	/// The network whose bundled metadata to compare pallet indices against, e.g.
+	/// `polkadot-asset-hub`. If not provided, it is inferred from the runtime's `spec_name`.
+	#[clap(long = "network", short)]
+	network: Option<String>,
+}
+
+/// The sub-command's "main" function.
+pub fn inspect_wasm(prefs: InspectWasmArgs) -> Result<(), Error> {
+	let file = prefs.file;
+	let invalid = |reason: String| Error::InvalidRuntime { file: file.clone(), reason };
+
+	let blob = read_file(&file)?;
+	let code = wasm::decompress_runtime(&blob).map_err(invalid)?;
+	let version = wasm::runtime_version(&code).map_err(invalid)?;
+	let metadata = wasm::runtime_metadata(&code).map_err(invalid)?;
+	let pallets = wasm::pallets(&metadata);
+
+	let network = match prefs.network {
+		Some(name) => Some(Network::from_name(&name).map_err(|_| {
+			Error::UnsupportedNetwork(String::from(
+				"Networks should be in the form `polkadot-asset-hub`.",
+			))
+		})?),
+		None => Network::from_spec_name(&version.spec_name).ok(),
+	};
+
+	println!("\nFile:                {file}");
+	match &network {
+		Some(network) => println!("Network:             {network:?}"),
+		None => println!("Network:             unknown"),
+	}
+	println!("spec_name:           {}", version.spec_name);
+	println!("impl_name:           {}", version.impl_name);
+	println!("spec_version:        {}", version.spec_version);
+	println!("impl_version:        {}", version.impl_version);
+	println!("transaction_version: {}", version.transaction_version);
+	println!("authoring_version:   {}", version.authoring_version);
+
+	// The blob is what gets uploaded, so its hash is the `code_hash` to authorize.
+	println!(
+		"\nBlob:         {:>9} bytes, blake2_256 0x{} (the `code_hash`)",
+		blob.len(),
+		hex::encode(blake2_256(&blob))
+	);
+	if code.len() == blob.len() {
+		println!("The blob is not compressed.");
+	} else {
+		println!(
+			"Uncompressed: {:>9} bytes, blake2_256 0x{}",
+			code.len(),
+			hex::encode(blake2_256(&code))
+		);
+	}
+
+	let changes = match &network {
+		Some(network) => {
+			let bundled = wasm::decode_metadata(network.bundled_metadata()).map_err(|reason| {
+				Error::InvalidRuntime { file: format!("The bundled {network:?} metadata"), reason }
+			})?;
+			pallet_index_changes(&wasm::pallets(&bundled), &pallets)
+		},
+		None => Vec::new(),
+	};
+
+	println!("\nPallets:\n");
+	for (name, index) in &pallets {
+		let note = changes.iter().find_map(|change| match change {
+			PalletIndexChange::Added { name: n, .. } if n == name => Some(String::from("(new)")),
+			PalletIndexChange::Moved { name: n, from, .. } if n == name =>
+				Some(format!("(moved from {from})")),
+			_ => None,
+		});
+		println!("  {index:>3}  {name} {}", note.unwrap_or_default());
+	}
+	for change in &changes {
+		if let PalletIndexChange::Removed { name, index } = change {
+			println!("    -  {name} (removed, was {index})");
+		}
+	}
+
+	match &network {
+		Some(network) if changes.is_empty() =>
+			println!("\nPallet indices match the bundled {network:?} metadata."),
+		Some(network) => println!(
+			"\nWarning: {} pallet index change(s) relative to the bundled {network:?} metadata. Calls \
+			built with this tool may not decode on this runtime.",
+			changes.len()
+		),
+		None => println!(
+			"\nUnknown `spec_name`; pass `--network` to compare with the bundled metadata."
+		),
+	}
+	Ok(())
+}
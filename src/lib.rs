@@ -0,0 +1,35 @@
+//! Utilities for submitting OpenGov referenda and constructing tedious calls.
+//!
+//! The `opengov-cli` binary is a thin front-end over this library. Use [`ProposalDetails::builder`]
+//! and [`submit_referendum::generate_calls`] to construct the calls for a referendum, or
+//! [`UpgradeDetails::builder`] and the functions in [`build_upgrade`] to construct a batch that
+//! upgrades a Relay Chain and its system parachains.
+
+mod types;
+pub use crate::types::*;
+mod error;
+pub use crate::error::Error;
+mod functions;
+use crate::functions::*;
+pub use crate::functions::{get_proposal_bytes, parse_account};
+pub mod build_spend;
+pub mod build_upgrade;
+pub mod decode;
+pub mod deposits;
+pub mod dynamic;
+pub mod fellowship;
+pub mod inspect_wasm;
+pub mod list_tracks;
+pub mod metadata_diff;
+pub mod node;
+pub mod offline;
+pub mod simulate;
+pub mod submit;
+pub mod submit_referendum;
+pub mod update_metadata;
+pub mod vote;
+pub mod wasm;
+pub mod wrap;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,386 @@
+use crate::*;
+use clap::Parser as ClapParser;
+use parity_scale_codec::Decode;
+
+/// List the OpenGov tracks of each network, with their IDs and origins.
+#[derive(Debug, ClapParser)]
+pub struct ListTracksArgs {
+	/// Only list the tracks of this network. `polkadot` or `kusama`.
+	#[clap(long = "network", short)]
+	network: Option<String>,
+
+	/// Also print each track's deposit, periods, and approval and support curves.
+	#[clap(long = "verbose", short)]
+	verbose: bool,
+}
+
+// The number of rows, after the first, in the tables printed by `print_curves`.
+const CURVE_STEPS: u32 = 10;
+
+// The block time of both Relay Chains, whose block numbers OpenGov uses.
+const BLOCK_TIME_SECONDS: u64 = 6;
+
+/// A referendum track of a Relay Chain's OpenGov, which lives on its Asset Hub.
+pub struct Track {
+	/// The track's ID in the `Referenda` pallet.
+	pub id: u16,
+	/// The track's name in the `Referenda` pallet, e.g. `small_tipper`.
+	pub name: String,
+	/// The origin with which referenda on this track dispatch, if this tool knows it.
+	pub origin: Option<NetworkTrack>,
+	/// How many referenda on this track may be deciding at once.
+	pub max_deciding: u32,
+	/// The deposit that must be placed for a referendum to start deciding, in the smallest unit of
+	/// the native token.
+	pub decision_deposit: u128,
+	/// The minimum number of blocks between submission and deciding.
+	pub prepare_period: u32,
+	/// The number of blocks that a referendum may be deciding before it times out.
+	pub decision_period: u32,
+	/// The number of blocks that a referendum must keep passing to be approved.
+	pub confirm_period: u32,
+	/// The minimum number of blocks between approval and enactment.
+	pub min_enactment_period: u32,
+	/// The share of votes in favour needed to pass, over the decision period.
+	pub min_approval: Curve,
+	/// The share of the total issuance that must vote in favour to pass, over the decision period.
+	pub min_support: Curve,
+}
+
+/// A threshold that falls over a referendum's decision period, as in `pallet_referenda::Curve`.
+/// `Perbill`s are given in parts per billion and `FixedI64`s in billionths.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Curve {
+	/// Linear from `ceil` to `floor` over the first `length` of the period, then flat.
+	LinearDecreasing { length: u32, floor: u32, ceil: u32 },
+	/// From `begin`, falling by `step` every `period` of the period, to no lower than `end`.
+	SteppedDecreasing { begin: u32, end: u32, step: u32, period: u32 },
+	/// `factor / (x + x_offset) + y_offset`, clamped between 0 and 1.
+	Reciprocal { factor: i64, x_offset: i64, y_offset: i64 },
+}
+
+impl Curve {
+	/// The threshold, between 0 and 1, at the fraction `x` (between 0 and 1) of the decision period.
+	pub fn threshold(&self, x: f64) -> f64 {
+		let x = x.clamp(0.0, 1.0);
+		let y = match *self {
+			Curve::LinearDecreasing { length, floor, ceil } => {
+				let (length, floor, ceil) =
+					(per_billion(length), per_billion(floor), per_billion(ceil));
+				let progress = if length == 0.0 { 1.0 } else { x.min(length) / length };
+				ceil - progress * (ceil - floor)
+			},
+			Curve::SteppedDecreasing { begin, end, step, period } => {
+				let (begin, end) = (per_billion(begin), per_billion(end));
+				let (step, period) = (per_billion(step), per_billion(period));
+				let steps = if period == 0.0 { 0.0 } else { (x / period).floor() };
+				(begin - (step * steps).min(begin)).max(end)
+			},
+			Curve::Reciprocal { factor, x_offset, y_offset } => {
+				let denominator = x + x_offset as f64 / 1e9;
+				if denominator <= 0.0 {
+					1.0
+				} else {
+					(factor as f64 / 1e9) / denominator + y_offset as f64 / 1e9
+				}
+			},
+		};
+		y.clamp(0.0, 1.0)
+	}
+}
+
+impl std::fmt::Display for Curve {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"{:.2}% falling to {:.2}% over the decision period",
+			self.threshold(0.0) * 100.0,
+			self.threshold(1.0) * 100.0
+		)
+	}
+}
+
+/// The sub-command's "main" function.
+pub fn list_tracks(prefs: ListTracksArgs) -> Result<(), Error> {
+	let networks = match prefs.network {
+		Some(name) => match Network::from_name(&name) {
+			Ok(network @ (Network::Polkadot | Network::Kusama)) => vec![network],
+			_ =>
+				return Err(Error::UnsupportedNetwork(String::from(
+					"`network` must be `polkadot` or `kusama`.",
+				))),
+		},
+		None => vec![Network::Polkadot, Network::Kusama],
+	};
+
+	for network in networks {
+		println!("\n{network:?} (on Asset Hub):\n");
+		if !prefs.verbose {
+			println!("  {:>3}  {:<22} Origin", "ID", "Track");
+		}
+		for track in tracks(&network)? {
+			let origin = match &track.origin {
+				Some(origin) => origin.name(),
+				None => String::from("unknown"),
+			};
+			if prefs.verbose {
+				print_track(&network, &track, None);
+				println!("  Origin:                {origin}");
+				print_curves(&track);
+				println!();
+			} else {
+				println!("  {:>3}  {:<22} {origin}", track.id, track.name);
+			}
+		}
+	}
+	Ok(())
+}
+
+/// Every track of the Relay Chain `network`'s OpenGov, in ID order, from the `Referenda` pallet's
+/// `Tracks` constant in the bundled Asset Hub metadata.
+pub fn tracks(network: &Network) -> Result<Vec<Track>, Error> {
+	let asset_hub = match network {
+		Network::Polkadot => Network::PolkadotAssetHub,
+		Network::Kusama => Network::KusamaAssetHub,
+		_ =>
+			return Err(Error::UnsupportedNetwork(String::from(
+				"`network` must be `polkadot` or `kusama`.",
+			))),
+	};
+	let invalid = |reason: String| Error::InvalidRuntime {
+		file: format!("The bundled {asset_hub:?} metadata"),
+		reason,
+	};
+	let metadata = wasm::decode_metadata(asset_hub.bundled_metadata()).map_err(invalid)?;
+	let constant = metadata
+		.pallet_by_name("Referenda")
+		.and_then(|pallet| pallet.constant_by_name("Tracks"))
+		.ok_or_else(|| invalid(String::from("has no `Referenda::Tracks` constant")))?;
+	let undecodable =
+		|e: parity_scale_codec::Error| invalid(format!("has `Tracks` that do not decode: {e}"));
+
+	let mut tracks = match network {
+		Network::Polkadot => {
+			use polkadot_asset_hub::runtime_types::pallet_referenda::types::{
+				Curve as C, TrackDetails,
+			};
+			let curve = |curve: C| match curve {
+				C::LinearDecreasing { length, floor, ceil } =>
+					Curve::LinearDecreasing { length: length.0, floor: floor.0, ceil: ceil.0 },
+				C::SteppedDecreasing { begin, end, step, period } => Curve::SteppedDecreasing {
+					begin: begin.0,
+					end: end.0,
+					step: step.0,
+					period: period.0,
+				},
+				C::Reciprocal { factor, x_offset, y_offset } => Curve::Reciprocal {
+					factor: factor.0,
+					x_offset: x_offset.0,
+					y_offset: y_offset.0,
+				},
+			};
+			Vec::<(u16, TrackDetails<u128, u32, String>)>::decode(&mut constant.value())
+				.map_err(undecodable)?
+				.into_iter()
+				.map(|(id, details)| Track {
+					id,
+					name: details.name,
+					origin: None,
+					max_deciding: details.max_deciding,
+					decision_deposit: details.decision_deposit,
+					prepare_period: details.prepare_period,
+					decision_period: details.decision_period,
+					confirm_period: details.confirm_period,
+					min_enactment_period: details.min_enactment_period,
+					min_approval: curve(details.min_approval),
+					min_support: curve(details.min_support),
+				})
+				.collect::<Vec<_>>()
+		},
+		_ => {
+			use kusama_asset_hub::runtime_types::pallet_referenda::types::{
+				Curve as C, TrackDetails,
+			};
+			let curve = |curve: C| match curve {
+				C::LinearDecreasing { length, floor, ceil } =>
+					Curve::LinearDecreasing { length: length.0, floor: floor.0, ceil: ceil.0 },
+				C::SteppedDecreasing { begin, end, step, period } => Curve::SteppedDecreasing {
+					begin: begin.0,
+					end: end.0,
+					step: step.0,
+					period: period.0,
+				},
+				C::Reciprocal { factor, x_offset, y_offset } => Curve::Reciprocal {
+					factor: factor.0,
+					x_offset: x_offset.0,
+					y_offset: y_offset.0,
+				},
+			};
+			Vec::<(u16, TrackDetails<u128, u32, String>)>::decode(&mut constant.value())
+				.map_err(undecodable)?
+				.into_iter()
+				.map(|(id, details)| Track {
+					id,
+					name: details.name,
+					origin: None,
+					max_deciding: details.max_deciding,
+					decision_deposit: details.decision_deposit,
+					prepare_period: details.prepare_period,
+					decision_period: details.decision_period,
+					confirm_period: details.confirm_period,
+					min_enactment_period: details.min_enactment_period,
+					min_approval: curve(details.min_approval),
+					min_support: curve(details.min_support),
+				})
+				.collect::<Vec<_>>()
+		},
+	};
+
+	for track in &mut tracks {
+		// Names are padded with zeros to a fixed length.
+		track.name = String::from(track.name.trim_end_matches('\0'));
+		track.origin = origin_by_name(network, &track.name)?;
+	}
+	Ok(tracks)
+}
+
+/// The track of the Relay Chain `network` whose referenda dispatch with `origin`.
+pub fn track_for(network: &Network, origin: &NetworkTrack) -> Result<Track, Error> {
+	tracks(network)?
+		.into_iter()
+		.find(|track| track_key(&track.name) == track_key(&origin.name()))
+		.ok_or_else(|| {
+			Error::UnsupportedTrack(format!(
+				"{network:?} has no track for the {} origin.",
+				origin.name()
+			))
+		})
+}
+
+/// The earliest that a referendum on `track` could enact, given the requested `dispatch` time, as
+/// an optional block number and a number of blocks after submission. It enacts at whichever of the
+/// two is later.
+///
+/// This assumes that the decision deposit is placed right away and that the referendum passes as
+/// soon as it starts deciding: after the prepare period, it must pass for the whole confirm period,
+/// and is then enacted no sooner than the minimum enactment period.
+pub fn earliest_enactment(track: &Track, dispatch: &DispatchTimeWrapper) -> (Option<u32>, u32) {
+	let approval = track.prepare_period.saturating_add(track.confirm_period);
+	match dispatch {
+		DispatchTimeWrapper::At(block) =>
+			(Some(*block), approval.saturating_add(track.min_enactment_period)),
+		DispatchTimeWrapper::After(blocks) =>
+			(None, approval.saturating_add((*blocks).max(track.min_enactment_period))),
+	}
+}
+
+/// Print the parameters of `track` on the Relay Chain `network`, and when a referendum on it could
+/// enact at the earliest given `dispatch`, if any.
+pub fn print_track(network: &Network, track: &Track, dispatch: Option<&DispatchTimeWrapper>) {
+	let (symbol, decimals) = network.token();
+	println!("Track {} ({}) on {network:?}:", track.name, track.id);
+	println!(
+		"  Decision deposit:      {} {symbol}",
+		format_amount(track.decision_deposit, decimals)
+	);
+	println!("  Max deciding:          {}", track.max_deciding);
+	println!("  Prepare period:        {}", format_blocks(track.prepare_period));
+	println!("  Decision period:       {}", format_blocks(track.decision_period));
+	println!("  Confirm period:        {}", format_blocks(track.confirm_period));
+	println!("  Min enactment period:  {}", format_blocks(track.min_enactment_period));
+	println!("  Min approval:          {}", track.min_approval);
+	println!("  Min support:           {}", track.min_support);
+	match dispatch.map(|dispatch| earliest_enactment(track, dispatch)) {
+		Some((None, after)) =>
+			println!("  Earliest enactment:    {} after submission", format_blocks(after)),
+		Some((Some(at), after)) => println!(
+			"  Earliest enactment:    block {at}, or {} after submission if that is later",
+			format_blocks(after)
+		),
+		None => {},
+	}
+}
+
+/// Print the approval and support thresholds of `track` over its decision period.
+pub fn print_curves(track: &Track) {
+	println!(
+		"
+  {:>24}  {:>9}  {:>9}",
+		"Time deciding", "Approval", "Support"
+	);
+	for step in 0..=CURVE_STEPS {
+		let x = step as f64 / CURVE_STEPS as f64;
+		let blocks = (track.decision_period as f64 * x) as u32;
+		println!(
+			"  {:>24}  {:>8.2}%  {:>8.2}%",
+			format_blocks(blocks),
+			track.min_approval.threshold(x) * 100.0,
+			track.min_support.threshold(x) * 100.0
+		);
+	}
+}
+
+/// Every origin of the Relay Chain `network`'s OpenGov: `Root`, then each variant of the
+/// generated `pallet_custom_origins::Origin` enum, in index order.
+pub fn origins(network: &Network) -> Result<Vec<NetworkTrack>, Error> {
+	// The variants have no fields, so each is encoded as just its index.
+	let indices = 0..=u8::MAX;
+	match network {
+		Network::Polkadot => Ok(std::iter::once(NetworkTrack::PolkadotRoot)
+			.chain(indices.filter_map(|i| {
+				PolkadotAssetHubOpenGovOrigin::decode(&mut &[i][..])
+					.ok()
+					.map(NetworkTrack::Polkadot)
+			}))
+			.collect()),
+		Network::Kusama => Ok(std::iter::once(NetworkTrack::KusamaRoot)
+			.chain(indices.filter_map(|i| {
+				KusamaAssetHubOpenGovOrigin::decode(&mut &[i][..]).ok().map(NetworkTrack::Kusama)
+			}))
+			.collect()),
+		_ => Err(Error::UnsupportedNetwork(String::from(
+			"`network` must be `polkadot` or `kusama`.",
+		))),
+	}
+}
+
+/// The origin of the Relay Chain `network` called `name`, e.g. `small-tipper`, `small_tipper`, or
+/// `SmallTipper`. Case, dashes, and underscores are ignored.
+pub fn origin_by_name(network: &Network, name: &str) -> Result<Option<NetworkTrack>, Error> {
+	Ok(origins(network)?.into_iter().find(|origin| track_key(&origin.name()) == track_key(name)))
+}
+
+/// The origin of the track of the Relay Chain `network` called `name`, e.g. `small-tipper`,
+/// `small_tipper`, or `SmallTipper`. Case, dashes, and underscores are ignored. Unlike
+/// `origin_by_name`, origins without a track, like Kusama's `Fellows`, are not found.
+pub fn track_by_name(network: &Network, name: &str) -> Result<Option<NetworkTrack>, Error> {
+	Ok(tracks(network)?
+		.into_iter()
+		.find(|track| track_key(&track.name) == track_key(name))
+		.and_then(|track| track.origin))
+}
+
+// A track or origin name with case, dashes, and underscores removed, for comparison.
+fn track_key(name: &str) -> String {
+	name.to_ascii_lowercase().replace(['-', '_', ' '], "")
+}
+
+// A `Perbill` as a fraction.
+fn per_billion(parts: u32) -> f64 {
+	parts as f64 / 1e9
+}
+
+// A number of blocks and roughly how long they take, e.g. `14,400 blocks (1 day)`.
+fn format_blocks(blocks: u32) -> String {
+	let seconds = blocks as u64 * BLOCK_TIME_SECONDS;
+	let (days, hours, minutes) = (seconds / 86_400, seconds % 86_400 / 3_600, seconds % 3_600 / 60);
+	let plural = |n: u64, unit: &str| format!("{n} {unit}{}", if n == 1 { "" } else { "s" });
+	let time = match (days, hours, minutes) {
+		(0, 0, minutes) => plural(minutes, "minute"),
+		(0, hours, 0) => plural(hours, "hour"),
+		(0, hours, minutes) => format!("{} {}", plural(hours, "hour"), plural(minutes, "minute")),
+		(days, 0, _) => plural(days, "day"),
+		(days, hours, _) => format!("{} {}", plural(days, "day"), plural(hours, "hour")),
+	};
+	format!("{} blocks ({time})", format_amount(blocks as u128, 0))
+}
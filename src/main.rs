@@ -1,28 +1,55 @@
-mod types;
-use crate::types::*;
-mod functions;
-use crate::functions::*;
-mod build_upgrade;
-use crate::build_upgrade::{build_upgrade, UpgradeArgs};
-mod submit_referendum;
-use crate::submit_referendum::{submit_referendum, ReferendumArgs};
 use clap::Parser as ClapParser;
-
-#[cfg(test)]
-mod tests;
+use opengov_cli::{
+	build_spend::{build_spend, SpendArgs},
+	build_upgrade::{build_upgrade, UpgradeArgs},
+	decode::{decode, DecodeArgs},
+	fellowship::{fellowship, FellowshipArgs},
+	inspect_wasm::{inspect_wasm, InspectWasmArgs},
+	list_tracks::{list_tracks, ListTracksArgs},
+	metadata_diff::{metadata_diff, MetadataDiffArgs},
+	offline::{assemble, AssembleArgs},
+	simulate::{simulate, SimulateArgs},
+	submit_referendum::{submit_referendum, ReferendumArgs},
+	update_metadata::{update_metadata, UpdateMetadataArgs},
+	vote::{vote, VoteArgs},
+};
 
 /// Utilities for submitting OpenGov referenda and constructing tedious calls.
 #[derive(Debug, ClapParser)]
 enum Command {
 	BuildUpgrade(UpgradeArgs),
+	BuildSpend(SpendArgs),
 	SubmitReferendum(ReferendumArgs),
+	Assemble(AssembleArgs),
+	Simulate(SimulateArgs),
+	Vote(VoteArgs),
+	ListTracks(ListTracksArgs),
+	Fellowship(FellowshipArgs),
+	Decode(DecodeArgs),
+	InspectWasm(InspectWasmArgs),
+	MetadataDiff(MetadataDiffArgs),
+	UpdateMetadata(UpdateMetadataArgs),
 }
 
 #[tokio::main]
 async fn main() {
 	let args = Command::parse();
-	match args {
+	let result = match args {
 		Command::BuildUpgrade(prefs) => build_upgrade(prefs).await,
+		Command::BuildSpend(prefs) => build_spend(prefs).await,
 		Command::SubmitReferendum(prefs) => submit_referendum(prefs).await,
+		Command::Assemble(prefs) => assemble(prefs),
+		Command::Simulate(prefs) => simulate(prefs).await,
+		Command::Vote(prefs) => vote(prefs),
+		Command::ListTracks(prefs) => list_tracks(prefs),
+		Command::Fellowship(prefs) => fellowship(prefs),
+		Command::Decode(prefs) => decode(prefs),
+		Command::InspectWasm(prefs) => inspect_wasm(prefs),
+		Command::MetadataDiff(prefs) => metadata_diff(prefs),
+		Command::UpdateMetadata(prefs) => update_metadata(prefs).await,
+	};
+	if let Err(e) = result {
+		eprintln!("\nError: {e}");
+		std::process::exit(e.exit_code());
 	}
 }
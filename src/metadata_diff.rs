@@ -0,0 +1,321 @@
+use crate::*;
+use clap::Parser as ClapParser;
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef};
+use subxt::Metadata;
+
+/// Compare the bundled metadata with that of a new runtime, for the pallets and calls this tool
+/// constructs.
+#[derive(Debug, ClapParser)]
+pub struct MetadataDiffArgs {
+	/// The new runtime: either a Wasm blob (compressed or not) or a SCALE-encoded metadata file.
+	file: String,
+
+	/// The network whose bundled metadata to compare against, e.g. `polkadot-asset-hub`. If not
+	/// provided, it is inferred from the `spec_name` in the new runtime's metadata.
+	#[clap(long = "network", short)]
+	network: Option<String>,
+
+	/// Compare against this Wasm blob or metadata file instead of the bundled metadata.
+	#[clap(long = "against")]
+	against: Option<String>,
+}
+
+/// The pallets whose calls this tool constructs, and so whose calls are compared.
+pub const CONSTRUCTED_PALLETS: [&str; 13] = [
+	"System",
+	"Utility",
+	"Preimage",
+	"Referenda",
+	"FellowshipReferenda",
+	"FellowshipCollective",
+	"ConvictionVoting",
+	"Whitelist",
+	"Treasury",
+	"Multisig",
+	"Proxy",
+	"PolkadotXcm",
+	"XcmPallet",
+];
+
+// How many levels of nested types to compare in call arguments.
+const SIGNATURE_DEPTH: usize = 4;
+
+/// How a pallet's index differs between the bundled metadata and a runtime.
+#[derive(Debug, PartialEq)]
+pub enum PalletIndexChange {
+	/// The pallet is not in the bundled metadata.
+	Added { name: String, index: u8 },
+	/// The pallet is in the bundled metadata but not in the runtime.
+	Removed { name: String, index: u8 },
+	/// The pallet is in both, at different indices.
+	Moved { name: String, from: u8, to: u8 },
+}
+
+/// How a call in one of the `CONSTRUCTED_PALLETS` differs between the bundled metadata and a
+/// runtime.
+#[derive(Debug, PartialEq)]
+pub enum CallChange {
+	/// The call is not in the bundled metadata.
+	Added { pallet: String, call: String, index: u8 },
+	/// The call is in the bundled metadata but not in the runtime.
+	Removed { pallet: String, call: String, index: u8 },
+	/// The call is in both, at different indices.
+	Moved { pallet: String, call: String, from: u8, to: u8 },
+	/// The names or types of some of the call's arguments differ.
+	ArgumentsChanged { pallet: String, call: String, arguments: Vec<String> },
+}
+
+/// Everything that differs between two versions of a runtime's metadata that could make this tool
+/// construct wrong call data.
+#[derive(Debug, Default, PartialEq)]
+pub struct MetadataDiff {
+	pub pallets: Vec<PalletIndexChange>,
+	pub calls: Vec<CallChange>,
+}
+
+impl MetadataDiff {
+	/// Whether the metadata is the same, as far as this tool is concerned.
+	pub fn is_empty(&self) -> bool {
+		self.pallets.is_empty() && self.calls.is_empty()
+	}
+}
+
+/// The sub-command's "main" function.
+pub fn metadata_diff(prefs: MetadataDiffArgs) -> Result<(), Error> {
+	let new = load_metadata(&prefs.file)?;
+
+	let (old, old_name) = match prefs.against {
+		Some(against) => (load_metadata(&against)?, against),
+		None => {
+			let network = match prefs.network {
+				Some(name) => Network::from_name(&name).map_err(|_| {
+					Error::UnsupportedNetwork(String::from(
+						"Networks should be in the form `polkadot-asset-hub`.",
+					))
+				})?,
+				None => wasm::metadata_runtime_version(&new)
+					.and_then(|version| Network::from_spec_name(&version.spec_name).ok())
+					.ok_or_else(|| {
+						Error::UnsupportedNetwork(format!(
+							"Could not tell which network {} is for. Pass `--network`.",
+							prefs.file
+						))
+					})?,
+			};
+			let bundled = wasm::decode_metadata(network.bundled_metadata()).map_err(|reason| {
+				Error::InvalidRuntime { file: format!("The bundled {network:?} metadata"), reason }
+			})?;
+			(bundled, format!("the bundled {network:?} metadata"))
+		},
+	};
+
+	let diff = diff_metadata(&old, &new);
+	println!("\nComparing {} with {old_name}.\n", prefs.file);
+	if diff.is_empty() {
+		println!(
+			"No changes to pallet indices or to the calls of {}.",
+			CONSTRUCTED_PALLETS.join(", ")
+		);
+		return Ok(());
+	}
+
+	print_metadata_diff(&diff);
+	println!(
+		"Call data built from the bundled metadata may be wrong for the new runtime. Update the \
+		files in `metadata/` with `update-metadata` and rebuild."
+	);
+	Ok(())
+}
+
+/// Print the changes in `diff`, one per line.
+pub fn print_metadata_diff(diff: &MetadataDiff) {
+	if !diff.pallets.is_empty() {
+		println!("Pallets:");
+		for change in &diff.pallets {
+			match change {
+				PalletIndexChange::Added { name, index } => println!("  + {name} ({index})"),
+				PalletIndexChange::Removed { name, index } => println!("  - {name} ({index})"),
+				PalletIndexChange::Moved { name, from, to } =>
+					println!("  ~ {name} moved from {from} to {to}"),
+			}
+		}
+		println!();
+	}
+	if !diff.calls.is_empty() {
+		println!("Calls:");
+		for change in &diff.calls {
+			match change {
+				CallChange::Added { pallet, call, index } =>
+					println!("  + {pallet}.{call} ({index})"),
+				CallChange::Removed { pallet, call, index } =>
+					println!("  - {pallet}.{call} ({index})"),
+				CallChange::Moved { pallet, call, from, to } =>
+					println!("  ~ {pallet}.{call} moved from {from} to {to}"),
+				CallChange::ArgumentsChanged { pallet, call, arguments } =>
+					println!("  ~ {pallet}.{call} arguments changed: {}", arguments.join(", ")),
+			}
+		}
+		println!();
+	}
+}
+
+/// Read metadata from `path`, which is either a runtime Wasm blob (compressed or not) or a
+/// SCALE-encoded metadata file such as those in `metadata/`.
+pub fn load_metadata(path: &str) -> Result<Metadata, Error> {
+	let invalid = |reason: String| Error::InvalidRuntime { file: String::from(path), reason };
+	let bytes = read_file(path)?;
+	let code = wasm::decompress_runtime(&bytes).map_err(invalid)?;
+	if code.starts_with(b"\0asm") {
+		wasm::runtime_metadata(&code).map_err(invalid)
+	} else {
+		wasm::decode_metadata(&bytes).map_err(invalid)
+	}
+}
+
+/// Compare the `old` metadata (usually the bundled one) with the `new`.
+pub fn diff_metadata(old: &Metadata, new: &Metadata) -> MetadataDiff {
+	let pallets = pallet_index_changes(&wasm::pallets(old), &wasm::pallets(new));
+
+	let mut calls = Vec::new();
+	for name in CONSTRUCTED_PALLETS {
+		// Pallets that were added or removed are already reported above.
+		let (Some(old_pallet), Some(new_pallet)) =
+			(old.pallet_by_name(name), new.pallet_by_name(name))
+		else {
+			continue;
+		};
+		let old_calls = old_pallet.call_variants().unwrap_or_default();
+		let new_calls = new_pallet.call_variants().unwrap_or_default();
+		let pallet = String::from(name);
+
+		for new_call in new_calls {
+			let call = new_call.name.clone();
+			let Some(old_call) = old_calls.iter().find(|c| c.name == new_call.name) else {
+				calls.push(CallChange::Added {
+					pallet: pallet.clone(),
+					call,
+					index: new_call.index,
+				});
+				continue;
+			};
+			if old_call.index != new_call.index {
+				calls.push(CallChange::Moved {
+					pallet: pallet.clone(),
+					call: call.clone(),
+					from: old_call.index,
+					to: new_call.index,
+				});
+			}
+			let arguments =
+				changed_arguments((old.types(), &old_call.fields), (new.types(), &new_call.fields));
+			if !arguments.is_empty() {
+				calls.push(CallChange::ArgumentsChanged {
+					pallet: pallet.clone(),
+					call,
+					arguments,
+				});
+			}
+		}
+		for old_call in old_calls {
+			if !new_calls.iter().any(|c| c.name == old_call.name) {
+				calls.push(CallChange::Removed {
+					pallet: pallet.clone(),
+					call: old_call.name.clone(),
+					index: old_call.index,
+				});
+			}
+		}
+	}
+
+	MetadataDiff { pallets, calls }
+}
+
+/// Compare the `(name, index)` pallets of the bundled metadata with those of a runtime.
+pub fn pallet_index_changes(
+	bundled: &[(String, u8)],
+	runtime: &[(String, u8)],
+) -> Vec<PalletIndexChange> {
+	let mut changes = Vec::new();
+	for (name, index) in runtime {
+		match bundled.iter().find(|(n, _)| n == name) {
+			None => changes.push(PalletIndexChange::Added { name: name.clone(), index: *index }),
+			Some((_, from)) if from != index => changes.push(PalletIndexChange::Moved {
+				name: name.clone(),
+				from: *from,
+				to: *index,
+			}),
+			Some(_) => {},
+		}
+	}
+	for (name, index) in bundled {
+		if !runtime.iter().any(|(n, _)| n == name) {
+			changes.push(PalletIndexChange::Removed { name: name.clone(), index: *index });
+		}
+	}
+	changes
+}
+
+// The names of the arguments that differ, by position, between two versions of a call.
+fn changed_arguments(
+	(old_types, old_fields): (&PortableRegistry, &[Field<PortableForm>]),
+	(new_types, new_fields): (&PortableRegistry, &[Field<PortableForm>]),
+) -> Vec<String> {
+	let mut changed = Vec::new();
+	for i in 0..old_fields.len().max(new_fields.len()) {
+		let old = old_fields.get(i);
+		let new = new_fields.get(i);
+		let same = match (old, new) {
+			(Some(old), Some(new)) =>
+				old.name == new.name
+					&& type_signature(old_types, old.ty.id, SIGNATURE_DEPTH)
+						== type_signature(new_types, new.ty.id, SIGNATURE_DEPTH),
+			_ => false,
+		};
+		if !same {
+			let name = new.or(old).and_then(|f| f.name.clone()).unwrap_or_else(|| format!("{i}"));
+			changed.push(name);
+		}
+	}
+	changed
+}
+
+// A description of the shape of type `id`, `depth` levels deep, for comparing types across
+// metadata (where type IDs differ). `RuntimeCall`s are not expanded, since changes to the pallets
+// they contain are reported separately.
+fn type_signature(types: &PortableRegistry, id: u32, depth: usize) -> String {
+	let Some(ty) = types.resolve(id) else { return String::from("?") };
+	let path = ty.path.segments.last().cloned().unwrap_or_default();
+	if depth == 0 || path == "RuntimeCall" {
+		return path;
+	}
+
+	let inner = |id: u32| type_signature(types, id, depth - 1);
+	let fields = |fields: &[Field<PortableForm>]| {
+		fields
+			.iter()
+			.map(|f| match &f.name {
+				Some(name) => format!("{name}: {}", inner(f.ty.id)),
+				None => inner(f.ty.id),
+			})
+			.collect::<Vec<_>>()
+			.join(", ")
+	};
+	match &ty.type_def {
+		TypeDef::Composite(c) => format!("{path}{{{}}}", fields(&c.fields)),
+		TypeDef::Variant(v) => {
+			let variants = v
+				.variants
+				.iter()
+				.map(|v| format!("{} {}({})", v.index, v.name, fields(&v.fields)))
+				.collect::<Vec<_>>();
+			format!("{path}<{}>", variants.join(" | "))
+		},
+		TypeDef::Sequence(s) => format!("[{}]", inner(s.type_param.id)),
+		TypeDef::Array(a) => format!("[{}; {}]", inner(a.type_param.id), a.len),
+		TypeDef::Tuple(t) =>
+			format!("({})", t.fields.iter().map(|f| inner(f.id)).collect::<Vec<_>>().join(", ")),
+		TypeDef::Primitive(p) => format!("{p:?}").to_ascii_lowercase(),
+		TypeDef::Compact(c) => format!("Compact<{}>", inner(c.type_param.id)),
+		TypeDef::BitSequence(_) => String::from("BitSequence"),
+	}
+}
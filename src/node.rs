@@ -0,0 +1,77 @@
+use crate::*;
+use parity_scale_codec::Decode;
+use subxt::backend::{legacy::LegacyRpcMethods, rpc::RpcClient};
+use subxt::{OnlineClient, PolkadotConfig};
+
+/// A connection to the RPC endpoint of a node, for the few queries that this tool makes.
+pub struct Node {
+	url: String,
+	client: RpcClient,
+	rpc: LegacyRpcMethods<PolkadotConfig>,
+}
+
+impl Node {
+	/// Connect to the node at `url`, e.g. `ws://localhost:9944`.
+	pub async fn connect(url: &str) -> Result<Self, Error> {
+		let client = RpcClient::from_insecure_url(url)
+			.await
+			.map_err(|e| Error::Rpc { url: String::from(url), reason: e.to_string() })?;
+		Ok(Self { url: String::from(url), rpc: LegacyRpcMethods::new(client.clone()), client })
+	}
+
+	/// The URL of the node.
+	pub fn url(&self) -> &str {
+		&self.url
+	}
+
+	/// A `subxt` client of the node, to submit extrinsics with. This fetches the node's metadata.
+	pub async fn online_client(&self) -> Result<OnlineClient<PolkadotConfig>, Error> {
+		OnlineClient::from_rpc_client(self.client.clone())
+			.await
+			.map_err(|e| self.error(e.to_string()))
+	}
+
+	/// Call the runtime API function `method` with SCALE-encoded `data` at the best block, and
+	/// return its SCALE-encoded result.
+	pub async fn state_call(&self, method: &str, data: &[u8]) -> Result<Vec<u8>, Error> {
+		self.rpc.state_call(method, Some(data), None).await.map_err(|e| self.error(e.to_string()))
+	}
+
+	/// The value under the storage item `pallet::item`, which is not a map, at the best block.
+	/// `None` if nothing is stored there.
+	pub async fn storage_value(&self, pallet: &str, item: &str) -> Result<Option<Vec<u8>>, Error> {
+		let key =
+			[sp_core::twox_128(pallet.as_bytes()), sp_core::twox_128(item.as_bytes())].concat();
+		self.rpc.state_get_storage(&key, None).await.map_err(|e| self.error(e.to_string()))
+	}
+
+	/// The network that the node runs, from the `spec_name` of its runtime.
+	pub async fn network(&self) -> Result<Network, Error> {
+		let version = self.state_call("Core_version", &[]).await?;
+		let version = wasm::decode_runtime_version(&mut &version[..])
+			.map_err(|e| self.error(format!("returned a version that does not decode: {e}")))?;
+		Network::from_spec_name(&version.spec_name).map_err(|_| {
+			Error::UnsupportedNetwork(format!(
+				"The node at {} runs `{}`, which this tool does not support.",
+				self.url, version.spec_name
+			))
+		})
+	}
+
+	/// The number of referenda ever submitted to the `pallet` (`Referenda` or
+	/// `FellowshipReferenda`) of the node's chain, which is the index of the next one.
+	pub async fn referendum_count(&self, pallet: &str) -> Result<u32, Error> {
+		match self.storage_value(pallet, "ReferendumCount").await? {
+			Some(count) => u32::decode(&mut &count[..]).map_err(|e| {
+				self.error(format!("returned a `ReferendumCount` that does not decode: {e}"))
+			}),
+			// The storage item defaults to zero.
+			None => Ok(0),
+		}
+	}
+
+	// An `Error::Rpc` for a request to this node that failed for `reason`.
+	fn error(&self, reason: String) -> Error {
+		Error::Rpc { url: self.url.clone(), reason }
+	}
+}
@@ -0,0 +1,410 @@
+use crate::*;
+use clap::Parser as ClapParser;
+use parity_scale_codec::{Compact, Encode};
+use scale_info::{PortableRegistry, TypeDef};
+use sp_core::Pair as _;
+use std::collections::BTreeMap;
+
+/// Assemble a signed extrinsic from a payload exported with `--export-payloads` and the signature
+/// that an offline signer made of it.
+#[derive(Debug, ClapParser)]
+pub struct AssembleArgs {
+	/// A payload file written by `submit-referendum --export-payloads`.
+	#[clap(long = "payload")]
+	payload: String,
+
+	/// The signature of the payload, in hex. Either the 64-byte signature itself or, as Polkadot
+	/// Vault gives it, prefixed with the byte of its key type.
+	#[clap(long = "signature")]
+	signature: String,
+}
+
+/// The key type of the account that signs an exported payload.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Crypto {
+	Ed25519,
+	Sr25519,
+}
+
+impl Crypto {
+	/// Parse a user-provided key type, `sr25519` or `ed25519`.
+	pub fn from_name(name: &str) -> Result<Crypto, Error> {
+		match name.to_ascii_lowercase().as_str() {
+			"sr25519" => Ok(Crypto::Sr25519),
+			"ed25519" => Ok(Crypto::Ed25519),
+			_ => Err(Error::InvalidInput(format!(
+				"The key type must be `sr25519` or `ed25519`, not `{name}`."
+			))),
+		}
+	}
+
+	/// The name of the key type, as accepted by `from_name`.
+	pub fn name(&self) -> &'static str {
+		match self {
+			Crypto::Ed25519 => "ed25519",
+			Crypto::Sr25519 => "sr25519",
+		}
+	}
+
+	// The index of the key type in `sp_runtime::MultiSignature`, which Polkadot Vault also uses.
+	fn index(&self) -> u8 {
+		match self {
+			Crypto::Ed25519 => 0,
+			Crypto::Sr25519 => 1,
+		}
+	}
+}
+
+/// What the signer of a transaction must know about the chain and its account, beyond the call.
+/// Read, per network, from the JSON given with `--tx-context`.
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransactionContext {
+	/// The hash of the chain's genesis block.
+	pub genesis_hash: String,
+	/// The hash of a recent block, from which the transaction is valid for `era_period` blocks.
+	/// Immortal if not given, which is valid until the nonce is used.
+	pub block_hash: Option<String>,
+	/// The number of the block with `block_hash`.
+	pub block_number: Option<u32>,
+	/// How many blocks a mortal transaction is valid for. Rounded up to a power of two. Defaults to
+	/// 64.
+	pub era_period: Option<u64>,
+	/// The signer's nonce on the chain. Further calls on the same chain use the nonces after it.
+	pub nonce: u32,
+	/// A tip for the block author, in the smallest unit of the native token.
+	#[serde(default)]
+	pub tip: u128,
+	/// The `spec_version` of the chain's runtime. Defaults to that of the bundled metadata.
+	pub spec_version: Option<u32>,
+	/// The `transaction_version` of the chain's runtime. Defaults to that of the bundled metadata.
+	pub transaction_version: Option<u32>,
+}
+
+/// Everything that an offline signer needs to sign one call, and that `assemble` needs to turn
+/// its signature into an extrinsic.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignerPayload {
+	/// The network on which to submit the extrinsic.
+	pub network: Network,
+	/// The account ID of the signer.
+	pub signer: [u8; 32],
+	/// The key type of the signer.
+	pub crypto: Crypto,
+	/// The encoded call.
+	pub call: Vec<u8>,
+	/// The explicit data of the transaction extensions, which is part of the extrinsic.
+	pub extra: Vec<u8>,
+	/// The implicit data of the transaction extensions, which is signed but left out of the
+	/// extrinsic.
+	pub implicit: Vec<u8>,
+	/// The hash of the network's genesis block.
+	pub genesis_hash: [u8; 32],
+}
+
+/// The sub-command's "main" function.
+pub fn assemble(prefs: AssembleArgs) -> Result<(), Error> {
+	let contents = String::from_utf8(read_file(&prefs.payload)?).map_err(|_| {
+		Error::InvalidInput(format!("The payload file {} is not valid UTF-8.", prefs.payload))
+	})?;
+	let payload = SignerPayload::from_json(&contents)?;
+	let signature = hex::decode(prefs.signature.trim().trim_start_matches("0x"))?;
+	let extrinsic = payload.assemble(&signature)?;
+	println!("\nSigned extrinsic to broadcast on {:?}:", payload.network);
+	println!("0x{}", hex::encode(extrinsic));
+	Ok(())
+}
+
+/// Parse the `--tx-context` JSON, either the JSON itself or a file path that contains it. It is an
+/// object from the name of each network, e.g. `polkadot-asset-hub`, to its `TransactionContext`.
+pub fn load_contexts(input: &str) -> Result<Vec<(Network, TransactionContext)>, Error> {
+	let contents = if input.trim_start().starts_with('{') {
+		String::from(input)
+	} else {
+		String::from_utf8(read_file(input)?).map_err(|_| {
+			Error::InvalidInput(format!("The transaction context file {input} is not valid UTF-8."))
+		})?
+	};
+	let contexts: BTreeMap<String, TransactionContext> = serde_json::from_str(&contents)
+		.map_err(|e| Error::InvalidInput(format!("Invalid transaction context: {e}")))?;
+	contexts
+		.into_iter()
+		.map(|(name, context)| {
+			let network = Network::from_name(&name).map_err(|_| {
+				Error::UnsupportedNetwork(format!("`{name}` in the transaction context."))
+			})?;
+			Ok((network, context))
+		})
+		.collect()
+}
+
+/// Build a `SignerPayload` for each of the encoded `calls`, in order, from the context of its
+/// network. Calls on the same network use consecutive nonces.
+pub fn signer_payloads(
+	calls: Vec<(Network, Vec<u8>)>,
+	signer: [u8; 32],
+	crypto: Crypto,
+	contexts: &[(Network, TransactionContext)],
+) -> Result<Vec<SignerPayload>, Error> {
+	let mut nonces: Vec<(Network, u32)> = Vec::new();
+	calls
+		.into_iter()
+		.map(|(network, call)| {
+			let context = match contexts.iter().find(|(n, _)| *n == network) {
+				Some((_, context)) => context,
+				None =>
+					return Err(Error::InvalidInput(format!(
+						"The transaction context has no entry for {network:?}."
+					))),
+			};
+			let nonce = match nonces.iter_mut().find(|(n, _)| *n == network) {
+				Some((_, nonce)) => {
+					*nonce += 1;
+					*nonce
+				},
+				None => {
+					nonces.push((network.clone(), context.nonce));
+					context.nonce
+				},
+			};
+			SignerPayload::new(network, call, signer, crypto, context, nonce)
+		})
+		.collect()
+}
+
+/// Write each of `payloads` to its own file in `directory`, and return their paths.
+pub fn export_payloads(payloads: &[SignerPayload], directory: &str) -> Result<Vec<String>, Error> {
+	std::fs::create_dir_all(directory)
+		.map_err(|source| Error::WriteFile { path: directory.to_string(), source })?;
+	let mut paths = Vec::new();
+	for (index, payload) in payloads.iter().enumerate() {
+		let path = format!("{directory}/{index}-{:?}.json", payload.network);
+		let document = serde_json::to_string_pretty(&payload.to_json()).expect("it serializes");
+		write_file(&path, document)?;
+		paths.push(path);
+	}
+	Ok(paths)
+}
+
+impl SignerPayload {
+	/// The payload for `signer` to sign `call` on `network`, with the details in `context` and
+	/// `nonce`.
+	pub fn new(
+		network: Network,
+		call: Vec<u8>,
+		signer: [u8; 32],
+		crypto: Crypto,
+		context: &TransactionContext,
+		nonce: u32,
+	) -> Result<Self, Error> {
+		let genesis_hash = parse_hash(&context.genesis_hash, "genesis_hash")?;
+		let (era, era_block_hash) = match (&context.block_hash, context.block_number) {
+			(Some(hash), Some(number)) => (
+				mortal_era(number, context.era_period.unwrap_or(64)).to_vec(),
+				parse_hash(hash, "block_hash")?,
+			),
+			(None, None) => (vec![0], genesis_hash),
+			_ => return Err(Error::InvalidInput(String::from(
+				"Give both `block_hash` and `block_number` for a mortal transaction, or neither.",
+			))),
+		};
+		let invalid = |reason: String| Error::InvalidRuntime {
+			file: format!("The bundled {network:?} metadata"),
+			reason,
+		};
+		let metadata = wasm::decode_metadata(network.bundled_metadata()).map_err(invalid)?;
+		let (spec_version, transaction_version) =
+			match (context.spec_version, context.transaction_version) {
+				(Some(spec), Some(transaction)) => (spec, transaction),
+				(spec, transaction) => {
+					let bundled = wasm::metadata_runtime_version(&metadata)
+						.ok_or_else(|| invalid(String::from("has no runtime version")))?;
+					(
+						spec.unwrap_or(bundled.spec_version),
+						transaction.unwrap_or(bundled.transaction_version),
+					)
+				},
+			};
+
+		// The data of each of the runtime's transaction extensions, in order. Version 4
+		// extrinsics use the extensions of version 0.
+		let extensions = metadata
+			.extrinsic()
+			.transaction_extensions_by_version(0)
+			.ok_or_else(|| invalid(String::from("has no transaction extensions of version 0")))?;
+		let mut extra = Vec::new();
+		let mut implicit = Vec::new();
+		for extension in extensions {
+			match extension.identifier() {
+				"CheckSpecVersion" => spec_version.encode_to(&mut implicit),
+				"CheckTxVersion" => transaction_version.encode_to(&mut implicit),
+				"CheckGenesis" => implicit.extend_from_slice(&genesis_hash),
+				"CheckMortality" => {
+					extra.extend_from_slice(&era);
+					implicit.extend_from_slice(&era_block_hash);
+				},
+				"CheckNonce" => Compact(nonce).encode_to(&mut extra),
+				"ChargeTransactionPayment" => Compact(context.tip).encode_to(&mut extra),
+				// Also the asset to pay fees in. `None` is the native token.
+				"ChargeAssetTxPayment" => {
+					Compact(context.tip).encode_to(&mut extra);
+					extra.push(0);
+				},
+				// Disabled, as the signer is expected to have the metadata, so with no hash.
+				"CheckMetadataHash" => {
+					extra.push(0);
+					implicit.push(0);
+				},
+				// Others, like `CheckNonZeroSender` and `CheckWeight`, must have no data.
+				_ if has_no_data(metadata.types(), extension.extra_ty())
+					&& has_no_data(metadata.types(), extension.additional_ty()) => {},
+				identifier =>
+					return Err(invalid(format!(
+						"has the transaction extension `{identifier}`, whose data is unknown"
+					))),
+			}
+		}
+
+		Ok(Self { network, signer, crypto, call, extra, implicit, genesis_hash })
+	}
+
+	/// The bytes to sign: the call and the extensions' data, or their blake2_256 hash if that is
+	/// longer than 256 bytes.
+	pub fn signing_payload(&self) -> Vec<u8> {
+		let payload = [&self.call[..], &self.extra, &self.implicit].concat();
+		if payload.len() > 256 {
+			blake2_256(&payload).to_vec()
+		} else {
+			payload
+		}
+	}
+
+	/// The payload in the Universal Offline Signatures format that Polkadot Vault scans, for
+	/// encoding into a QR code, e.g. with `qrencode`.
+	pub fn qr_payload(&self) -> Vec<u8> {
+		// "Substrate", the key type, and "sign a transaction".
+		let mut payload = vec![0x53, self.crypto.index(), 0x02];
+		payload.extend_from_slice(&self.signer);
+		self.call.encode_to(&mut payload);
+		payload.extend_from_slice(&self.extra);
+		payload.extend_from_slice(&self.implicit);
+		payload.extend_from_slice(&self.genesis_hash);
+		payload
+	}
+
+	/// The signed extrinsic, ready to broadcast, once `signature` is checked against the signing
+	/// payload and signer.
+	pub fn assemble(&self, signature: &[u8]) -> Result<Vec<u8>, Error> {
+		let signature: [u8; 64] = match signature {
+			[index, signature @ ..] if signature.len() == 64 && *index == self.crypto.index() =>
+				signature.try_into().expect("it is 64 bytes"),
+			signature => signature.try_into().map_err(|_| {
+				Error::InvalidInput(format!(
+					"A {} signature has 64 bytes, optionally after the byte {}, not {}.",
+					self.crypto.name(),
+					self.crypto.index(),
+					signature.len()
+				))
+			})?,
+		};
+		let message = self.signing_payload();
+		let valid = match self.crypto {
+			Crypto::Ed25519 => sp_core::ed25519::Pair::verify(
+				&sp_core::ed25519::Signature::from_raw(signature),
+				&message,
+				&sp_core::ed25519::Public::from_raw(self.signer),
+			),
+			Crypto::Sr25519 => sp_core::sr25519::Pair::verify(
+				&sp_core::sr25519::Signature::from_raw(signature),
+				&message,
+				&sp_core::sr25519::Public::from_raw(self.signer),
+			),
+		};
+		if !valid {
+			return Err(Error::InvalidInput(String::from(
+				"The signature is not the signer's signature of the payload.",
+			)));
+		}
+
+		// A signed extrinsic of version 4, with a `MultiAddress::Id` and a `MultiSignature`.
+		let mut extrinsic = vec![0x84, 0];
+		extrinsic.extend_from_slice(&self.signer);
+		extrinsic.push(self.crypto.index());
+		extrinsic.extend_from_slice(&signature);
+		extrinsic.extend_from_slice(&self.extra);
+		extrinsic.extend_from_slice(&self.call);
+		// Extrinsics are length-prefixed, like a `Vec<u8>`.
+		Ok(extrinsic.encode())
+	}
+
+	/// The payload as the JSON document written to a payload file.
+	pub fn to_json(&self) -> serde_json::Value {
+		let hex = |bytes: &[u8]| format!("0x{}", hex::encode(bytes));
+		serde_json::json!({
+			"network": self.network,
+			"signer": hex(&self.signer),
+			"crypto": self.crypto.name(),
+			"call": hex(&self.call),
+			"extra": hex(&self.extra),
+			"implicit": hex(&self.implicit),
+			"genesis_hash": hex(&self.genesis_hash),
+			"signing_payload": hex(&self.signing_payload()),
+			"qr_payload": hex(&self.qr_payload()),
+		})
+	}
+
+	/// The inverse of `to_json`, from the contents of a payload file.
+	pub fn from_json(contents: &str) -> Result<Self, Error> {
+		let invalid =
+			|reason: String| Error::InvalidInput(format!("Invalid payload file: {reason}"));
+		let document: serde_json::Value =
+			serde_json::from_str(contents).map_err(|e| invalid(e.to_string()))?;
+		let field = |name: &str| {
+			document[name].as_str().ok_or_else(|| invalid(format!("`{name}` is missing.")))
+		};
+		let bytes = |name: &str| -> Result<Vec<u8>, Error> {
+			Ok(hex::decode(field(name)?.trim_start_matches("0x"))?)
+		};
+		let network = Network::from_name(field("network")?)
+			.map_err(|e| invalid(format!("`network` is an {e}.")))?;
+		Ok(Self {
+			network,
+			signer: parse_hash(field("signer")?, "signer")?,
+			crypto: Crypto::from_name(field("crypto")?)?,
+			call: bytes("call")?,
+			extra: bytes("extra")?,
+			implicit: bytes("implicit")?,
+			genesis_hash: parse_hash(field("genesis_hash")?, "genesis_hash")?,
+		})
+	}
+}
+
+/// The encoded `sp_runtime::generic::Era` of a transaction that is valid for `period` blocks
+/// (rounded up to a power of two, from 4 to 65,536) from the block `block_number`.
+pub fn mortal_era(block_number: u32, period: u64) -> [u8; 2] {
+	let period = period.checked_next_power_of_two().unwrap_or(1 << 16).clamp(4, 1 << 16);
+	let phase = u64::from(block_number) % period;
+	let quantize_factor = (period >> 12).max(1);
+	let encoded =
+		(period.trailing_zeros() - 1).clamp(1, 15) as u16 | ((phase / quantize_factor) << 4) as u16;
+	encoded.to_le_bytes()
+}
+
+// Whether values of the type `id` encode to nothing, like `()`.
+fn has_no_data(types: &PortableRegistry, id: u32) -> bool {
+	match types.resolve(id).map(|ty| &ty.type_def) {
+		Some(TypeDef::Composite(composite)) =>
+			composite.fields.iter().all(|field| has_no_data(types, field.ty.id)),
+		Some(TypeDef::Tuple(tuple)) =>
+			tuple.fields.iter().all(|field| has_no_data(types, field.id)),
+		Some(TypeDef::Array(array)) => array.len == 0 || has_no_data(types, array.type_param.id),
+		_ => false,
+	}
+}
+
+// Parse the hex-encoded 32 bytes of the field `name`, e.g. a block hash.
+fn parse_hash(hash: &str, name: &str) -> Result<[u8; 32], Error> {
+	hex::decode(hash.trim_start_matches("0x"))?
+		.try_into()
+		.map_err(|_| Error::InvalidInput(format!("`{name}` must be 32 bytes.")))
+}
@@ -0,0 +1,528 @@
+use crate::*;
+use clap::Parser as ClapParser;
+use dynamic::MetadataOverrides;
+use node::Node;
+use parity_scale_codec::Encode;
+use scale_info::{PortableRegistry, TypeDef, TypeDefPrimitive};
+use subxt::ext::scale_value::{self, Composite, Primitive, Value, ValueDef};
+use subxt::Metadata;
+
+/// The XCM version in which the dry run reports the messages that a call sends.
+const XCM_VERSION: u32 = 5;
+
+/// Dry-run a proposal, with the origin of its track, on a fork of the chain that would enact it,
+/// e.g. one run by Chopsticks, or offline against a state snapshot. With the Whitelist, also
+/// dry-run the Fellowship's call that whitelists it.
+#[derive(Debug, ClapParser)]
+pub struct SimulateArgs {
+	/// The encoded proposal that we want to simulate. This can either be the call data itself,
+	/// e.g. "0x0102...", or a file path that contains the data, e.g. "./my_proposal.call".
+	#[clap(long = "proposal", short)]
+	proposal: String,
+
+	/// Network on which the referendum would be submitted. `polkadot` or `kusama`.
+	#[clap(long = "network", short)]
+	network: String,
+
+	/// Track on which the referendum would be submitted, e.g. `small-spender`. Its origin
+	/// dispatches the proposal.
+	#[clap(long = "track", short)]
+	track: String,
+
+	/// Which network's Fellowship to use for whitelisting. Only supported with
+	/// `--network kusama --track whitelistedcaller`. Options: `kusama` (default) or `polkadot`.
+	#[clap(long = "fellowship")]
+	fellowship: Option<String>,
+
+	/// Encode calls with the metadata of a newer runtime instead of the bundled metadata. Either a
+	/// runtime Wasm blob or a SCALE-encoded metadata file. Can be repeated, once per network.
+	#[clap(long = "metadata")]
+	metadata: Vec<String>,
+
+	/// A fork of a chain on which to dispatch its call, e.g. `ws://localhost:8000`. The network is
+	/// taken from its `spec_name`. Can be repeated, once per network.
+	#[clap(long = "rpc", required_unless_present = "snapshot")]
+	rpc: Vec<String>,
+
+	/// Instead of a fork, execute this runtime Wasm blob offline, against the state in
+	/// `--snapshot`. Only the call on the runtime's network, from its `spec_name`, is dispatched.
+	#[clap(long = "runtime", requires = "snapshot", conflicts_with = "rpc")]
+	runtime: Option<String>,
+
+	/// A snapshot of the state of the runtime's chain, as written by `try-runtime create-snapshot`.
+	#[clap(long = "snapshot", requires = "runtime")]
+	snapshot: Option<String>,
+}
+
+/// A call to dispatch in a simulation, and the origin to dispatch it with.
+pub struct Dispatch {
+	/// What the call is, e.g. `Proposal`.
+	pub step: &'static str,
+	/// The network that dispatches the call.
+	pub network: Network,
+	/// The name of the origin, e.g. `Root` or `Fellows`.
+	pub origin_name: String,
+	/// The `OriginCaller` of the origin, in its on-chain encoding.
+	pub origin: Vec<u8>,
+	/// The call, in its on-chain encoding.
+	pub call: Vec<u8>,
+}
+
+/// What happened when a call was dispatched in a simulation.
+#[derive(Debug, PartialEq)]
+pub struct Effects {
+	/// `None` if the call succeeded, or else the error that it failed with.
+	pub error: Option<String>,
+	/// The events that it emitted, e.g. `System.Remarked { .. }`.
+	pub events: Vec<String>,
+	/// The XCM that it executed locally, if any.
+	pub local_xcm: Option<String>,
+	/// The XCMs that it sent, as `(destination, messages)`.
+	pub forwarded_xcms: Vec<(String, Vec<String>)>,
+}
+
+/// A change that a simulated call made to storage.
+#[derive(Debug, PartialEq)]
+pub struct StorageChange {
+	/// The storage item of the key, e.g. `System.Account`, if the metadata has it.
+	pub item: Option<String>,
+	/// The key, after the prefix of its storage item.
+	pub key: String,
+	/// The value before the call, or `None` if the key was empty.
+	pub old: Option<String>,
+	/// The value after the call, or `None` if the key was removed.
+	pub new: Option<String>,
+}
+
+/// The sub-command's "main" function.
+pub async fn simulate(prefs: SimulateArgs) -> Result<(), Error> {
+	let track = submit_referendum::parse_track(&prefs.network, &prefs.track)?;
+	let proposal_details = ProposalDetails::builder(prefs.proposal, track)
+		.fellowship_on_polkadot(submit_referendum::parse_fellowship(prefs.fellowship.as_deref())?)
+		.metadata(MetadataOverrides::load(&prefs.metadata)?)
+		// Only the calls are needed, so never write long ones to a file.
+		.output_len_limit(u32::MAX)
+		.build()?;
+	let dispatches = dispatches(&proposal_details).await?;
+	if let (Some(runtime), Some(snapshot)) = (&prefs.runtime, &prefs.snapshot) {
+		return simulate_offline(&dispatches, runtime, snapshot);
+	}
+
+	let mut nodes = Vec::new();
+	for url in &prefs.rpc {
+		let node = Node::connect(url).await?;
+		nodes.push((node.network().await?, node));
+	}
+
+	// Simulate every call before reporting the first that failed.
+	let mut failure = None;
+	for dispatch in &dispatches {
+		let network = &dispatch.network;
+		let node = match nodes.iter().find(|(node_network, _)| node_network == network) {
+			Some((_, node)) => node,
+			None =>
+				return Err(Error::InvalidInput(format!(
+					"Pass a fork of {network:?} with `--rpc` to simulate its call."
+				))),
+		};
+		let effects = dry_run(node, dispatch).await?;
+		print_effects(dispatch, &effects);
+		if let (Some(error), None) = (effects.error, &failure) {
+			failure = Some(Error::Simulation {
+				network: network.clone(),
+				reason: format!("{} failed to dispatch: {error}", dispatch.step),
+			});
+		}
+	}
+	failure.map_or(Ok(()), Err)
+}
+
+// Dry-run the call among `dispatches` on the network of the Wasm blob at `runtime`, in an
+// embedded executor, against the state snapshot at `snapshot`.
+fn simulate_offline(dispatches: &[Dispatch], runtime: &str, snapshot: &str) -> Result<(), Error> {
+	let invalid = |reason: String| Error::InvalidRuntime { file: String::from(runtime), reason };
+	let blob = read_file(runtime)?;
+	let code = wasm::decompress_runtime(&blob).map_err(invalid)?;
+	let version = wasm::runtime_version(&blob).map_err(invalid)?;
+	let network = Network::from_spec_name(&version.spec_name).map_err(|_| {
+		Error::UnsupportedNetwork(format!(
+			"The runtime in {runtime} is `{}`, which this tool does not support.",
+			version.spec_name
+		))
+	})?;
+	let Some(dispatch) = dispatches.iter().find(|dispatch| dispatch.network == network) else {
+		let networks: Vec<String> = dispatches.iter().map(|d| format!("{:?}", d.network)).collect();
+		return Err(Error::InvalidInput(format!(
+			"The proposal has no call to dispatch on {network:?}. Pass the runtime of {}.",
+			networks.join(" or ")
+		)));
+	};
+	let metadata = update_metadata::newest_wasm_metadata(&code)
+		.and_then(|metadata| wasm::decode_metadata(&metadata))
+		.map_err(invalid)?;
+	let state = wasm::Snapshot::decode(&read_file(snapshot)?)
+		.map_err(|reason| Error::InvalidInput(format!("The snapshot {snapshot} {reason}.")))?;
+
+	let (output, changes) =
+		wasm::call_runtime_at(&code, &state, "DryRunApi_dry_run_call", &dry_run_input(dispatch))
+			.map_err(invalid)?;
+	let effects = dry_run_effects(&network, &metadata, &output)?;
+	print_effects(dispatch, &effects);
+	print_storage_changes(&storage_changes(&metadata, changes));
+	for other in dispatches.iter().filter(|other| other.network != network) {
+		println!("\nThe {} on {:?} was not simulated.", other.step.to_lowercase(), other.network);
+	}
+	match effects.error {
+		Some(error) => Err(Error::Simulation {
+			network,
+			reason: format!("{} failed to dispatch: {error}", dispatch.step),
+		}),
+		None => Ok(()),
+	}
+}
+
+/// The calls that would be dispatched if the referendum for the proposal in `proposal_details`
+/// passed, in order: the Fellowship's call that whitelists it, if the track needs one, and then
+/// the proposal itself.
+///
+/// With the Whitelist, the proposal is dispatched with the Root origin that
+/// `dispatch_whitelisted_call_with_preimage` gives it, since the fork has not whitelisted it yet.
+pub async fn dispatches(proposal_details: &ProposalDetails) -> Result<Vec<Dispatch>, Error> {
+	let proposal = get_proposal_bytes(proposal_details.proposal.clone())?;
+	let calls = submit_referendum::generate_calls(proposal_details).await?;
+	let track = &proposal_details.track;
+	let metadata = &proposal_details.metadata;
+
+	let mut dispatches = Vec::new();
+	if let Some(whitelist_over_xcm) = calls.whitelist_over_xcm {
+		let network = whitelist_over_xcm.network;
+		dispatches.push(Dispatch {
+			step: "Fellowship whitelist call",
+			origin_name: String::from("Fellows"),
+			origin: metadata.encode_origin(&network, &fellows_origin(&network)?)?,
+			network,
+			call: whitelist_over_xcm.encoded,
+		});
+	}
+	let network = submit_referendum::public_network(track);
+	let (origin_name, origin) = track_origin(track);
+	dispatches.push(Dispatch {
+		step: "Proposal",
+		origin_name,
+		origin: metadata.encode_origin(&network, &origin)?,
+		network,
+		call: proposal,
+	});
+	Ok(dispatches)
+}
+
+// The origin that enacts a Fellowship referendum on `network`, encoded with the static types.
+fn fellows_origin(network: &Network) -> Result<Vec<u8>, Error> {
+	use polkadot_collectives::runtime_types::collectives_polkadot_runtime::OriginCaller as CollectivesOriginCaller;
+
+	match network {
+		Network::Kusama => Ok(KusamaOriginCaller::Origins(KusamaOpenGovOrigin::Fellows).encode()),
+		Network::PolkadotCollectives =>
+			Ok(CollectivesOriginCaller::FellowshipOrigins(FellowshipOrigins::Fellows).encode()),
+		_ => Err(Error::UnsupportedNetwork(format!("{network:?} has no Fellowship."))),
+	}
+}
+
+// The name and encoding, with the static types, of the origin that dispatches a proposal on
+// `track`, on Asset Hub.
+fn track_origin(track: &NetworkTrack) -> (String, Vec<u8>) {
+	use kusama_asset_hub::runtime_types::frame_support::dispatch::RawOrigin as KusamaRawOrigin;
+	use polkadot_asset_hub::runtime_types::frame_support::dispatch::RawOrigin as PolkadotRawOrigin;
+
+	let root = String::from("Root");
+	match track {
+		NetworkTrack::KusamaRoot
+		| NetworkTrack::Kusama(KusamaAssetHubOpenGovOrigin::WhitelistedCaller) =>
+			(root, KusamaAssetHubOriginCaller::system(KusamaRawOrigin::Root).encode()),
+		NetworkTrack::Kusama(origin) =>
+			(track.name(), KusamaAssetHubOriginCaller::Origins(origin.clone()).encode()),
+		NetworkTrack::PolkadotRoot
+		| NetworkTrack::Polkadot(PolkadotAssetHubOpenGovOrigin::WhitelistedCaller) =>
+			(root, PolkadotAssetHubOriginCaller::system(PolkadotRawOrigin::Root).encode()),
+		NetworkTrack::Polkadot(origin) =>
+			(track.name(), PolkadotAssetHubOriginCaller::Origins(origin.clone()).encode()),
+	}
+}
+
+/// Dry-run `dispatch` on `node`, a fork of its network, with the `DryRunApi` of its runtime.
+/// Nothing is written to the fork's state.
+pub async fn dry_run(node: &Node, dispatch: &Dispatch) -> Result<Effects, Error> {
+	let metadata = node.online_client().await?.metadata();
+	let output = node.state_call("DryRunApi_dry_run_call", &dry_run_input(dispatch)).await?;
+	dry_run_effects(&dispatch.network, &metadata, &output)
+}
+
+// The input of `DryRunApi_dry_run_call` to dispatch `dispatch`: its origin, its call, and the XCM
+// version of the result.
+fn dry_run_input(dispatch: &Dispatch) -> Vec<u8> {
+	let mut input = dispatch.origin.clone();
+	input.extend_from_slice(&dispatch.call);
+	input.extend(XCM_VERSION.encode());
+	input
+}
+
+/// Read the output of `DryRunApi_dry_run_call`, a `Result<CallDryRunEffects, Error>`, with the
+/// `metadata` of the runtime on `network` that returned it.
+pub fn dry_run_effects(
+	network: &Network,
+	metadata: &Metadata,
+	output: &[u8],
+) -> Result<Effects, Error> {
+	let fail = |reason: String| Error::Simulation { network: network.clone(), reason };
+	let types = metadata.types();
+	let output_ty = metadata
+		.runtime_api_trait_by_name("DryRunApi")
+		.and_then(|api| api.method_by_name("dry_run_call").map(|method| method.output_ty()))
+		.ok_or_else(|| fail(String::from("the runtime has no `DryRunApi::dry_run_call`")))?;
+	let value = scale_value::scale::decode_as_type(&mut &output[..], output_ty, types)
+		.map_err(|e| fail(format!("the dry run returned a result that does not decode: {e}")))?;
+
+	let effects = match variant(&value) {
+		Some(("Ok", Some(effects))) => effects,
+		Some(("Err", Some(reason))) =>
+			return Err(fail(format!(
+				"the runtime could not dry-run the call: {}",
+				format_value(reason, types)
+			))),
+		_ => return Err(fail(String::from("the dry run returned something other than a result"))),
+	};
+	let error = match field(effects, "execution_result").and_then(variant) {
+		Some(("Ok", _)) => None,
+		Some(("Err", Some(failure))) => Some(match field(failure, "error") {
+			Some(dispatch_error) => describe_error(dispatch_error, metadata),
+			None => format_value(failure, types),
+		}),
+		_ => return Err(fail(String::from("the dry run has no execution result"))),
+	};
+	let events = items(field(effects, "emitted_events"))
+		.into_iter()
+		.map(|event| match variant(event) {
+			// Each event is a variant of the pallet's, which wraps its own.
+			Some((pallet, Some(event))) => format!("{pallet}.{}", format_value(event, types)),
+			_ => format_value(event, types),
+		})
+		.collect();
+	let local_xcm = match field(effects, "local_xcm").and_then(variant) {
+		Some(("Some", Some(xcm))) => Some(format_value(xcm, types)),
+		_ => None,
+	};
+	let forwarded_xcms = items(field(effects, "forwarded_xcms"))
+		.into_iter()
+		.filter_map(|entry| match items(Some(entry))[..] {
+			[destination, messages] => Some((
+				format_value(destination, types),
+				items(Some(messages)).into_iter().map(|m| format_value(m, types)).collect(),
+			)),
+			_ => None,
+		})
+		.collect();
+	Ok(Effects { error, events, local_xcm, forwarded_xcms })
+}
+
+/// Print what happened when `dispatch` was simulated.
+pub fn print_effects(dispatch: &Dispatch, effects: &Effects) {
+	println!(
+		"\n{} on {:?}, dispatched with the {} origin:",
+		dispatch.step, dispatch.network, dispatch.origin_name
+	);
+	match &effects.error {
+		None => println!("  Succeeded"),
+		Some(error) => println!("  Failed: {error}"),
+	}
+	if !effects.events.is_empty() {
+		println!("  Events:");
+		for event in &effects.events {
+			println!("    {event}");
+		}
+	}
+	if let Some(xcm) = &effects.local_xcm {
+		println!("  Executed XCM:\n    {xcm}");
+	}
+	for (destination, messages) in &effects.forwarded_xcms {
+		println!("  Sent XCM to {destination}:");
+		for message in messages {
+			println!("    {message}");
+		}
+	}
+}
+
+/// Describe the storage `changes` that a runtime made, with its `metadata`. Keys are named after
+/// their storage item, and the values of known items are decoded.
+pub fn storage_changes(
+	metadata: &Metadata,
+	changes: Vec<wasm::RawStorageChange>,
+) -> Vec<StorageChange> {
+	let types = metadata.types();
+	// The prefix of each storage item's keys, with its name and the type of its values.
+	let mut items = Vec::new();
+	for pallet in metadata.pallets() {
+		let Some(storage) = pallet.storage() else {
+			continue;
+		};
+		for entry in storage.entries() {
+			let prefix = [
+				sp_core::twox_128(storage.prefix().as_bytes()),
+				sp_core::twox_128(entry.name().as_bytes()),
+			]
+			.concat();
+			let name = format!("{}.{}", pallet.name(), entry.name());
+			items.push((prefix, name, entry.entry_type().value_ty()));
+		}
+	}
+
+	changes
+		.into_iter()
+		.map(|(key, old, new)| {
+			let item = items.iter().find(|(prefix, _, _)| key.starts_with(prefix));
+			let value = |value: Vec<u8>| {
+				let decoded = item.and_then(|(_, _, ty)| {
+					let input = &mut &value[..];
+					let decoded = scale_value::scale::decode_as_type(input, *ty, types).ok();
+					decoded.filter(|_| input.is_empty()).map(|v| format_value(&v, types))
+				});
+				decoded.unwrap_or_else(|| format!("0x{}", hex::encode(&value)))
+			};
+			let prefix_len = item.map_or(0, |(prefix, _, _)| prefix.len());
+			StorageChange {
+				item: item.map(|(_, name, _)| name.clone()),
+				key: format!("0x{}", hex::encode(&key[prefix_len..])),
+				old: old.map(value),
+				new: new.map(value),
+			}
+		})
+		.collect()
+}
+
+/// Print the storage changes that a simulated call made.
+pub fn print_storage_changes(changes: &[StorageChange]) {
+	if changes.is_empty() {
+		return;
+	}
+	let or_empty =
+		|value: &Option<String>| value.clone().unwrap_or_else(|| String::from("(empty)"));
+	println!("  Storage changes:");
+	for change in changes {
+		println!(
+			"    {} {}: {} -> {}",
+			change.item.as_deref().unwrap_or("(unknown)"),
+			change.key,
+			or_empty(&change.old),
+			or_empty(&change.new)
+		);
+	}
+}
+
+// A `DispatchError` as text. Module errors are named after their pallet and variant, e.g.
+// `Referenda.NotOngoing`.
+fn describe_error(dispatch_error: &Value<u32>, metadata: &Metadata) -> String {
+	let module_error = || {
+		let ("Module", Some(module_error)) = variant(dispatch_error)? else {
+			return None;
+		};
+		let index = field(module_error, "index")?.as_u128()?;
+		// The error is a `u8`, or a `[u8; 4]` whose first byte is the variant.
+		let error = field(module_error, "error")?;
+		let error = error.as_u128().or_else(|| items(Some(error)).first()?.as_u128())?;
+		let pallet = metadata.pallet_by_index(index.try_into().ok()?)?;
+		let variant = pallet.error_variant_by_index(error.try_into().ok()?)?;
+		Some(format!("{}.{}", pallet.name(), variant.name))
+	};
+	module_error().unwrap_or_else(|| format_value(dispatch_error, metadata.types()))
+}
+
+// The name of `value`'s variant, and its first field, if it is a variant.
+fn variant(value: &Value<u32>) -> Option<(&str, Option<&Value<u32>>)> {
+	match &value.value {
+		ValueDef::Variant(variant) => Some((variant.name.as_str(), variant.values.values().next())),
+		_ => None,
+	}
+}
+
+// The field called `name` of `value`, a struct or a variant with named fields.
+fn field<'a>(value: &'a Value<u32>, name: &str) -> Option<&'a Value<u32>> {
+	let fields = match &value.value {
+		ValueDef::Composite(Composite::Named(fields)) => fields,
+		ValueDef::Variant(variant) => match &variant.values {
+			Composite::Named(fields) => fields,
+			Composite::Unnamed(_) => return None,
+		},
+		_ => return None,
+	};
+	fields.iter().find(|(field, _)| field == name).map(|(_, value)| value)
+}
+
+// The items of `value`, a sequence or tuple. Empty for anything else.
+fn items(value: Option<&Value<u32>>) -> Vec<&Value<u32>> {
+	match value.map(|value| &value.value) {
+		Some(ValueDef::Composite(composite)) => composite.values().collect(),
+		_ => Vec::new(),
+	}
+}
+
+// `value` as text on one line, with the names of its variants and fields, byte arrays in hex, and
+// wrapper types such as `AccountId32` left out.
+fn format_value(value: &Value<u32>, types: &PortableRegistry) -> String {
+	let is_struct =
+		matches!(types.resolve(value.context).map(|ty| &ty.type_def), Some(TypeDef::Composite(_)));
+	match &value.value {
+		ValueDef::Composite(Composite::Unnamed(values)) if is_struct && values.len() == 1 =>
+			format_value(&values[0], types),
+		ValueDef::Composite(composite) => match bytes(composite, types) {
+			Some(bytes) => format!("0x{}", hex::encode(bytes)),
+			None => match composite {
+				Composite::Named(_) => format_fields(composite, types).trim_start().to_string(),
+				Composite::Unnamed(values) => format!(
+					"[{}]",
+					values.iter().map(|v| format_value(v, types)).collect::<Vec<_>>().join(", ")
+				),
+			},
+		},
+		ValueDef::Variant(variant) =>
+			format!("{}{}", variant.name, format_fields(&variant.values, types)),
+		_ => value.to_string(),
+	}
+}
+
+// The fields of a struct or variant as text, e.g. ` { who: 0x.., amount: 1 }` or `(1, 2)`.
+fn format_fields(fields: &Composite<u32>, types: &PortableRegistry) -> String {
+	match fields {
+		Composite::Named(fields) if !fields.is_empty() => format!(
+			" {{ {} }}",
+			fields
+				.iter()
+				.map(|(name, value)| format!("{name}: {}", format_value(value, types)))
+				.collect::<Vec<_>>()
+				.join(", ")
+		),
+		Composite::Unnamed(values) if !values.is_empty() => format!(
+			"({})",
+			values.iter().map(|v| format_value(v, types)).collect::<Vec<_>>().join(", ")
+		),
+		_ => String::new(),
+	}
+}
+
+// The bytes of `composite`, if it is a non-empty sequence or array of `u8`s.
+fn bytes(composite: &Composite<u32>, types: &PortableRegistry) -> Option<Vec<u8>> {
+	let Composite::Unnamed(values) = composite else {
+		return None;
+	};
+	if values.is_empty() {
+		return None;
+	}
+	values
+		.iter()
+		.map(|value| match (&value.value, types.resolve(value.context).map(|ty| &ty.type_def)) {
+			(
+				ValueDef::Primitive(Primitive::U128(byte)),
+				Some(TypeDef::Primitive(TypeDefPrimitive::U8)),
+			) => u8::try_from(*byte).ok(),
+			_ => None,
+		})
+		.collect()
+}
@@ -0,0 +1,208 @@
+use crate::*;
+use node::Node;
+use parity_scale_codec::Decode;
+use std::str::FromStr;
+use subxt_signer::{sr25519::Keypair, SecretUri};
+
+/// The pallets whose `Submitted` event gives the index of a new referendum.
+pub const REFERENDA_PALLETS: [&str; 2] = ["Referenda", "FellowshipReferenda"];
+
+/// The `Utility` events by which a batch says that some of its calls failed, although the batch
+/// itself succeeded.
+pub const BATCH_FAILURE_EVENTS: [&str; 3] =
+	["ItemFailed", "BatchCompletedWithErrors", "BatchInterrupted"];
+
+/// What happened to an extrinsic submitted with `submit_calls`.
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub struct Submission {
+	/// The network on which the extrinsic was submitted.
+	pub network: Network,
+	/// The hash of the extrinsic.
+	pub extrinsic_hash: String,
+	/// The hash of the block that included it.
+	pub block_hash: String,
+	/// The index of each referendum that it submitted, as `(pallet, index)`.
+	pub referenda: Vec<(String, u32)>,
+}
+
+/// A referendum whose decision deposit the calls place, on the index that it is predicted to get.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PredictedReferendum {
+	/// The network on which the referendum is submitted.
+	pub network: Network,
+	/// The pallet it is submitted to, `Referenda` or `FellowshipReferenda`.
+	pub pallet: &'static str,
+	/// The index that it is predicted to get.
+	pub index: u32,
+}
+
+// Call data that is already encoded, to be submitted as is.
+struct EncodedCall(Vec<u8>);
+
+impl subxt::tx::Payload for EncodedCall {
+	fn encode_call_data_to(
+		&self,
+		_metadata: &subxt::Metadata,
+		out: &mut Vec<u8>,
+	) -> Result<(), subxt::ext::subxt_core::Error> {
+		out.extend_from_slice(&self.0);
+		Ok(())
+	}
+}
+
+/// Load the sr25519 key to sign with, from either `suri` or a `keystore` file.
+///
+/// A secret URI is a mnemonic phrase or hex seed with an optional derivation path and password,
+/// e.g. `//Alice` for a development account. A keystore file, as written by a node, holds one as a
+/// JSON string.
+pub fn load_signer(suri: Option<&str>, keystore: Option<&str>) -> Result<Keypair, Error> {
+	let suri = match (suri, keystore) {
+		(Some(suri), None) => String::from(suri),
+		(None, Some(path)) => {
+			let contents = String::from_utf8(read_file(path)?).map_err(|_| {
+				Error::InvalidInput(format!("The keystore file {path} is not valid UTF-8."))
+			})?;
+			let contents = contents.trim();
+			serde_json::from_str::<String>(contents).unwrap_or_else(|_| String::from(contents))
+		},
+		_ =>
+			return Err(Error::InvalidInput(String::from(
+				"Pass either `--suri` or `--keystore` to sign with.",
+			))),
+	};
+	// Secrets are left out of the errors.
+	let uri = SecretUri::from_str(&suri)
+		.map_err(|_| Error::InvalidInput(String::from("The secret URI is not valid.")))?;
+	Keypair::from_uri(&uri)
+		.map_err(|_| Error::InvalidInput(String::from("The secret URI does not derive a key.")))
+}
+
+/// Sign each of the encoded `calls` with `signer` and submit it to the node, among `rpc`, that runs
+/// its network, in order. Each extrinsic must be included in a block, and succeed, before the next
+/// one is submitted. A batch only succeeds if all of its calls do.
+///
+/// Before signing a call on a network, the `ReferendumCount` of each of the `predicted` referenda
+/// on it that are not yet submitted must still be their index, or no deposit would be placed on
+/// the right referendum.
+pub async fn submit_calls(
+	calls: Vec<(Network, Vec<u8>)>,
+	predicted: &[PredictedReferendum],
+	signer: &Keypair,
+	rpc: &[String],
+) -> Result<Vec<Submission>, Error> {
+	let mut nodes = Vec::new();
+	for url in rpc {
+		let node = Node::connect(url).await?;
+		nodes.push((node.network().await?, node));
+	}
+
+	let mut submissions = Vec::new();
+	for (network, call) in calls {
+		let node = match nodes.iter().find(|(node_network, _)| *node_network == network) {
+			Some((_, node)) => node,
+			None =>
+				return Err(Error::InvalidInput(format!(
+					"Pass a {network:?} node with `--rpc` to submit its calls."
+				))),
+		};
+		let extrinsic_error =
+			|reason: String| Error::Extrinsic { network: network.clone(), reason };
+		let rpc_error =
+			|e: subxt::Error| Error::Rpc { url: String::from(node.url()), reason: e.to_string() };
+
+		for referendum in predicted.iter().filter(|r| r.network == network) {
+			let key = (String::from(referendum.pallet), referendum.index);
+			if submissions.iter().any(|s| s.network == network && s.referenda.contains(&key)) {
+				continue;
+			}
+			let count = node.referendum_count(referendum.pallet).await?;
+			if count != referendum.index {
+				return Err(extrinsic_error(format!(
+					"was not signed, as it would place the decision deposit of {} referendum {}, 					 but the next referendum is {count}. Run the command again to predict the 					 index anew.",
+					referendum.pallet, referendum.index
+				)));
+			}
+		}
+
+		let client = node.online_client().await?;
+		let in_block = client
+			.tx()
+			.sign_and_submit_then_watch_default(&EncodedCall(call), signer)
+			.await
+			.map_err(rpc_error)?
+			.wait_for_in_block()
+			.await
+			.map_err(rpc_error)?;
+		let events =
+			in_block.wait_for_success().await.map_err(|e| extrinsic_error(e.to_string()))?;
+
+		let mut referenda = Vec::new();
+		for event in events.iter() {
+			let event = event.map_err(|e| extrinsic_error(e.to_string()))?;
+			if event.pallet_name() == "Utility"
+				&& BATCH_FAILURE_EVENTS.contains(&event.variant_name())
+			{
+				let fields = event.field_values().map_err(|e| extrinsic_error(e.to_string()))?;
+				return Err(extrinsic_error(format!(
+					"a call in its batch failed in block 0x{}, with `Utility::{}` {fields}",
+					hex::encode(in_block.block_hash()),
+					event.variant_name()
+				)));
+			}
+			if event.variant_name() == "Submitted"
+				&& REFERENDA_PALLETS.contains(&event.pallet_name())
+			{
+				// The index is the event's first field.
+				let index = u32::decode(&mut event.field_bytes()).map_err(|e| {
+					extrinsic_error(format!(
+						"emitted a `Submitted` event that does not decode: {e}"
+					))
+				})?;
+				referenda.push((String::from(event.pallet_name()), index));
+			}
+			// A decision deposit is placed on a predicted index, which is wrong if another
+			// referendum was submitted first.
+			if event.variant_name() == "DecisionDepositPlaced"
+				&& REFERENDA_PALLETS.contains(&event.pallet_name())
+			{
+				let pallet = String::from(event.pallet_name());
+				let index = u32::decode(&mut event.field_bytes()).map_err(|e| {
+					extrinsic_error(format!(
+						"emitted a `DecisionDepositPlaced` event that does not decode: {e}"
+					))
+				})?;
+				let submitted = referenda.contains(&(pallet.clone(), index))
+					|| submissions.iter().any(|s: &Submission| {
+						s.network == network && s.referenda.contains(&(pallet.clone(), index))
+					});
+				if !submitted {
+					return Err(extrinsic_error(format!(
+						"placed the decision deposit of {pallet} referendum {index}, which it did \
+						 not submit, as another referendum was submitted first. Place the deposit \
+						 of the right referendum by hand."
+					)));
+				}
+			}
+		}
+		submissions.push(Submission {
+			network,
+			extrinsic_hash: format!("0x{}", hex::encode(in_block.extrinsic_hash())),
+			block_hash: format!("0x{}", hex::encode(in_block.block_hash())),
+			referenda,
+		});
+	}
+	Ok(submissions)
+}
+
+/// Print what happened to each submitted extrinsic.
+pub fn print_submissions(submissions: &[Submission]) {
+	for submission in submissions {
+		println!(
+			"\nSubmitted on {:?} in block {}: extrinsic {}",
+			submission.network, submission.block_hash, submission.extrinsic_hash
+		);
+		for (pallet, index) in &submission.referenda {
+			println!("  {pallet} referendum index: {index}");
+		}
+	}
+}
@@ -1,10 +1,12 @@
 use crate::*;
 use clap::Parser as ClapParser;
-use std::fs;
+use dynamic::MetadataOverrides;
+use node::Node;
+use wrap::Wrapper;
 
 /// Generate all the calls needed to submit a proposal as a referendum in OpenGov.
 #[derive(Debug, ClapParser)]
-pub(crate) struct ReferendumArgs {
+pub struct ReferendumArgs {
 	/// The encoded proposal that we want to submit. This can either be the call data itself,
 	/// e.g. "0x0102...", or a file path that contains the data, e.g. "./my_proposal.call".
 	#[clap(long = "proposal", short)]
@@ -14,7 +16,8 @@ pub(crate) struct ReferendumArgs {
 	#[clap(long = "network", short)]
 	network: String,
 
-	/// Track on which to submit the referendum.
+	/// Track on which to submit the referendum, e.g. `small-spender`. Run `list-tracks` to see them
+	/// all.
 	#[clap(long = "track", short)]
 	track: String,
 
@@ -34,7 +37,7 @@ pub(crate) struct ReferendumArgs {
 	#[clap(long = "no-batch")]
 	no_batch: bool,
 
-	/// Form of output. `AppsUiLink` or `CallData`. Defaults to Apps UI.
+	/// Form of output. `AppsUiLink`, `CallData`, or `Json`. Defaults to Apps UI.
 	#[clap(long = "output")]
 	output: Option<String>,
 
@@ -46,124 +49,420 @@ pub(crate) struct ReferendumArgs {
 	/// `--network kusama --track whitelistedcaller`. Options: `kusama` (default) or `polkadot`.
 	#[clap(long = "fellowship")]
 	fellowship: Option<String>,
+
+	/// Encode calls with the metadata of a newer runtime instead of the bundled metadata. Either a
+	/// runtime Wasm blob or a SCALE-encoded metadata file. The network is taken from its
+	/// `spec_name`. Can be repeated, once per network.
+	#[clap(long = "metadata")]
+	metadata: Vec<String>,
+
+	/// Also place the decision deposit of each referendum, in the batch after its submission. Needs
+	/// the index that each referendum will get, from `--ref-index` and `--fellowship-ref-index` or
+	/// from a node given with `--rpc`.
+	#[clap(long = "decision-deposit")]
+	decision_deposit: bool,
+
+	/// The index that the public referendum will get. Defaults to the `ReferendumCount` of the Asset
+	/// Hub node given with `--rpc`.
+	#[clap(long = "ref-index", requires = "decision_deposit")]
+	ref_index: Option<u32>,
+
+	/// The index that the Fellowship referendum will get. Defaults to the `ReferendumCount` of the
+	/// Fellowship's node given with `--rpc`.
+	#[clap(long = "fellowship-ref-index", requires = "decision_deposit")]
+	fellowship_ref_index: Option<u32>,
+
+	/// A node to query for the index of the next referendum on its chain, and to submit its calls
+	/// to with `--submit`, e.g. `ws://localhost:9944`. The network is taken from its `spec_name`.
+	/// Can be repeated, once per network.
+	#[clap(long = "rpc")]
+	rpc: Vec<String>,
+
+	/// Sign the batch for each chain (or, with `--no-batch`, each call) and submit it to the node
+	/// of that chain given with `--rpc`, waiting for each to be included in a block.
+	#[clap(long = "submit", requires = "rpc")]
+	submit: bool,
+
+	/// The secret URI of the key to sign with, e.g. a mnemonic phrase, a hex seed, or `//Alice`.
+	/// Prefer `--keystore` outside of development, as arguments can leak through the shell.
+	#[clap(long = "suri", requires = "submit", conflicts_with = "keystore")]
+	suri: Option<String>,
+
+	/// A keystore file that holds the secret URI of the key to sign with.
+	#[clap(long = "keystore", requires = "submit")]
+	keystore: Option<String>,
+
+	/// Wrap each call, and each batch, for a multisig to submit, given as
+	/// `<threshold>:<signatory,...>`. The first signatory submits the first approval. Accounts are
+	/// SS58 addresses or hex account IDs.
+	#[clap(long = "multisig")]
+	multisig: Option<String>,
+
+	/// Where the first approval of the multisig on a network was included, given as
+	/// `<network>:<height>:<index>`, to build the approvals of the other signatories. Can be
+	/// repeated, once per network, for its batch. The approvals are only printed, as each is for
+	/// another signatory to sign.
+	#[clap(
+		long = "timepoint",
+		requires_all = ["multisig", "max_weight"],
+		conflicts_with_all = ["no_batch", "submit", "export_payloads"]
+	)]
+	timepoint: Vec<String>,
+
+	/// The weight of the call that the multisig dispatches on a network, given as
+	/// `<network>:<ref_time>:<proof_size>`, e.g. from `TransactionPaymentCallApi_query_call_info`.
+	/// The approval that dispatches it can use no more. Can be repeated, once per network.
+	#[clap(long = "max-weight", requires = "timepoint")]
+	max_weight: Vec<String>,
+
+	/// Wrap each call, and each batch, in `Proxy::proxy` to submit on behalf of another account,
+	/// given as `<real>[:<proxy type>]`. With `--multisig`, the multisig is the proxy.
+	#[clap(long = "proxy")]
+	proxy: Option<String>,
+
+	/// Instead of submitting, write a payload for an offline signer, e.g. Polkadot Vault, for each
+	/// call that `--submit` would sign, to this directory. Sign each and pass it to `assemble`.
+	#[clap(
+		long = "export-payloads",
+		requires_all = ["signer", "tx_context"],
+		conflicts_with = "submit"
+	)]
+	export_payloads: Option<String>,
+
+	/// The account that will sign the exported payloads, as an SS58 address or a hex account ID.
+	#[clap(long = "signer", requires = "export_payloads")]
+	signer: Option<String>,
+
+	/// The key type of `--signer`. `sr25519` (default) or `ed25519`.
+	#[clap(long = "signer-crypto", requires = "export_payloads")]
+	signer_crypto: Option<String>,
+
+	/// The nonce, genesis hash, and other details of each network to sign for, as JSON. Either the
+	/// JSON itself or a file path that contains it. See the README for its format.
+	#[clap(long = "tx-context", requires = "export_payloads")]
+	tx_context: Option<String>,
 }
 
-// The sub-command's "main" function.
-pub(crate) async fn submit_referendum(prefs: ReferendumArgs) {
+/// The sub-command's "main" function.
+pub async fn submit_referendum(prefs: ReferendumArgs) -> Result<(), Error> {
+	// Load the key first, so that a bad one fails before any output.
+	let signer = if prefs.submit {
+		Some(submit::load_signer(prefs.suri.as_deref(), prefs.keystore.as_deref())?)
+	} else {
+		None
+	};
+	let rpc = prefs.rpc.clone();
+	let export = match (&prefs.export_payloads, &prefs.signer, &prefs.tx_context) {
+		(Some(directory), Some(signer), Some(context)) => {
+			let network = Network::from_name(&prefs.network)
+				.map_err(|e| Error::UnsupportedNetwork(String::from(e)))?;
+			let signer = parse_account(signer, &network)?;
+			let crypto =
+				offline::Crypto::from_name(prefs.signer_crypto.as_deref().unwrap_or("sr25519"))?;
+			Some((directory.clone(), signer, crypto, offline::load_contexts(context)?))
+		},
+		_ => None,
+	};
 	// Find out what the user wants to do.
-	let proposal_details = parse_inputs(prefs);
+	let proposal_details = parse_inputs(prefs).await?;
+	let json = matches!(proposal_details.output, Output::Json);
+	// Show what the chosen track commits the proposer to.
+	if !json {
+		let network = proposal_details.track.network();
+		let track = list_tracks::track_for(&network, &proposal_details.track)?;
+		list_tracks::print_track(&network, &track, Some(&proposal_details.dispatch));
+	}
 	// Generate the calls necessary.
-	let calls = generate_calls(&proposal_details).await;
+	let calls = generate_calls(&proposal_details).await?;
+	let predicted = predicted_referenda(&proposal_details);
 	// Tell the user what to do.
-	deliver_output(proposal_details, calls);
+	let calls_to_submit = deliver_output(proposal_details, calls)?;
+	// Or prepare it for an offline signer.
+	if let Some((directory, signer, crypto, contexts)) = export {
+		let payloads = offline::signer_payloads(calls_to_submit, signer, crypto, &contexts)?;
+		let paths = offline::export_payloads(&payloads, &directory)?;
+		let log = |line: String| if json { eprintln!("{line}") } else { println!("{line}") };
+		log(String::from("\nPayloads for the offline signer:"));
+		for path in paths {
+			log(format!("  {path}"));
+		}
+		return Ok(());
+	}
+	// Or do it for them.
+	if let Some(signer) = signer {
+		let submissions = submit::submit_calls(calls_to_submit, &predicted, &signer, &rpc).await?;
+		if json {
+			// Keep stdout a single JSON document.
+			let document = serde_json::json!({ "submissions": submissions });
+			eprintln!("{}", serde_json::to_string_pretty(&document).expect("it serializes"));
+		} else {
+			submit::print_submissions(&submissions);
+		}
+	}
+	Ok(())
 }
 
 // Parse the CLI inputs and return a typed struct with all the details needed.
-fn parse_inputs(prefs: ReferendumArgs) -> ProposalDetails {
-	use DispatchTimeWrapper::*;
-	use NetworkTrack::*;
-	use Output::*;
-
+async fn parse_inputs(prefs: ReferendumArgs) -> Result<ProposalDetails, Error> {
 	let proposal = prefs.proposal;
+	let track = parse_track(&prefs.network, &prefs.track)?;
+	let output = parse_output(prefs.output)?;
+	let dispatch = parse_dispatch(prefs.at, prefs.after, &output)?;
 
-	let track = match prefs.network.to_ascii_lowercase().as_str() {
-		"polkadot" => match prefs.track.to_ascii_lowercase().as_str() {
-			"root" => PolkadotRoot,
-			"whitelisted-caller" | "whitelistedcaller" =>
-				Polkadot(PolkadotAssetHubOpenGovOrigin::WhitelistedCaller),
-			"staking-admin" | "stakingadmin" => Polkadot(PolkadotAssetHubOpenGovOrigin::StakingAdmin),
-			"treasurer" => Polkadot(PolkadotAssetHubOpenGovOrigin::Treasurer),
-			"lease-admin" | "leaseadmin" => Polkadot(PolkadotAssetHubOpenGovOrigin::LeaseAdmin),
-			"fellowship-admin" | "fellowshipadmin" =>
-				Polkadot(PolkadotAssetHubOpenGovOrigin::FellowshipAdmin),
-			"general-admin" | "generaladmin" => Polkadot(PolkadotAssetHubOpenGovOrigin::GeneralAdmin),
-			"auction-admin" | "auctionadmin" => Polkadot(PolkadotAssetHubOpenGovOrigin::AuctionAdmin),
-			"referendum-killer" | "referendumkiller" =>
-				Polkadot(PolkadotAssetHubOpenGovOrigin::ReferendumKiller),
-			"referendum-canceller" | "referendumcanceller" =>
-				Polkadot(PolkadotAssetHubOpenGovOrigin::ReferendumCanceller),
-			_ => panic!("Unsupported track! Tracks should be in the form `general-admin` or `generaladmin`."),
-		},
-		"kusama" => match prefs.track.to_ascii_lowercase().as_str() {
-			"root" => KusamaRoot,
-			"whitelisted-caller" | "whitelistedcaller" =>
-				Kusama(KusamaAssetHubOpenGovOrigin::WhitelistedCaller),
-			"staking-admin" | "stakingadmin" => Kusama(KusamaAssetHubOpenGovOrigin::StakingAdmin),
-			"treasurer" => Kusama(KusamaAssetHubOpenGovOrigin::Treasurer),
-			"lease-admin" | "leaseadmin" => Kusama(KusamaAssetHubOpenGovOrigin::LeaseAdmin),
-			"fellowship-admin" | "fellowshipadmin" => Kusama(KusamaAssetHubOpenGovOrigin::FellowshipAdmin),
-			"general-admin" | "generaladmin" => Kusama(KusamaAssetHubOpenGovOrigin::GeneralAdmin),
-			"auction-admin" | "auctionadmin" => Kusama(KusamaAssetHubOpenGovOrigin::AuctionAdmin),
-			"referendum-killer" | "referendumkiller" =>
-				Kusama(KusamaAssetHubOpenGovOrigin::ReferendumKiller),
-			"referendum-canceller" | "referendumcanceller" =>
-				Kusama(KusamaAssetHubOpenGovOrigin::ReferendumCanceller),
-			_ => panic!("Unsupported track! Tracks should be in the form `general-admin` or `generaladmin`."),
-		},
-		_ => panic!("`network` must be `polkadot` or `kusama`"),
+	let output_len_limit = prefs.output_len_limit.unwrap_or(1_000);
+
+	let print_batch = !prefs.no_batch;
+
+	let use_light_client = prefs.light_client;
+
+	let fellowship_on_polkadot = parse_fellowship(prefs.fellowship.as_deref())?;
+
+	let decision_deposit = if prefs.decision_deposit {
+		let indices = predict_referendum_indices(
+			&track,
+			fellowship_on_polkadot,
+			prefs.ref_index,
+			prefs.fellowship_ref_index,
+			&prefs.rpc,
+		)
+		.await?;
+		Some(indices)
+	} else {
+		None
 	};
 
-	let dispatch = match (prefs.at, prefs.after) {
-		(None, None) => {
-			println!("\nNo enactment time specified. Defaulting to `After(10)`.");
-			println!("Specify an enactment time with `--at <block>` or `--after <blocks>`.\n");
-			After(10)
-		},
-		(Some(_), Some(_)) => {
-			panic!("\nBoth `At` and `After` dispatch times provided. You can only use one.\n");
-		},
-		(Some(at), None) => At(at),
-		(None, Some(after)) => After(after),
+	let wrapper = Wrapper::parse(
+		prefs.multisig.as_deref(),
+		prefs.proxy.as_deref(),
+		&prefs.timepoint,
+		&prefs.max_weight,
+		&track.network(),
+	)?;
+
+	let builder = ProposalDetails::builder(proposal, track)
+		.dispatch(dispatch)
+		.output(output)
+		.output_len_limit(output_len_limit)
+		.print_batch(print_batch)
+		.use_light_client(use_light_client)
+		.fellowship_on_polkadot(fellowship_on_polkadot)
+		.metadata(MetadataOverrides::load(&prefs.metadata)?)
+		.wrapper(wrapper);
+	match decision_deposit {
+		Some(indices) => builder.decision_deposit(indices),
+		None => builder,
+	}
+	.build()
+}
+
+// The indices that the referenda on `track` will get: `ref_index` and `fellowship_ref_index` if
+// given, or else the `ReferendumCount` of their chain, queried from whichever of the nodes at
+// `rpc` runs it.
+pub(crate) async fn predict_referendum_indices(
+	track: &NetworkTrack,
+	fellowship_on_polkadot: bool,
+	ref_index: Option<u32>,
+	fellowship_ref_index: Option<u32>,
+	rpc: &[String],
+) -> Result<ReferendumIndices, Error> {
+	let mut nodes = Vec::new();
+	for url in rpc {
+		let node = Node::connect(url).await?;
+		nodes.push((node.network().await?, node));
+	}
+	let public =
+		next_referendum_index(ref_index, &nodes, public_network(track), "Referenda", "--ref-index")
+			.await?;
+	let fellowship = match fellowship_network(track, fellowship_on_polkadot) {
+		Some(network) => Some(
+			next_referendum_index(
+				fellowship_ref_index,
+				&nodes,
+				network,
+				"FellowshipReferenda",
+				"--fellowship-ref-index",
+			)
+			.await?,
+		),
+		None => None,
 	};
+	Ok(ReferendumIndices { public, fellowship })
+}
 
-	let output_len_limit = prefs.output_len_limit.unwrap_or(1_000);
+// The `given` index, or else the `ReferendumCount` of `pallet` on the node in `nodes` that runs
+// `network`.
+async fn next_referendum_index(
+	given: Option<u32>,
+	nodes: &[(Network, Node)],
+	network: Network,
+	pallet: &str,
+	flag: &str,
+) -> Result<u32, Error> {
+	if let Some(index) = given {
+		return Ok(index);
+	}
+	match nodes.iter().find(|(node_network, _)| *node_network == network) {
+		Some((_, node)) => node.referendum_count(pallet).await,
+		None => Err(Error::InvalidInput(format!(
+			"Pass `{flag}` or a {network:?} node with `--rpc` to place the decision deposit."
+		))),
+	}
+}
 
-	let print_batch = !prefs.no_batch;
+// The network of the public referendum, which is always on the Asset Hub of the chosen network.
+pub(crate) fn public_network(track: &NetworkTrack) -> Network {
+	match track {
+		NetworkTrack::KusamaRoot | NetworkTrack::Kusama(_) => Network::KusamaAssetHub,
+		NetworkTrack::PolkadotRoot | NetworkTrack::Polkadot(_) => Network::PolkadotAssetHub,
+	}
+}
+
+// The network of the Fellowship referendum to whitelist a proposal on `track`, if it needs one.
+fn fellowship_network(track: &NetworkTrack, fellowship_on_polkadot: bool) -> Option<Network> {
+	match track {
+		NetworkTrack::Polkadot(PolkadotAssetHubOpenGovOrigin::WhitelistedCaller) =>
+			Some(Network::PolkadotCollectives),
+		NetworkTrack::Kusama(KusamaAssetHubOpenGovOrigin::WhitelistedCaller) =>
+			if fellowship_on_polkadot {
+				Some(Network::PolkadotCollectives)
+			} else {
+				Some(Network::Kusama)
+			},
+		_ => None,
+	}
+}
+
+// The referenda whose decision deposits the calls for `proposal_details` place, if any.
+pub(crate) fn predicted_referenda(
+	proposal_details: &ProposalDetails,
+) -> Vec<submit::PredictedReferendum> {
+	let Some(indices) = proposal_details.decision_deposit else {
+		return Vec::new();
+	};
+	let track = &proposal_details.track;
+	let mut predicted = vec![submit::PredictedReferendum {
+		network: public_network(track),
+		pallet: "Referenda",
+		index: indices.public,
+	}];
+	let fellowship = fellowship_network(track, proposal_details.fellowship_on_polkadot);
+	if let (Some(network), Some(index)) = (fellowship, indices.fellowship) {
+		predicted.push(submit::PredictedReferendum {
+			network,
+			pallet: "FellowshipReferenda",
+			index,
+		});
+	}
+	predicted
+}
 
-	let output = if let Some(input) = prefs.output {
+/// The call to place the decision deposit of the referendum with `index` on `network`: in the
+/// `FellowshipReferenda` pallet on the Kusama Relay Chain or Polkadot Collectives, and in the
+/// `Referenda` pallet on either Asset Hub.
+pub fn place_decision_deposit(network: &Network, index: u32) -> Result<NetworkRuntimeCall, Error> {
+	use kusama_asset_hub::runtime_types::pallet_referenda::pallet::Call as KusamaAssetHubReferendaCall;
+	use kusama_relay::runtime_types::pallet_referenda::pallet::Call as KusamaReferendaCall;
+	use polkadot_asset_hub::runtime_types::pallet_referenda::pallet::Call as PolkadotAssetHubReferendaCall;
+	use polkadot_collectives::runtime_types::pallet_referenda::pallet::Call as CollectivesReferendaCall;
+
+	Ok(match network {
+		Network::Kusama => NetworkRuntimeCall::Kusama(KusamaRuntimeCall::FellowshipReferenda(
+			KusamaReferendaCall::place_decision_deposit { index },
+		)),
+		Network::KusamaAssetHub =>
+			NetworkRuntimeCall::KusamaAssetHub(KusamaAssetHubRuntimeCall::Referenda(
+				KusamaAssetHubReferendaCall::place_decision_deposit { index },
+			)),
+		Network::PolkadotAssetHub =>
+			NetworkRuntimeCall::PolkadotAssetHub(PolkadotAssetHubRuntimeCall::Referenda(
+				PolkadotAssetHubReferendaCall::place_decision_deposit { index },
+			)),
+		Network::PolkadotCollectives =>
+			NetworkRuntimeCall::PolkadotCollectives(CollectivesRuntimeCall::FellowshipReferenda(
+				CollectivesReferendaCall::place_decision_deposit { index },
+			)),
+		_ =>
+			return Err(Error::UnsupportedNetwork(format!(
+				"{network:?} has no referenda to place a decision deposit for."
+			))),
+	})
+}
+
+// Parse a user-provided `track` on `network`.
+pub(crate) fn parse_track(network: &str, track: &str) -> Result<NetworkTrack, Error> {
+	let network = match network.to_ascii_lowercase().as_str() {
+		"polkadot" => Network::Polkadot,
+		"kusama" => Network::Kusama,
+		_ =>
+			return Err(Error::UnsupportedNetwork(String::from(
+				"`network` must be `polkadot` or `kusama`.",
+			))),
+	};
+	list_tracks::track_by_name(&network, track)?.ok_or_else(|| {
+		Error::UnsupportedTrack(format!(
+			"Tracks should be in the form `general-admin` or `generaladmin`. Run `list-tracks` to \
+			see the tracks on {network:?}."
+		))
+	})
+}
+
+// Parse a user-provided `--fellowship`, returning whether to use the Polkadot Fellowship. Defaults
+// to the Kusama Fellowship.
+pub(crate) fn parse_fellowship(fellowship: Option<&str>) -> Result<bool, Error> {
+	match fellowship.map(|f| f.to_ascii_lowercase()).as_deref() {
+		None | Some("kusama") => Ok(false),
+		Some("polkadot") => Ok(true),
+		_ =>
+			Err(Error::InvalidInput(String::from("`--fellowship` must be `kusama` or `polkadot`."))),
+	}
+}
+
+// Parse a user-provided `--output`, defaulting to `AppsUiLink`.
+pub(crate) fn parse_output(output: Option<String>) -> Result<Output, Error> {
+	use Output::*;
+
+	Ok(if let Some(input) = output {
 		match input.to_ascii_lowercase().as_str() {
 			"calldata" | "call-data" => CallData,
 			"appsuilink" | "apps-ui-link" => AppsUiLink,
-			_ => panic!("`output` must be `calldata` or `appsuilink`. If not specified, the default is `appsuilink`."),
+			"json" => Json,
+			_ => return Err(Error::InvalidInput(String::from("`output` must be `calldata`, `appsuilink`, or `json`. If not specified, the default is `appsuilink`."))),
 		}
 	} else {
 		AppsUiLink
-	};
-
-	let use_light_client = prefs.light_client;
-
-	let fellowship_on_polkadot = if let Some(ref f) = prefs.fellowship {
-		match f.to_ascii_lowercase().as_str() {
-			"kusama" => false,
-			"polkadot" => true,
-			_ => panic!("`--fellowship` must be `kusama` or `polkadot`."),
-		}
-	} else {
-		false
-	};
+	})
+}
 
-	if fellowship_on_polkadot {
-		let is_kusama_whitelisted =
-			matches!(track, NetworkTrack::Kusama(KusamaAssetHubOpenGovOrigin::WhitelistedCaller));
-		if !is_kusama_whitelisted {
-			panic!("`--fellowship polkadot` is only supported with `--network kusama --track whitelistedcaller`.");
-		}
-	}
+// Parse the user-provided `--at` and `--after` enactment times, defaulting to `After(10)`.
+pub(crate) fn parse_dispatch(
+	at: Option<u32>,
+	after: Option<u32>,
+	output: &Output,
+) -> Result<DispatchTimeWrapper, Error> {
+	use DispatchTimeWrapper::*;
 
-	ProposalDetails {
-		proposal,
-		track,
-		dispatch,
-		output,
-		output_len_limit,
-		print_batch,
-		use_light_client,
-		fellowship_on_polkadot,
-	}
+	Ok(match (at, after) {
+		(None, None) => {
+			// Keep stdout a single JSON document when using JSON output.
+			if !matches!(output, Output::Json) {
+				println!("\nNo enactment time specified. Defaulting to `After(10)`.");
+				println!("Specify an enactment time with `--at <block>` or `--after <blocks>`.\n");
+			}
+			After(10)
+		},
+		(Some(_), Some(_)) =>
+			return Err(Error::InvalidInput(String::from(
+				"Both `At` and `After` dispatch times provided. You can only use one.",
+			))),
+		(Some(at), None) => At(at),
+		(None, Some(after)) => After(after),
+	})
 }
 
-// Generate all the calls needed.
-pub(crate) async fn generate_calls(proposal_details: &ProposalDetails) -> PossibleCallsToSubmit {
+/// Generate all the calls needed to submit the proposal in `proposal_details` as a referendum.
+pub async fn generate_calls(
+	proposal_details: &ProposalDetails,
+) -> Result<PossibleCallsToSubmit, Error> {
 	match &proposal_details.track {
 		// Kusama Root Origin. Since the Root origin is not part of `OpenGovOrigin`, we match it
 		// specially.
@@ -220,7 +519,9 @@ pub(crate) async fn generate_calls(proposal_details: &ProposalDetails) -> Possib
 }
 
 // Generate the calls needed for a proposal to pass through the Fellowship.
-async fn kusama_fellowship_referenda(proposal_details: &ProposalDetails) -> PossibleCallsToSubmit {
+async fn kusama_fellowship_referenda(
+	proposal_details: &ProposalDetails,
+) -> Result<PossibleCallsToSubmit, Error> {
 	use kusama_asset_hub::runtime_types::{
 		frame_support::traits::{preimages::Bounded::Lookup, schedule::DispatchTime},
 		pallet_preimage::pallet::Call as PreimageCall,
@@ -265,7 +566,7 @@ async fn kusama_fellowship_referenda(proposal_details: &ProposalDetails) -> Poss
 	// 3. Make a Fellowship referendum for `send_whitelist`.
 	//
 	// 4. Make a public referendum on Asset Hub.
-	let proposal_bytes = get_proposal_bytes(proposal_details.proposal.clone());
+	let proposal_bytes = get_proposal_bytes(proposal_details.proposal.clone())?;
 	let proposal_call_info = CallInfo::from_bytes(&proposal_bytes, Network::KusamaAssetHub);
 
 	let public_referendum_dispatch_time = match proposal_details.dispatch {
@@ -273,14 +574,14 @@ async fn kusama_fellowship_referenda(proposal_details: &ProposalDetails) -> Poss
 		DispatchTimeWrapper::After(block) => DispatchTime::After(block),
 	};
 
-	let whitelist_call = CallInfo::from_runtime_call(NetworkRuntimeCall::KusamaAssetHub(
-		KusamaAssetHubRuntimeCall::Whitelist(WhitelistCall::whitelist_call {
-			call_hash: H256(proposal_call_info.hash),
-		}),
-	));
+	let whitelist_call = proposal_details.metadata.call_info(
+		NetworkRuntimeCall::KusamaAssetHub(KusamaAssetHubRuntimeCall::Whitelist(
+			WhitelistCall::whitelist_call { call_hash: H256(proposal_call_info.hash) },
+		)),
+	)?;
 
 	// This is what the Fellowship will actually vote on enacting.
-	let whitelist_over_xcm = CallInfo::from_runtime_call(NetworkRuntimeCall::Kusama(
+	let whitelist_over_xcm = proposal_details.metadata.call_info(NetworkRuntimeCall::Kusama(
 		KusamaRuntimeCall::XcmPallet(KusamaXcmCall::send {
 			dest: Box::new(VersionedLocation::V5(Location {
 				parents: 0,
@@ -298,60 +599,63 @@ async fn kusama_fellowship_referenda(proposal_details: &ProposalDetails) -> Poss
 				},
 			]))),
 		}),
-	));
+	))?;
 
 	// The Inline limit is 128 bytes.
-	assert!(
-		whitelist_over_xcm.length <= 128,
-		"Fellowship proposal exceeds Inline limit of 128 bytes ({} bytes). There is no longer a preimage pallet on Kusama Relay Chain, try again as a root ref.",
-		whitelist_over_xcm.length
-	);
+	if whitelist_over_xcm.length > 128 {
+		return Err(Error::InvalidInput(format!(
+			"Fellowship proposal exceeds Inline limit of 128 bytes ({} bytes). There is no longer a preimage pallet on Kusama Relay Chain, try again as a root ref.",
+			whitelist_over_xcm.length
+		)));
+	}
 
 	// The actual Fellowship referendum submission.
-	let fellowship_proposal = CallInfo::from_runtime_call(NetworkRuntimeCall::Kusama(
+	let fellowship_proposal = proposal_details.metadata.call_info(NetworkRuntimeCall::Kusama(
 		KusamaRuntimeCall::FellowshipReferenda(KusamaReferendaCall::submit {
 			proposal_origin: Box::new(KusamaOriginCaller::Origins(KusamaOpenGovOrigin::Fellows)),
-			proposal: Inline(BoundedVec(whitelist_over_xcm.encoded)),
+			proposal: Inline(BoundedVec(whitelist_over_xcm.encoded.clone())),
 			enactment_moment: KusamaDispatchTime::After(10u32),
 		}),
-	));
+	))?;
 
 	// Now we put together the public referendum part. This still needs separate logic because the
 	// actual proposal gets wrapped in a Whitelist call.
-	let dispatch_whitelisted_call = CallInfo::from_runtime_call(
+	let dispatch_whitelisted_call = proposal_details.metadata.call_info(
 		NetworkRuntimeCall::KusamaAssetHub(KusamaAssetHubRuntimeCall::Whitelist(
 			WhitelistCall::dispatch_whitelisted_call_with_preimage {
 				call: Box::new(
-					proposal_call_info
-						.get_kusama_asset_hub_call()
-						.expect("it is a kusama asset hub call"),
+					proposal_details
+						.metadata
+						.to_bundled(&proposal_call_info)?
+						.get_kusama_asset_hub_call()?,
 				),
 			},
 		)),
-	);
+	)?;
 
-	let preimage_for_dispatch_whitelisted_call = CallInfo::from_runtime_call(
+	let preimage_for_dispatch_whitelisted_call = proposal_details.metadata.call_info(
 		NetworkRuntimeCall::KusamaAssetHub(KusamaAssetHubRuntimeCall::Preimage(
 			PreimageCall::note_preimage { bytes: dispatch_whitelisted_call.encoded.clone() },
 		)),
-	);
-	let public_proposal = CallInfo::from_runtime_call(NetworkRuntimeCall::KusamaAssetHub(
-		KusamaAssetHubRuntimeCall::Referenda(ReferendaCall::submit {
-			proposal_origin: Box::new(KusamaAssetHubOriginCaller::Origins(
-				KusamaAssetHubOpenGovOrigin::WhitelistedCaller,
-			)),
-			proposal: Lookup {
-				hash: H256(dispatch_whitelisted_call.hash),
-				len: dispatch_whitelisted_call.length,
-			},
-			enactment_moment: public_referendum_dispatch_time,
-		}),
-	));
+	)?;
+	let public_proposal =
+		proposal_details.metadata.call_info(NetworkRuntimeCall::KusamaAssetHub(
+			KusamaAssetHubRuntimeCall::Referenda(ReferendaCall::submit {
+				proposal_origin: Box::new(KusamaAssetHubOriginCaller::Origins(
+					KusamaAssetHubOpenGovOrigin::WhitelistedCaller,
+				)),
+				proposal: Lookup {
+					hash: H256(dispatch_whitelisted_call.hash),
+					len: dispatch_whitelisted_call.length,
+				},
+				enactment_moment: public_referendum_dispatch_time,
+			}),
+		))?;
 
 	// Check the lengths and prepare preimages for printing.
 	let (dispatch_preimage_print, dispatch_preimage_print_len) =
 		preimage_for_dispatch_whitelisted_call
-			.create_print_output(proposal_details.output_len_limit);
+			.create_print_output(proposal_details.output_len_limit, &proposal_details.metadata)?;
 
 	// If it's a hash, let's write the data to a file you can upload.
 	match dispatch_preimage_print {
@@ -359,24 +663,24 @@ async fn kusama_fellowship_referenda(proposal_details: &ProposalDetails) -> Poss
 		CallOrHash::Hash(_) => {
 			let mut info_to_write = "0x".to_owned();
 			info_to_write.push_str(hex::encode(dispatch_whitelisted_call.encoded).as_str());
-			fs::write("kusama_asset_hub_public_referendum_preimage_to_note.call", info_to_write)
-				.expect("it should write");
+			write_file("kusama_asset_hub_public_referendum_preimage_to_note.call", info_to_write)?;
 		},
 	}
 
-	PossibleCallsToSubmit {
+	Ok(PossibleCallsToSubmit {
 		preimage_for_whitelist_call: None,
 		preimage_for_public_referendum: Some((
 			dispatch_preimage_print,
 			dispatch_preimage_print_len,
 		)),
 		fellowship_referendum_submission: Some(NetworkRuntimeCall::Kusama(
-			fellowship_proposal.get_kusama_call().expect("kusama relay"),
+			proposal_details.metadata.to_bundled(&fellowship_proposal)?.get_kusama_call()?,
 		)),
 		public_referendum_submission: Some(NetworkRuntimeCall::KusamaAssetHub(
-			public_proposal.get_kusama_asset_hub_call().expect("kusama asset hub"),
+			proposal_details.metadata.to_bundled(&public_proposal)?.get_kusama_asset_hub_call()?,
 		)),
-	}
+		whitelist_over_xcm: Some(whitelist_over_xcm),
+	})
 }
 
 // Generate the calls needed for a Kusama proposal to pass through the Polkadot Fellowship.
@@ -384,7 +688,7 @@ async fn kusama_fellowship_referenda(proposal_details: &ProposalDetails) -> Poss
 // targets Kusama Asset Hub.
 async fn polkadot_fellowship_for_kusama_referenda(
 	proposal_details: &ProposalDetails,
-) -> PossibleCallsToSubmit {
+) -> Result<PossibleCallsToSubmit, Error> {
 	use kusama_asset_hub::runtime_types::{
 		frame_support::traits::{preimages::Bounded::Lookup, schedule::DispatchTime},
 		pallet_preimage::pallet::Call as PreimageCall,
@@ -420,7 +724,7 @@ async fn polkadot_fellowship_for_kusama_referenda(
 	//    via Polkadot Asset Hub and the bridge.
 	// 3. Make a Fellowship referendum on Collectives for the XCM send.
 	// 4. Make a public referendum on Kusama Asset Hub.
-	let proposal_bytes = get_proposal_bytes(proposal_details.proposal.clone());
+	let proposal_bytes = get_proposal_bytes(proposal_details.proposal.clone())?;
 	let proposal_call_info = CallInfo::from_bytes(&proposal_bytes, Network::KusamaAssetHub);
 
 	let public_referendum_dispatch_time = match proposal_details.dispatch {
@@ -429,11 +733,11 @@ async fn polkadot_fellowship_for_kusama_referenda(
 	};
 
 	// whitelist_call for AHK
-	let whitelist_call = CallInfo::from_runtime_call(NetworkRuntimeCall::KusamaAssetHub(
-		KusamaAssetHubRuntimeCall::Whitelist(WhitelistCall::whitelist_call {
-			call_hash: H256(proposal_call_info.hash),
-		}),
-	));
+	let whitelist_call = proposal_details.metadata.call_info(
+		NetworkRuntimeCall::KusamaAssetHub(KusamaAssetHubRuntimeCall::Whitelist(
+			WhitelistCall::whitelist_call { call_hash: H256(proposal_call_info.hash) },
+		)),
+	)?;
 
 	// XCM to execute on AHK (final destination, appended by InitiateTransfer)
 	let xcm_on_ahk = Xcm(vec![
@@ -467,87 +771,98 @@ async fn polkadot_fellowship_for_kusama_referenda(
 	// This is what the Fellowship will actually vote on enacting.
 	// Send from Collectives to Polkadot Asset Hub (sibling parachain), which then bridges
 	// to Kusama Asset Hub via InitiateTransfer.
-	let whitelist_over_xcm = CallInfo::from_runtime_call(NetworkRuntimeCall::PolkadotCollectives(
-		CollectivesRuntimeCall::PolkadotXcm(CollectivesXcmCall::send {
-			dest: Box::new(VersionedLocation::V5(Location {
-				parents: 1,
-				interior: X1([Parachain(1000)]),
-			})),
-			message: Box::new(V5(xcm_for_ahp)),
-		}),
-	));
+	let whitelist_over_xcm =
+		proposal_details.metadata.call_info(NetworkRuntimeCall::PolkadotCollectives(
+			CollectivesRuntimeCall::PolkadotXcm(CollectivesXcmCall::send {
+				dest: Box::new(VersionedLocation::V5(Location {
+					parents: 1,
+					interior: X1([Parachain(1000)]),
+				})),
+				message: Box::new(V5(xcm_for_ahp)),
+			}),
+		))?;
 
 	// The Inline limit is 128 bytes. Use Inline if within limit, otherwise fall back to Lookup.
 	let (fellowship_proposal, preimage_for_whitelist_over_xcm) = if whitelist_over_xcm.length <= 128
 	{
-		let proposal = CallInfo::from_runtime_call(NetworkRuntimeCall::PolkadotCollectives(
-			CollectivesRuntimeCall::FellowshipReferenda(CollectivesReferendaCall::submit {
-				proposal_origin: Box::new(CollectivesOriginCaller::FellowshipOrigins(
-					FellowshipOrigins::Fellows,
-				)),
-				proposal: CollectivesInline(CollectivesBoundedVec(whitelist_over_xcm.encoded)),
-				enactment_moment: CollectivesDispatchTime::After(10u32),
-			}),
-		));
+		let proposal =
+			proposal_details.metadata.call_info(NetworkRuntimeCall::PolkadotCollectives(
+				CollectivesRuntimeCall::FellowshipReferenda(CollectivesReferendaCall::submit {
+					proposal_origin: Box::new(CollectivesOriginCaller::FellowshipOrigins(
+						FellowshipOrigins::Fellows,
+					)),
+					proposal: CollectivesInline(CollectivesBoundedVec(
+						whitelist_over_xcm.encoded.clone(),
+					)),
+					enactment_moment: CollectivesDispatchTime::After(10u32),
+				}),
+			))?;
 		(proposal, None)
 	} else {
-		let preimage = CallInfo::from_runtime_call(NetworkRuntimeCall::PolkadotCollectives(
-			CollectivesRuntimeCall::Preimage(CollectivesPreimageCall::note_preimage {
-				bytes: whitelist_over_xcm.encoded.clone(),
-			}),
-		));
-		let proposal = CallInfo::from_runtime_call(NetworkRuntimeCall::PolkadotCollectives(
-			CollectivesRuntimeCall::FellowshipReferenda(CollectivesReferendaCall::submit {
-				proposal_origin: Box::new(CollectivesOriginCaller::FellowshipOrigins(
-					FellowshipOrigins::Fellows,
-				)),
-				proposal: CollectivesLookup {
-					hash: H256(whitelist_over_xcm.hash),
-					len: whitelist_over_xcm.length,
-				},
-				enactment_moment: CollectivesDispatchTime::After(10u32),
-			}),
-		));
+		let preimage =
+			proposal_details.metadata.call_info(NetworkRuntimeCall::PolkadotCollectives(
+				CollectivesRuntimeCall::Preimage(CollectivesPreimageCall::note_preimage {
+					bytes: whitelist_over_xcm.encoded.clone(),
+				}),
+			))?;
+		let proposal =
+			proposal_details.metadata.call_info(NetworkRuntimeCall::PolkadotCollectives(
+				CollectivesRuntimeCall::FellowshipReferenda(CollectivesReferendaCall::submit {
+					proposal_origin: Box::new(CollectivesOriginCaller::FellowshipOrigins(
+						FellowshipOrigins::Fellows,
+					)),
+					proposal: CollectivesLookup {
+						hash: H256(whitelist_over_xcm.hash),
+						len: whitelist_over_xcm.length,
+					},
+					enactment_moment: CollectivesDispatchTime::After(10u32),
+				}),
+			))?;
 		(proposal, Some(preimage))
 	};
 
 	// Now we put together the public referendum part on Kusama Asset Hub.
-	let dispatch_whitelisted_call = CallInfo::from_runtime_call(
+	let dispatch_whitelisted_call = proposal_details.metadata.call_info(
 		NetworkRuntimeCall::KusamaAssetHub(KusamaAssetHubRuntimeCall::Whitelist(
 			WhitelistCall::dispatch_whitelisted_call_with_preimage {
 				call: Box::new(
-					proposal_call_info
-						.get_kusama_asset_hub_call()
-						.expect("it is a kusama asset hub call"),
+					proposal_details
+						.metadata
+						.to_bundled(&proposal_call_info)?
+						.get_kusama_asset_hub_call()?,
 				),
 			},
 		)),
-	);
+	)?;
 
-	let preimage_for_dispatch_whitelisted_call = CallInfo::from_runtime_call(
+	let preimage_for_dispatch_whitelisted_call = proposal_details.metadata.call_info(
 		NetworkRuntimeCall::KusamaAssetHub(KusamaAssetHubRuntimeCall::Preimage(
 			PreimageCall::note_preimage { bytes: dispatch_whitelisted_call.encoded.clone() },
 		)),
-	);
-	let public_proposal = CallInfo::from_runtime_call(NetworkRuntimeCall::KusamaAssetHub(
-		KusamaAssetHubRuntimeCall::Referenda(ReferendaCall::submit {
-			proposal_origin: Box::new(KusamaAssetHubOriginCaller::Origins(
-				KusamaAssetHubOpenGovOrigin::WhitelistedCaller,
-			)),
-			proposal: Lookup {
-				hash: H256(dispatch_whitelisted_call.hash),
-				len: dispatch_whitelisted_call.length,
-			},
-			enactment_moment: public_referendum_dispatch_time,
-		}),
-	));
+	)?;
+	let public_proposal =
+		proposal_details.metadata.call_info(NetworkRuntimeCall::KusamaAssetHub(
+			KusamaAssetHubRuntimeCall::Referenda(ReferendaCall::submit {
+				proposal_origin: Box::new(KusamaAssetHubOriginCaller::Origins(
+					KusamaAssetHubOpenGovOrigin::WhitelistedCaller,
+				)),
+				proposal: Lookup {
+					hash: H256(dispatch_whitelisted_call.hash),
+					len: dispatch_whitelisted_call.length,
+				},
+				enactment_moment: public_referendum_dispatch_time,
+			}),
+		))?;
 
 	// Check the lengths and prepare preimages for printing.
 	let whitelist_preimage_print = preimage_for_whitelist_over_xcm
-		.map(|p| p.create_print_output(proposal_details.output_len_limit));
+		.map(|p| {
+			p.create_print_output(proposal_details.output_len_limit, &proposal_details.metadata)
+		})
+		.transpose()?;
 	let (dispatch_preimage_print, dispatch_preimage_print_len) =
 		preimage_for_dispatch_whitelisted_call
-			.create_print_output(proposal_details.output_len_limit);
+			.create_print_output(proposal_details.output_len_limit, &proposal_details.metadata)?;
 
 	// If it's a hash, let's write the data to a file you can upload.
 	match dispatch_preimage_print {
@@ -555,38 +870,41 @@ async fn polkadot_fellowship_for_kusama_referenda(
 		CallOrHash::Hash(_) => {
 			let mut info_to_write = "0x".to_owned();
 			info_to_write.push_str(hex::encode(dispatch_whitelisted_call.encoded).as_str());
-			fs::write("kusama_asset_hub_public_referendum_preimage_to_note.call", info_to_write)
-				.expect("it should write");
+			write_file("kusama_asset_hub_public_referendum_preimage_to_note.call", info_to_write)?;
 		},
 	}
 
-	PossibleCallsToSubmit {
+	Ok(PossibleCallsToSubmit {
 		preimage_for_whitelist_call: whitelist_preimage_print,
 		preimage_for_public_referendum: Some((
 			dispatch_preimage_print,
 			dispatch_preimage_print_len,
 		)),
 		fellowship_referendum_submission: Some(NetworkRuntimeCall::PolkadotCollectives(
-			fellowship_proposal.get_polkadot_collectives_call().expect("polkadot collectives"),
+			proposal_details
+				.metadata
+				.to_bundled(&fellowship_proposal)?
+				.get_polkadot_collectives_call()?,
 		)),
 		public_referendum_submission: Some(NetworkRuntimeCall::KusamaAssetHub(
-			public_proposal.get_kusama_asset_hub_call().expect("kusama asset hub"),
+			proposal_details.metadata.to_bundled(&public_proposal)?.get_kusama_asset_hub_call()?,
 		)),
-	}
+		whitelist_over_xcm: Some(whitelist_over_xcm),
+	})
 }
 
 // Generate the calls needed for a proposal to pass on Kusama without the Fellowship.
 fn kusama_non_fellowship_referenda(
 	proposal_details: &ProposalDetails,
 	origin: KusamaAssetHubOriginCaller,
-) -> PossibleCallsToSubmit {
+) -> Result<PossibleCallsToSubmit, Error> {
 	use kusama_asset_hub::runtime_types::{
 		frame_support::traits::{preimages::Bounded::Lookup, schedule::DispatchTime},
 		pallet_preimage::pallet::Call as PreimageCall,
 		pallet_referenda::pallet::Call as ReferendaCall,
 	};
 
-	let proposal_bytes = get_proposal_bytes(proposal_details.proposal.clone());
+	let proposal_bytes = get_proposal_bytes(proposal_details.proposal.clone())?;
 	let proposal_call_info = CallInfo::from_bytes(&proposal_bytes, Network::KusamaAssetHub);
 
 	let public_referendum_dispatch_time = match proposal_details.dispatch {
@@ -594,36 +912,40 @@ fn kusama_non_fellowship_referenda(
 		DispatchTimeWrapper::After(block) => DispatchTime::After(block),
 	};
 
-	let note_proposal_preimage = CallInfo::from_runtime_call(NetworkRuntimeCall::KusamaAssetHub(
-		KusamaAssetHubRuntimeCall::Preimage(PreimageCall::note_preimage { bytes: proposal_bytes }),
-	));
-	let public_proposal = CallInfo::from_runtime_call(NetworkRuntimeCall::KusamaAssetHub(
-		KusamaAssetHubRuntimeCall::Referenda(ReferendaCall::submit {
-			proposal_origin: Box::new(origin),
-			proposal: Lookup {
-				hash: H256(proposal_call_info.hash),
-				len: proposal_call_info.length,
-			},
-			enactment_moment: public_referendum_dispatch_time,
-		}),
-	));
-	let (preimage_print, preimage_print_len) =
-		note_proposal_preimage.create_print_output(proposal_details.output_len_limit);
+	let note_proposal_preimage = proposal_details.metadata.call_info(
+		NetworkRuntimeCall::KusamaAssetHub(KusamaAssetHubRuntimeCall::Preimage(
+			PreimageCall::note_preimage { bytes: proposal_bytes },
+		)),
+	)?;
+	let public_proposal =
+		proposal_details.metadata.call_info(NetworkRuntimeCall::KusamaAssetHub(
+			KusamaAssetHubRuntimeCall::Referenda(ReferendaCall::submit {
+				proposal_origin: Box::new(origin),
+				proposal: Lookup {
+					hash: H256(proposal_call_info.hash),
+					len: proposal_call_info.length,
+				},
+				enactment_moment: public_referendum_dispatch_time,
+			}),
+		))?;
+	let (preimage_print, preimage_print_len) = note_proposal_preimage
+		.create_print_output(proposal_details.output_len_limit, &proposal_details.metadata)?;
 
-	PossibleCallsToSubmit {
+	Ok(PossibleCallsToSubmit {
 		preimage_for_whitelist_call: None,
 		preimage_for_public_referendum: Some((preimage_print, preimage_print_len)),
 		fellowship_referendum_submission: None,
 		public_referendum_submission: Some(NetworkRuntimeCall::KusamaAssetHub(
-			public_proposal.get_kusama_asset_hub_call().expect("kusama asset hub"),
+			proposal_details.metadata.to_bundled(&public_proposal)?.get_kusama_asset_hub_call()?,
 		)),
-	}
+		whitelist_over_xcm: None,
+	})
 }
 
 // Generate the calls needed for a proposal to pass through the Polkadot Fellowship.
 async fn polkadot_fellowship_referenda(
 	proposal_details: &ProposalDetails,
-) -> PossibleCallsToSubmit {
+) -> Result<PossibleCallsToSubmit, Error> {
 	use polkadot_asset_hub::runtime_types::{
 		frame_support::traits::{preimages::Bounded::Lookup, schedule::DispatchTime},
 		pallet_preimage::pallet::Call as PreimageCall,
@@ -672,7 +994,7 @@ async fn polkadot_fellowship_referenda(
 	// 3. Make a Fellowship referendum for `send_whitelist`.
 	//
 	// 4. Make a public referendum on Asset Hub.
-	let proposal_bytes = get_proposal_bytes(proposal_details.proposal.clone());
+	let proposal_bytes = get_proposal_bytes(proposal_details.proposal.clone())?;
 	let proposal_call_info = CallInfo::from_bytes(&proposal_bytes, Network::PolkadotAssetHub);
 
 	let public_referendum_dispatch_time = match proposal_details.dispatch {
@@ -680,105 +1002,116 @@ async fn polkadot_fellowship_referenda(
 		DispatchTimeWrapper::After(block) => DispatchTime::After(block),
 	};
 	// Whitelist the call on the Relay Chain.
-	let whitelist_call = CallInfo::from_runtime_call(NetworkRuntimeCall::PolkadotAssetHub(
-		PolkadotAssetHubRuntimeCall::Whitelist(WhitelistCall::whitelist_call {
-			call_hash: H256(proposal_call_info.hash),
-		}),
-	));
+	let whitelist_call = proposal_details.metadata.call_info(
+		NetworkRuntimeCall::PolkadotAssetHub(PolkadotAssetHubRuntimeCall::Whitelist(
+			WhitelistCall::whitelist_call { call_hash: H256(proposal_call_info.hash) },
+		)),
+	)?;
 
 	// This is what the Fellowship will actually vote on enacting.
-	let whitelist_over_xcm = CallInfo::from_runtime_call(NetworkRuntimeCall::PolkadotCollectives(
-		CollectivesRuntimeCall::PolkadotXcm(CollectivesXcmCall::send {
-			dest: Box::new(VersionedLocation::V5(Location {
-				parents: 1,
-				interior: X1([Parachain(1000)]),
-			})),
-			message: Box::new(V5(Xcm(vec![
-				Instruction::UnpaidExecution {
-					weight_limit: WeightLimit::Unlimited,
-					check_origin: None,
-				},
-				Instruction::Transact {
-					origin_kind: OriginKind::Xcm,
-					fallback_max_weight: None,
-					call: DoubleEncoded { encoded: whitelist_call.encoded },
-				},
-			]))),
-		}),
-	));
+	let whitelist_over_xcm =
+		proposal_details.metadata.call_info(NetworkRuntimeCall::PolkadotCollectives(
+			CollectivesRuntimeCall::PolkadotXcm(CollectivesXcmCall::send {
+				dest: Box::new(VersionedLocation::V5(Location {
+					parents: 1,
+					interior: X1([Parachain(1000)]),
+				})),
+				message: Box::new(V5(Xcm(vec![
+					Instruction::UnpaidExecution {
+						weight_limit: WeightLimit::Unlimited,
+						check_origin: None,
+					},
+					Instruction::Transact {
+						origin_kind: OriginKind::Xcm,
+						fallback_max_weight: None,
+						call: DoubleEncoded { encoded: whitelist_call.encoded },
+					},
+				]))),
+			}),
+		))?;
 
 	// The Inline limit is 128 bytes. Use Inline if within limit, otherwise fall back to Lookup.
 	let (fellowship_proposal, preimage_for_whitelist_over_xcm) = if whitelist_over_xcm.length <= 128
 	{
-		let proposal = CallInfo::from_runtime_call(NetworkRuntimeCall::PolkadotCollectives(
-			CollectivesRuntimeCall::FellowshipReferenda(CollectivesReferendaCall::submit {
-				proposal_origin: Box::new(CollectivesOriginCaller::FellowshipOrigins(
-					FellowshipOrigins::Fellows,
-				)),
-				proposal: CollectivesInline(CollectivesBoundedVec(whitelist_over_xcm.encoded)),
-				enactment_moment: CollectivesDispatchTime::After(10u32),
-			}),
-		));
+		let proposal =
+			proposal_details.metadata.call_info(NetworkRuntimeCall::PolkadotCollectives(
+				CollectivesRuntimeCall::FellowshipReferenda(CollectivesReferendaCall::submit {
+					proposal_origin: Box::new(CollectivesOriginCaller::FellowshipOrigins(
+						FellowshipOrigins::Fellows,
+					)),
+					proposal: CollectivesInline(CollectivesBoundedVec(
+						whitelist_over_xcm.encoded.clone(),
+					)),
+					enactment_moment: CollectivesDispatchTime::After(10u32),
+				}),
+			))?;
 		(proposal, None)
 	} else {
-		let preimage = CallInfo::from_runtime_call(NetworkRuntimeCall::PolkadotCollectives(
-			CollectivesRuntimeCall::Preimage(CollectivesPreimageCall::note_preimage {
-				bytes: whitelist_over_xcm.encoded.clone(),
-			}),
-		));
-		let proposal = CallInfo::from_runtime_call(NetworkRuntimeCall::PolkadotCollectives(
-			CollectivesRuntimeCall::FellowshipReferenda(CollectivesReferendaCall::submit {
-				proposal_origin: Box::new(CollectivesOriginCaller::FellowshipOrigins(
-					FellowshipOrigins::Fellows,
-				)),
-				proposal: CollectivesLookup {
-					hash: H256(whitelist_over_xcm.hash),
-					len: whitelist_over_xcm.length,
-				},
-				enactment_moment: CollectivesDispatchTime::After(10u32),
-			}),
-		));
+		let preimage =
+			proposal_details.metadata.call_info(NetworkRuntimeCall::PolkadotCollectives(
+				CollectivesRuntimeCall::Preimage(CollectivesPreimageCall::note_preimage {
+					bytes: whitelist_over_xcm.encoded.clone(),
+				}),
+			))?;
+		let proposal =
+			proposal_details.metadata.call_info(NetworkRuntimeCall::PolkadotCollectives(
+				CollectivesRuntimeCall::FellowshipReferenda(CollectivesReferendaCall::submit {
+					proposal_origin: Box::new(CollectivesOriginCaller::FellowshipOrigins(
+						FellowshipOrigins::Fellows,
+					)),
+					proposal: CollectivesLookup {
+						hash: H256(whitelist_over_xcm.hash),
+						len: whitelist_over_xcm.length,
+					},
+					enactment_moment: CollectivesDispatchTime::After(10u32),
+				}),
+			))?;
 		(proposal, Some(preimage))
 	};
 
 	// Now we put together the public referendum part. This still needs separate logic because the
 	// actual proposal gets wrapped in a Whitelist call.
-	let dispatch_whitelisted_call = CallInfo::from_runtime_call(
+	let dispatch_whitelisted_call = proposal_details.metadata.call_info(
 		NetworkRuntimeCall::PolkadotAssetHub(PolkadotAssetHubRuntimeCall::Whitelist(
 			WhitelistCall::dispatch_whitelisted_call_with_preimage {
 				call: Box::new(
-					proposal_call_info
-						.get_polkadot_asset_hub_call()
-						.expect("it is a polkadot asset hub call"),
+					proposal_details
+						.metadata
+						.to_bundled(&proposal_call_info)?
+						.get_polkadot_asset_hub_call()?,
 				),
 			},
 		)),
-	);
+	)?;
 
-	let preimage_for_dispatch_whitelisted_call = CallInfo::from_runtime_call(
+	let preimage_for_dispatch_whitelisted_call = proposal_details.metadata.call_info(
 		NetworkRuntimeCall::PolkadotAssetHub(PolkadotAssetHubRuntimeCall::Preimage(
 			PreimageCall::note_preimage { bytes: dispatch_whitelisted_call.encoded.clone() },
 		)),
-	);
-	let public_proposal = CallInfo::from_runtime_call(NetworkRuntimeCall::PolkadotAssetHub(
-		PolkadotAssetHubRuntimeCall::Referenda(ReferendaCall::submit {
-			proposal_origin: Box::new(PolkadotAssetHubOriginCaller::Origins(
-				PolkadotAssetHubOpenGovOrigin::WhitelistedCaller,
-			)),
-			proposal: Lookup {
-				hash: H256(dispatch_whitelisted_call.hash),
-				len: dispatch_whitelisted_call.length,
-			},
-			enactment_moment: public_referendum_dispatch_time,
-		}),
-	));
+	)?;
+	let public_proposal =
+		proposal_details.metadata.call_info(NetworkRuntimeCall::PolkadotAssetHub(
+			PolkadotAssetHubRuntimeCall::Referenda(ReferendaCall::submit {
+				proposal_origin: Box::new(PolkadotAssetHubOriginCaller::Origins(
+					PolkadotAssetHubOpenGovOrigin::WhitelistedCaller,
+				)),
+				proposal: Lookup {
+					hash: H256(dispatch_whitelisted_call.hash),
+					len: dispatch_whitelisted_call.length,
+				},
+				enactment_moment: public_referendum_dispatch_time,
+			}),
+		))?;
 
 	// Check the lengths and prepare preimages for printing.
 	let whitelist_preimage_print = preimage_for_whitelist_over_xcm
-		.map(|p| p.create_print_output(proposal_details.output_len_limit));
+		.map(|p| {
+			p.create_print_output(proposal_details.output_len_limit, &proposal_details.metadata)
+		})
+		.transpose()?;
 	let (dispatch_preimage_print, dispatch_preimage_print_len) =
 		preimage_for_dispatch_whitelisted_call
-			.create_print_output(proposal_details.output_len_limit);
+			.create_print_output(proposal_details.output_len_limit, &proposal_details.metadata)?;
 
 	// If it's a hash, let's write the data to a file you can upload.
 	match dispatch_preimage_print {
@@ -786,38 +1119,47 @@ async fn polkadot_fellowship_referenda(
 		CallOrHash::Hash(_) => {
 			let mut info_to_write = "0x".to_owned();
 			info_to_write.push_str(hex::encode(dispatch_whitelisted_call.encoded).as_str());
-			fs::write("polkadot_asset_hub_public_referendum_preimage_to_note.call", info_to_write)
-				.expect("it should write");
+			write_file(
+				"polkadot_asset_hub_public_referendum_preimage_to_note.call",
+				info_to_write,
+			)?;
 		},
 	}
 
-	PossibleCallsToSubmit {
+	Ok(PossibleCallsToSubmit {
 		preimage_for_whitelist_call: whitelist_preimage_print,
 		preimage_for_public_referendum: Some((
 			dispatch_preimage_print,
 			dispatch_preimage_print_len,
 		)),
 		fellowship_referendum_submission: Some(NetworkRuntimeCall::PolkadotCollectives(
-			fellowship_proposal.get_polkadot_collectives_call().expect("polkadot collectives"),
+			proposal_details
+				.metadata
+				.to_bundled(&fellowship_proposal)?
+				.get_polkadot_collectives_call()?,
 		)),
 		public_referendum_submission: Some(NetworkRuntimeCall::PolkadotAssetHub(
-			public_proposal.get_polkadot_asset_hub_call().expect("polkadot asset hub"),
+			proposal_details
+				.metadata
+				.to_bundled(&public_proposal)?
+				.get_polkadot_asset_hub_call()?,
 		)),
-	}
+		whitelist_over_xcm: Some(whitelist_over_xcm),
+	})
 }
 
 // Generate the calls needed for a proposal to pass on Polkadot without the Fellowship.
 fn polkadot_non_fellowship_referenda(
 	proposal_details: &ProposalDetails,
 	origin: PolkadotAssetHubOriginCaller,
-) -> PossibleCallsToSubmit {
+) -> Result<PossibleCallsToSubmit, Error> {
 	use polkadot_asset_hub::runtime_types::{
 		frame_support::traits::{preimages::Bounded::Lookup, schedule::DispatchTime},
 		pallet_preimage::pallet::Call as PreimageCall,
 		pallet_referenda::pallet::Call as ReferendaCall,
 	};
 
-	let proposal_bytes = get_proposal_bytes(proposal_details.proposal.clone());
+	let proposal_bytes = get_proposal_bytes(proposal_details.proposal.clone())?;
 	let proposal_call_info = CallInfo::from_bytes(&proposal_bytes, Network::PolkadotAssetHub);
 
 	let public_referendum_dispatch_time = match proposal_details.dispatch {
@@ -825,45 +1167,204 @@ fn polkadot_non_fellowship_referenda(
 		DispatchTimeWrapper::After(block) => DispatchTime::After(block),
 	};
 
-	let note_proposal_preimage = CallInfo::from_runtime_call(NetworkRuntimeCall::PolkadotAssetHub(
-		PolkadotAssetHubRuntimeCall::Preimage(PreimageCall::note_preimage {
-			bytes: proposal_bytes,
-		}),
-	));
-	let public_proposal = CallInfo::from_runtime_call(NetworkRuntimeCall::PolkadotAssetHub(
-		PolkadotAssetHubRuntimeCall::Referenda(ReferendaCall::submit {
-			proposal_origin: Box::new(origin),
-			proposal: Lookup {
-				hash: H256(proposal_call_info.hash),
-				len: proposal_call_info.length,
-			},
-			enactment_moment: public_referendum_dispatch_time,
-		}),
-	));
-	let (preimage_print, preimage_print_len) =
-		note_proposal_preimage.create_print_output(proposal_details.output_len_limit);
+	let note_proposal_preimage = proposal_details.metadata.call_info(
+		NetworkRuntimeCall::PolkadotAssetHub(PolkadotAssetHubRuntimeCall::Preimage(
+			PreimageCall::note_preimage { bytes: proposal_bytes },
+		)),
+	)?;
+	let public_proposal =
+		proposal_details.metadata.call_info(NetworkRuntimeCall::PolkadotAssetHub(
+			PolkadotAssetHubRuntimeCall::Referenda(ReferendaCall::submit {
+				proposal_origin: Box::new(origin),
+				proposal: Lookup {
+					hash: H256(proposal_call_info.hash),
+					len: proposal_call_info.length,
+				},
+				enactment_moment: public_referendum_dispatch_time,
+			}),
+		))?;
+	let (preimage_print, preimage_print_len) = note_proposal_preimage
+		.create_print_output(proposal_details.output_len_limit, &proposal_details.metadata)?;
 
-	PossibleCallsToSubmit {
+	Ok(PossibleCallsToSubmit {
 		preimage_for_whitelist_call: None,
 		preimage_for_public_referendum: Some((preimage_print, preimage_print_len)),
 		fellowship_referendum_submission: None,
 		public_referendum_submission: Some(NetworkRuntimeCall::PolkadotAssetHub(
-			public_proposal.get_polkadot_asset_hub_call().expect("polkadot asset hub"),
+			proposal_details
+				.metadata
+				.to_bundled(&public_proposal)?
+				.get_polkadot_asset_hub_call()?,
 		)),
+		whitelist_over_xcm: None,
+	})
+}
+
+// One call in the document printed with `--output json`.
+#[derive(Debug, PartialEq, serde::Serialize)]
+pub(crate) struct JsonStep {
+	// Which call this is. One of the `PossibleCallsToSubmit` fields, or `batch`.
+	pub(crate) step: &'static str,
+	// The network on which to submit the call.
+	pub(crate) network: Network,
+	// The call data. `None` if the call was elided for exceeding `output_len_limit`.
+	pub(crate) call_data: Option<String>,
+	// The blake2_256 hash of the call.
+	pub(crate) call_hash: String,
+	// The length of the call in bytes.
+	pub(crate) length: u32,
+	// Whether the call was elided for exceeding `output_len_limit`.
+	pub(crate) elided: bool,
+	// The signatory of a multisig who submits the call, if it is an approval after the first.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) signatory: Option<String>,
+	// The account of the multisig that the call is wrapped for, if any.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) multisig_account: Option<String>,
+	// The hash of the call that the multisig's signatories approve, if any.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) multisig_call_hash: Option<String>,
+}
+
+impl JsonStep {
+	// A step for a call that will be printed in full.
+	pub(crate) fn from_call(step: &'static str, call_info: &CallInfo) -> Self {
+		Self {
+			step,
+			network: call_info.network.clone(),
+			call_data: Some(format!("0x{}", hex::encode(&call_info.encoded))),
+			call_hash: format!("0x{}", hex::encode(call_info.hash)),
+			length: call_info.length,
+			elided: false,
+			signatory: None,
+			multisig_account: None,
+			multisig_call_hash: None,
+		}
+	}
+
+	// A step for a call that was too long to print.
+	fn elided(step: &'static str, network: Network, hash: [u8; 32], length: u32) -> Self {
+		Self {
+			step,
+			network,
+			call_data: None,
+			call_hash: format!("0x{}", hex::encode(hash)),
+			length,
+			elided: true,
+			signatory: None,
+			multisig_account: None,
+			multisig_call_hash: None,
+		}
+	}
+}
+
+// Log a single call, wrapped for a multisig or proxy if the user wants, and any approvals that
+// follow it. Text output is printed immediately, while JSON output is collected in `steps` and
+// printed as one document at the end. Returns the network and encoding of the call to submit.
+fn log_call(
+	proposal_details: &ProposalDetails,
+	steps: &mut Vec<JsonStep>,
+	step: &'static str,
+	heading: &str,
+	call: &NetworkRuntimeCall,
+) -> Result<(Network, Vec<u8>), Error> {
+	let metadata = &proposal_details.metadata;
+	let json = matches!(proposal_details.output, Output::Json);
+	let network = call.network();
+	let log = |steps: &mut Vec<JsonStep>, heading: &str, call_info: &CallInfo| {
+		if json {
+			steps.push(JsonStep::from_call(step, call_info));
+			Ok(())
+		} else {
+			println!("\n{heading}");
+			print_output(&proposal_details.output, call_info, proposal_details.use_light_client)
+		}
+	};
+
+	if proposal_details.wrapper.is_empty() {
+		let encoded = metadata.encode(call)?;
+		log(steps, heading, &CallInfo::from_bytes(&encoded, network.clone()))?;
+		return Ok((network, encoded));
+	}
+	let wrapped = proposal_details.wrapper.wrap(call, metadata)?;
+	let encoded = metadata.encode(&wrapped.call)?;
+	let multisig = wrapped.multisig.map(|(account, call_hash)| {
+		(format!("0x{}", hex::encode(account)), format!("0x{}", hex::encode(call_hash)))
+	});
+	// A step of the wrapped call, with the multisig that its signatories approve.
+	let json_step = |call_info: &CallInfo, signatory: Option<String>| {
+		let mut json_step = JsonStep::from_call(step, call_info);
+		json_step.signatory = signatory;
+		if let Some((account, call_hash)) = &multisig {
+			json_step.multisig_account = Some(account.clone());
+			json_step.multisig_call_hash = Some(call_hash.clone());
+		}
+		json_step
+	};
+	let call_info = CallInfo::from_bytes(&encoded, network.clone());
+	if json {
+		steps.push(json_step(&call_info, None));
+	} else {
+		log(steps, heading, &call_info)?;
+	}
+	if let (Some((account, call_hash)), false) = (&multisig, json) {
+		println!("Multisig account: {account}");
+		println!("Multisig call hash: {call_hash}");
+		let threshold = proposal_details.wrapper.multisig.as_ref().map_or(1, |m| m.threshold);
+		if wrapped.approvals.is_empty() && threshold > 1 {
+			println!(
+				"Once the first approval is included, pass `--timepoint {network:?}:<block>:<index>` \
+				 and `--max-weight {network:?}:<ref_time>:<proof_size>`, the weight of the call, for \
+				 the other signatories' approvals."
+			);
+		}
+	}
+	for (signatory, approval) in &wrapped.approvals {
+		let signatory = format!("0x{}", hex::encode(signatory));
+		let call_info = CallInfo::from_bytes(&metadata.encode(approval)?, network.clone());
+		if json {
+			steps.push(json_step(&call_info, Some(signatory)));
+		} else {
+			log(steps, &format!("Then, signatory {signatory} approves:"), &call_info)?;
+		}
 	}
+	Ok((network, encoded))
 }
 
 // Takes all the `calls` needed to submit and logs them according to the user's preferences.
-fn deliver_output(proposal_details: ProposalDetails, calls: PossibleCallsToSubmit) {
+// Returns the encoded calls that the user should sign: the batch for each network, or each call
+// on its own with `--no-batch`.
+pub(crate) fn deliver_output(
+	proposal_details: ProposalDetails,
+	calls: PossibleCallsToSubmit,
+) -> Result<Vec<(Network, Vec<u8>)>, Error> {
 	let mut batch_of_calls = Vec::new();
+	let mut individual_calls = Vec::new();
+	let mut steps = Vec::new();
+	let json = matches!(proposal_details.output, Output::Json);
+	let deposits = deposits::referendum_deposits(&proposal_details.track, &calls)?;
+
+	let public_network = public_network(&proposal_details.track);
 
 	if let Some((call_or_hash, len)) = calls.preimage_for_whitelist_call {
 		match call_or_hash {
 			CallOrHash::Call(c) => {
-				println!("\nSubmit the preimage for the Fellowship referendum:");
-				print_output(&proposal_details.output, &c, proposal_details.use_light_client);
+				individual_calls.push(log_call(
+					&proposal_details,
+					&mut steps,
+					"preimage_for_whitelist_call",
+					"Submit the preimage for the Fellowship referendum:",
+					&c,
+				)?);
 				batch_of_calls.push(c);
 			},
+			// Only the Polkadot Fellowship, on Collectives, needs a preimage for its referendum.
+			CallOrHash::Hash(h) if json => steps.push(JsonStep::elided(
+				"preimage_for_whitelist_call",
+				Network::PolkadotCollectives,
+				h,
+				len,
+			)),
 			CallOrHash::Hash(h) => {
 				println!(
 					"\nPreimage for the public whitelist call too large ({len} bytes). Not included in batch."
@@ -873,17 +1374,45 @@ fn deliver_output(proposal_details: ProposalDetails, calls: PossibleCallsToSubmi
 		}
 	}
 	if let Some(c) = calls.fellowship_referendum_submission {
-		println!("\nOpen a Fellowship referendum to whitelist the call:");
-		print_output(&proposal_details.output, &c, proposal_details.use_light_client);
+		individual_calls.push(log_call(
+			&proposal_details,
+			&mut steps,
+			"fellowship_referendum_submission",
+			"Open a Fellowship referendum to whitelist the call:",
+			&c,
+		)?);
+		let index = proposal_details.decision_deposit.and_then(|indices| indices.fellowship);
+		let place = index.map(|index| place_decision_deposit(&c.network(), index)).transpose()?;
 		batch_of_calls.push(c);
+		if let Some(place) = place {
+			individual_calls.push(log_call(
+				&proposal_details,
+				&mut steps,
+				"fellowship_decision_deposit",
+				"Place the decision deposit of the Fellowship referendum:",
+				&place,
+			)?);
+			batch_of_calls.push(place);
+		}
 	}
 	if let Some((call_or_hash, len)) = calls.preimage_for_public_referendum {
 		match call_or_hash {
 			CallOrHash::Call(c) => {
-				println!("\nSubmit the preimage for the public referendum:");
-				print_output(&proposal_details.output, &c, proposal_details.use_light_client);
+				individual_calls.push(log_call(
+					&proposal_details,
+					&mut steps,
+					"preimage_for_public_referendum",
+					"Submit the preimage for the public referendum:",
+					&c,
+				)?);
 				batch_of_calls.push(c);
 			},
+			CallOrHash::Hash(h) if json => steps.push(JsonStep::elided(
+				"preimage_for_public_referendum",
+				public_network,
+				h,
+				len,
+			)),
 			CallOrHash::Hash(h) => {
 				println!(
 					"\nPreimage for the public referendum too large ({len} bytes). Not included in batch."
@@ -894,34 +1423,66 @@ fn deliver_output(proposal_details: ProposalDetails, calls: PossibleCallsToSubmi
 		}
 	}
 	if let Some(c) = calls.public_referendum_submission {
-		println!("\nOpen a public referendum to dispatch the call:");
-		print_output(&proposal_details.output, &c, proposal_details.use_light_client);
+		individual_calls.push(log_call(
+			&proposal_details,
+			&mut steps,
+			"public_referendum_submission",
+			"Open a public referendum to dispatch the call:",
+			&c,
+		)?);
+		let index = proposal_details.decision_deposit.map(|indices| indices.public);
+		let place = index.map(|index| place_decision_deposit(&c.network(), index)).transpose()?;
 		batch_of_calls.push(c);
+		if let Some(place) = place {
+			individual_calls.push(log_call(
+				&proposal_details,
+				&mut steps,
+				"public_decision_deposit",
+				"Place the decision deposit of the public referendum:",
+				&place,
+			)?);
+			batch_of_calls.push(place);
+		}
 	}
 
-	if proposal_details.print_batch {
-		handle_batch_of_calls(
-			&proposal_details.output,
-			batch_of_calls,
-			proposal_details.use_light_client,
-		);
+	let calls_to_sign = if proposal_details.print_batch {
+		handle_batch_of_calls(&proposal_details, batch_of_calls, &mut steps)?
+	} else {
+		individual_calls
+	};
+
+	if json {
+		let document = serde_json::json!({ "steps": steps, "deposits": deposits });
+		println!("{}", serde_json::to_string_pretty(&document).expect("it serializes"));
+	} else {
+		deposits::print_deposits(&deposits);
 	}
+	Ok(calls_to_sign)
 }
 
 // Takes a vec of calls, which could be intended for use on different networks, sorts them into the
-// appropriate network, and provides a single batch call for each network.
-fn handle_batch_of_calls(output: &Output, batch: Vec<NetworkRuntimeCall>, use_light_client: bool) {
+// appropriate network, and provides a single batch call for each network. Returns each batch,
+// encoded.
+fn handle_batch_of_calls(
+	proposal_details: &ProposalDetails,
+	batch: Vec<NetworkRuntimeCall>,
+	steps: &mut Vec<JsonStep>,
+) -> Result<Vec<(Network, Vec<u8>)>, Error> {
 	use kusama_asset_hub::runtime_types::pallet_utility::pallet::Call as KusamaAssetHubUtilityCall;
 	use kusama_relay::runtime_types::pallet_utility::pallet::Call as KusamaUtilityCall;
 	use polkadot_asset_hub::runtime_types::pallet_utility::pallet::Call as PolkadotAssetHubUtilityCall;
 	use polkadot_collectives::runtime_types::pallet_utility::pallet::Call as CollectivesUtilityCall;
 	use polkadot_relay::runtime_types::pallet_utility::pallet::Call as PolkadotRelayUtilityCall;
 
+	// A decision deposit is placed on the index that its referendum is predicted to get, so must
+	// not be placed if the submission fails.
+	let atomic = proposal_details.decision_deposit.is_some();
 	let mut kusama_relay_batch = Vec::new();
 	let mut kusama_asset_hub_batch = Vec::new();
 	let mut polkadot_asset_hub_batch = Vec::new();
 	let mut polkadot_relay_batch = Vec::new();
 	let mut polkadot_collectives_batch = Vec::new();
+	let mut batches = Vec::new();
 
 	for network_call in batch {
 		match network_call {
@@ -930,125 +1491,116 @@ fn handle_batch_of_calls(output: &Output, batch: Vec<NetworkRuntimeCall>, use_li
 			NetworkRuntimeCall::Polkadot(cc) => polkadot_relay_batch.push(cc),
 			NetworkRuntimeCall::PolkadotAssetHub(cc) => polkadot_asset_hub_batch.push(cc),
 			NetworkRuntimeCall::PolkadotCollectives(cc) => polkadot_collectives_batch.push(cc),
-			_ => panic!("no other chains are needed for this"),
+			other =>
+				return Err(Error::UnsupportedNetwork(format!(
+					"Cannot batch calls on {:?}.",
+					other.network()
+				))),
 		}
 	}
 	if !kusama_relay_batch.is_empty() {
-		let batch = KusamaRuntimeCall::Utility(KusamaUtilityCall::force_batch {
-			calls: kusama_relay_batch,
+		let batch = KusamaRuntimeCall::Utility(if atomic {
+			KusamaUtilityCall::batch_all { calls: kusama_relay_batch }
+		} else {
+			KusamaUtilityCall::force_batch { calls: kusama_relay_batch }
 		});
-		println!("\nBatch to submit on Kusama Relay Chain:");
-		print_output(output, &NetworkRuntimeCall::Kusama(batch), use_light_client);
+		let batch = NetworkRuntimeCall::Kusama(batch);
+		batches.push(log_call(
+			proposal_details,
+			steps,
+			"batch",
+			"Batch to submit on Kusama Relay Chain:",
+			&batch,
+		)?);
 	}
 	if !kusama_asset_hub_batch.is_empty() {
-		let batch = KusamaAssetHubRuntimeCall::Utility(KusamaAssetHubUtilityCall::force_batch {
-			calls: kusama_asset_hub_batch,
+		let batch = KusamaAssetHubRuntimeCall::Utility(if atomic {
+			KusamaAssetHubUtilityCall::batch_all { calls: kusama_asset_hub_batch }
+		} else {
+			KusamaAssetHubUtilityCall::force_batch { calls: kusama_asset_hub_batch }
 		});
-		println!("\nBatch to submit on Kusama Asset Hub:");
-		print_output(output, &NetworkRuntimeCall::KusamaAssetHub(batch), use_light_client);
+		let batch = NetworkRuntimeCall::KusamaAssetHub(batch);
+		batches.push(log_call(
+			proposal_details,
+			steps,
+			"batch",
+			"Batch to submit on Kusama Asset Hub:",
+			&batch,
+		)?);
 	}
 	if !polkadot_relay_batch.is_empty() {
-		let batch = PolkadotRuntimeCall::Utility(PolkadotRelayUtilityCall::force_batch {
-			calls: polkadot_relay_batch,
+		let batch = PolkadotRuntimeCall::Utility(if atomic {
+			PolkadotRelayUtilityCall::batch_all { calls: polkadot_relay_batch }
+		} else {
+			PolkadotRelayUtilityCall::force_batch { calls: polkadot_relay_batch }
 		});
-		println!("\nBatch to submit on Polkadot Relay Chain:");
-		print_output(output, &NetworkRuntimeCall::Polkadot(batch), use_light_client);
+		let batch = NetworkRuntimeCall::Polkadot(batch);
+		batches.push(log_call(
+			proposal_details,
+			steps,
+			"batch",
+			"Batch to submit on Polkadot Relay Chain:",
+			&batch,
+		)?);
 	}
 	if !polkadot_asset_hub_batch.is_empty() {
-		let batch =
-			PolkadotAssetHubRuntimeCall::Utility(PolkadotAssetHubUtilityCall::force_batch {
-				calls: polkadot_asset_hub_batch,
-			});
-		println!("\nBatch to submit on Polkadot Asset Hub:");
-		print_output(output, &NetworkRuntimeCall::PolkadotAssetHub(batch), use_light_client);
+		let batch = PolkadotAssetHubRuntimeCall::Utility(if atomic {
+			PolkadotAssetHubUtilityCall::batch_all { calls: polkadot_asset_hub_batch }
+		} else {
+			PolkadotAssetHubUtilityCall::force_batch { calls: polkadot_asset_hub_batch }
+		});
+		let batch = NetworkRuntimeCall::PolkadotAssetHub(batch);
+		batches.push(log_call(
+			proposal_details,
+			steps,
+			"batch",
+			"Batch to submit on Polkadot Asset Hub:",
+			&batch,
+		)?);
 	}
 	if !polkadot_collectives_batch.is_empty() {
-		let batch = CollectivesRuntimeCall::Utility(CollectivesUtilityCall::force_batch {
-			calls: polkadot_collectives_batch,
+		let batch = CollectivesRuntimeCall::Utility(if atomic {
+			CollectivesUtilityCall::batch_all { calls: polkadot_collectives_batch }
+		} else {
+			CollectivesUtilityCall::force_batch { calls: polkadot_collectives_batch }
 		});
-		println!("\nBatch to submit on Polkadot Collectives Chain:");
-		print_output(output, &NetworkRuntimeCall::PolkadotCollectives(batch), use_light_client);
+		let batch = NetworkRuntimeCall::PolkadotCollectives(batch);
+		batches.push(log_call(
+			proposal_details,
+			steps,
+			"batch",
+			"Batch to submit on Polkadot Collectives Chain:",
+			&batch,
+		)?);
 	}
+	Ok(batches)
 }
 
 // Format the data to print to console.
-fn print_output(output: &Output, network_call: &NetworkRuntimeCall, use_light_client: bool) {
-	match network_call {
-		NetworkRuntimeCall::Kusama(call) => {
-			let endpoint = if use_light_client {
-				"light-client"
-			} else {
-				"wss%3A%2F%2Fkusama-rpc.dwellir.com"
-			};
-			let network_id = "kusama";
-			match output {
-				Output::CallData => println!("0x{}", hex::encode(call.encode())),
-				Output::AppsUiLink => println!(
-					"https://dev.papi.how/extrinsics#data=0x{}&networkId={network_id}&endpoint={endpoint}",
-					hex::encode(call.encode())
-				),
-			}
-		},
-		NetworkRuntimeCall::KusamaAssetHub(call) => {
-			let endpoint = if use_light_client {
-				"light-client"
-			} else {
-				"wss%3A%2F%2Fasset-hub-kusama-rpc.dwellir.com"
-			};
-			let network_id = "kusama_asset_hub";
-			match output {
-				Output::CallData => println!("0x{}", hex::encode(call.encode())),
-				Output::AppsUiLink => println!(
-					"https://dev.papi.how/extrinsics#data=0x{}&networkId={network_id}&endpoint={endpoint}",
-					hex::encode(call.encode())
-				),
-			}
-		},
-		NetworkRuntimeCall::Polkadot(call) => {
-			let endpoint = if use_light_client {
-				"light-client"
-			} else {
-				"wss%3A%2F%2Fpolkadot-rpc.dwellir.com"
-			};
-			let network_id = "polkadot";
-			match output {
-				Output::CallData => println!("0x{}", hex::encode(call.encode())),
-				Output::AppsUiLink => println!(
-					"https://dev.papi.how/extrinsics#data=0x{}&networkId={network_id}&endpoint={endpoint}",
-					hex::encode(call.encode())
-				),
-			}
-		},
-		NetworkRuntimeCall::PolkadotAssetHub(call) => {
-			let endpoint = if use_light_client {
-				"light-client"
-			} else {
-				"wss%3A%2F%2Fasset-hub-polkadot-rpc.dwellir.com"
-			};
-			let network_id = "polkadot_asset_hub";
-			match output {
-				Output::CallData => println!("0x{}", hex::encode(call.encode())),
-				Output::AppsUiLink => println!(
-					"https://dev.papi.how/extrinsics#data=0x{}&networkId={network_id}&endpoint={endpoint}",
-					hex::encode(call.encode())
-				),
-			}
-		},
-		NetworkRuntimeCall::PolkadotCollectives(call) => {
-			let endpoint = if use_light_client {
-				"light-client"
-			} else {
-				"wss%3A%2F%2Fpolkadot-collectives-rpc.polkadot.io"
-			};
-			let network_id = "polkadot_collectives";
-			match output {
-				Output::CallData => println!("0x{}", hex::encode(call.encode())),
-				Output::AppsUiLink => println!(
-					"https://dev.papi.how/extrinsics#data=0x{}&networkId={network_id}&endpoint={endpoint}",
-					hex::encode(call.encode())
-				),
-			}
-		},
-		_ => panic!("no other chains are needed for this"),
+pub(crate) fn print_output(
+	output: &Output,
+	call_info: &CallInfo,
+	use_light_client: bool,
+) -> Result<(), Error> {
+	let (network_id, rpc) = match call_info.network {
+		Network::Kusama => ("kusama", "wss%3A%2F%2Fkusama-rpc.dwellir.com"),
+		Network::KusamaAssetHub =>
+			("kusama_asset_hub", "wss%3A%2F%2Fasset-hub-kusama-rpc.dwellir.com"),
+		Network::Polkadot => ("polkadot", "wss%3A%2F%2Fpolkadot-rpc.dwellir.com"),
+		Network::PolkadotAssetHub =>
+			("polkadot_asset_hub", "wss%3A%2F%2Fasset-hub-polkadot-rpc.dwellir.com"),
+		Network::PolkadotCollectives =>
+			("polkadot_collectives", "wss%3A%2F%2Fpolkadot-collectives-rpc.polkadot.io"),
+		network =>
+			return Err(Error::UnsupportedNetwork(format!("No endpoint to link for {network:?}."))),
+	};
+	let endpoint = if use_light_client { "light-client" } else { rpc };
+	let call_data = hex::encode(&call_info.encoded);
+	match output {
+		Output::CallData | Output::Json => println!("0x{call_data}"),
+		Output::AppsUiLink => println!(
+			"https://dev.papi.how/extrinsics#data=0x{call_data}&networkId={network_id}&endpoint={endpoint}"
+		),
 	}
+	Ok(())
 }
@@ -1,147 +1,98 @@
 use crate::get_proposal_bytes;
 use crate::polkadot_asset_hub::runtime_types::frame_system::pallet::Call as PolkadotAssetHubSystemCall;
 use crate::polkadot_relay::runtime_types::frame_system::pallet::Call as PolkadotRelaySystemCall;
+use crate::Error;
 use crate::{
-	build_upgrade, submit_referendum::generate_calls, CallInfo, CallOrHash,
-	KusamaAssetHubOpenGovOrigin, Network, NetworkRuntimeCall, PolkadotAssetHubOpenGovOrigin,
-	PolkadotAssetHubRuntimeCall, PolkadotRuntimeCall, ProposalDetails, UpgradeArgs,
-	VersionedNetwork,
+	build_upgrade,
+	decode::{compact_byte_lists, decode_runtime_call, format_call, format_call_recursive},
+	submit_referendum::{generate_calls, JsonStep},
+	CallInfo, CallOrHash, KusamaAssetHubOpenGovOrigin, Network, NetworkRuntimeCall,
+	PolkadotAssetHubOpenGovOrigin, PolkadotAssetHubRuntimeCall, PolkadotRuntimeCall,
+	ProposalDetails, UpgradeArgs, UpgradeDetails, VersionedNetwork,
 };
 
 fn polkadot_whitelist_remark_user_input() -> ProposalDetails {
-	use crate::DispatchTimeWrapper::*;
 	use crate::NetworkTrack::*;
-	use crate::Output::*;
-	ProposalDetails {
-		// `system.remark("opengov-submit test")`
-		proposal: String::from("0x00004c6f70656e676f762d7375626d69742074657374"),
-		track: Polkadot(PolkadotAssetHubOpenGovOrigin::WhitelistedCaller),
-		dispatch: After(10),
-		output: AppsUiLink,
-		output_len_limit: 1_000,
-		print_batch: true,
-		use_light_client: false,
-		fellowship_on_polkadot: false,
-	}
+	// `system.remark("opengov-submit test")`
+	ProposalDetails::builder(
+		"0x00004c6f70656e676f762d7375626d69742074657374",
+		Polkadot(PolkadotAssetHubOpenGovOrigin::WhitelistedCaller),
+	)
+	.build()
+	.unwrap()
 }
 
 fn polkadot_staking_validator_user_input() -> ProposalDetails {
-	use crate::DispatchTimeWrapper::*;
 	use crate::NetworkTrack::*;
-	use crate::Output::*;
-	ProposalDetails {
-		// `staking.increase_validator_count(50)`
-		proposal: String::from("0x070ac8"),
-		track: Polkadot(PolkadotAssetHubOpenGovOrigin::StakingAdmin),
-		dispatch: After(10),
-		output: AppsUiLink,
-		output_len_limit: 1_000,
-		print_batch: true,
-		use_light_client: false,
-		fellowship_on_polkadot: false,
-	}
+	// `staking.increase_validator_count(50)`
+	ProposalDetails::builder("0x070ac8", Polkadot(PolkadotAssetHubOpenGovOrigin::StakingAdmin))
+		.build()
+		.unwrap()
 }
 
 fn polkadot_root_remark_user_input() -> ProposalDetails {
-	use crate::DispatchTimeWrapper::*;
 	use crate::NetworkTrack::*;
-	use crate::Output::*;
-	ProposalDetails {
-		// `system.remark("opengov-submit test")`
-		proposal: String::from("0x00004c6f70656e676f762d7375626d69742074657374"),
-		track: PolkadotRoot,
-		dispatch: After(10),
-		output: AppsUiLink,
-		output_len_limit: 1_000,
-		print_batch: true,
-		use_light_client: false,
-		fellowship_on_polkadot: false,
-	}
+	// `system.remark("opengov-submit test")`
+	ProposalDetails::builder("0x00004c6f70656e676f762d7375626d69742074657374", PolkadotRoot)
+		.build()
+		.unwrap()
 }
 
 fn kusama_whitelist_remark_user_input() -> ProposalDetails {
 	use crate::DispatchTimeWrapper::*;
 	use crate::NetworkTrack::*;
-	use crate::Output::*;
-	ProposalDetails {
-		// `system.remark("opengov-submit test")`
-		proposal: String::from("0x00004c6f70656e676f762d7375626d69742074657374"),
-		track: Kusama(KusamaAssetHubOpenGovOrigin::WhitelistedCaller),
-		dispatch: At(100_000_000),
-		output: AppsUiLink,
-		output_len_limit: 1_000,
-		print_batch: true,
-		use_light_client: false,
-		fellowship_on_polkadot: false,
-	}
+	// `system.remark("opengov-submit test")`
+	ProposalDetails::builder(
+		"0x00004c6f70656e676f762d7375626d69742074657374",
+		Kusama(KusamaAssetHubOpenGovOrigin::WhitelistedCaller),
+	)
+	.dispatch(At(100_000_000))
+	.build()
+	.unwrap()
 }
 
 fn kusama_whitelist_polkadot_fellowship_user_input() -> ProposalDetails {
 	use crate::DispatchTimeWrapper::*;
 	use crate::NetworkTrack::*;
-	use crate::Output::*;
-	ProposalDetails {
-		// `system.remark("opengov-submit test")`
-		proposal: String::from("0x00004c6f70656e676f762d7375626d69742074657374"),
-		track: Kusama(KusamaAssetHubOpenGovOrigin::WhitelistedCaller),
-		dispatch: At(100_000_000),
-		output: AppsUiLink,
-		output_len_limit: 1_000,
-		print_batch: true,
-		use_light_client: false,
-		fellowship_on_polkadot: true,
-	}
+	// `system.remark("opengov-submit test")`
+	ProposalDetails::builder(
+		"0x00004c6f70656e676f762d7375626d69742074657374",
+		Kusama(KusamaAssetHubOpenGovOrigin::WhitelistedCaller),
+	)
+	.dispatch(At(100_000_000))
+	.fellowship_on_polkadot(true)
+	.build()
+	.unwrap()
 }
 
 fn kusama_staking_validator_user_input() -> ProposalDetails {
 	use crate::DispatchTimeWrapper::*;
 	use crate::NetworkTrack::*;
-	use crate::Output::*;
-	ProposalDetails {
-		// `staking.increase_validator_count(50)`
-		proposal: String::from("0x060ac8"),
-		track: Kusama(KusamaAssetHubOpenGovOrigin::StakingAdmin),
-		dispatch: At(100_000_000),
-		output: AppsUiLink,
-		output_len_limit: 1_000,
-		print_batch: true,
-		use_light_client: false,
-		fellowship_on_polkadot: false,
-	}
+	// `staking.increase_validator_count(50)`
+	ProposalDetails::builder("0x060ac8", Kusama(KusamaAssetHubOpenGovOrigin::StakingAdmin))
+		.dispatch(At(100_000_000))
+		.build()
+		.unwrap()
 }
 
 fn kusama_root_remark_user_input() -> ProposalDetails {
-	use crate::DispatchTimeWrapper::*;
 	use crate::NetworkTrack::*;
-	use crate::Output::*;
-	ProposalDetails {
-		// `system.remark("opengov-submit test")`
-		proposal: String::from("0x00004c6f70656e676f762d7375626d69742074657374"),
-		track: KusamaRoot,
-		dispatch: After(10),
-		output: AppsUiLink,
-		output_len_limit: 1_000,
-		print_batch: true,
-		use_light_client: false,
-		fellowship_on_polkadot: false,
-	}
+	// `system.remark("opengov-submit test")`
+	ProposalDetails::builder("0x00004c6f70656e676f762d7375626d69742074657374", KusamaRoot)
+		.build()
+		.unwrap()
 }
 
 fn limited_length_user_input() -> ProposalDetails {
-	use crate::DispatchTimeWrapper::*;
 	use crate::NetworkTrack::*;
-	use crate::Output::*;
-	ProposalDetails {
-		// `system.remark("opengov-submit test")`
-		proposal: String::from("0x00004c6f70656e676f762d7375626d69742074657374"),
-		track: Polkadot(PolkadotAssetHubOpenGovOrigin::StakingAdmin),
-		dispatch: After(10),
-		output: AppsUiLink,
-		output_len_limit: 5, // very limiting
-		print_batch: true,
-		use_light_client: false,
-		fellowship_on_polkadot: false,
-	}
+	// `system.remark("opengov-submit test")`
+	ProposalDetails::builder(
+		"0x00004c6f70656e676f762d7375626d69742074657374",
+		Polkadot(PolkadotAssetHubOpenGovOrigin::StakingAdmin),
+	)
+	.output_len_limit(5) // very limiting
+	.build()
+	.unwrap()
 }
 
 fn upgrade_args_for_only_relay() -> UpgradeArgs {
@@ -149,6 +100,7 @@ fn upgrade_args_for_only_relay() -> UpgradeArgs {
 		network: String::from("polkadot"),
 		only: true,
 		local: false,
+		wasm: Vec::new(),
 		relay_version: Some(String::from("v1.2.0")),
 		asset_hub: None,
 		bridge_hub: None,
@@ -159,6 +111,8 @@ fn upgrade_args_for_only_relay() -> UpgradeArgs {
 		filename: None,
 		additional: None,
 		no_runtime_checks: false,
+		checksums: None,
+		metadata: Vec::new(),
 	}
 }
 
@@ -167,6 +121,7 @@ fn upgrade_args_for_only_asset_hub() -> UpgradeArgs {
 		network: String::from("polkadot"),
 		only: true,
 		local: false,
+		wasm: Vec::new(),
 		relay_version: None,
 		asset_hub: Some(String::from("v1.2.0")),
 		bridge_hub: None,
@@ -177,6 +132,8 @@ fn upgrade_args_for_only_asset_hub() -> UpgradeArgs {
 		filename: None,
 		additional: None,
 		no_runtime_checks: false,
+		checksums: None,
+		metadata: Vec::new(),
 	}
 }
 
@@ -185,6 +142,7 @@ fn upgrade_args_for_all() -> UpgradeArgs {
 		network: String::from("polkadot"),
 		only: false,
 		local: false,
+		wasm: Vec::new(),
 		relay_version: Some(String::from("v1.2.0")),
 		asset_hub: None,
 		bridge_hub: None,
@@ -195,6 +153,8 @@ fn upgrade_args_for_all() -> UpgradeArgs {
 		filename: None,
 		additional: None,
 		no_runtime_checks: false,
+		checksums: None,
+		metadata: Vec::new(),
 	}
 }
 
@@ -203,6 +163,7 @@ fn upgrade_args_with_additional() -> UpgradeArgs {
 		network: String::from("polkadot"),
 		only: true,
 		local: false,
+		wasm: Vec::new(),
 		relay_version: Some(String::from("v1.2.0")),
 		asset_hub: None,
 		bridge_hub: None,
@@ -214,13 +175,15 @@ fn upgrade_args_with_additional() -> UpgradeArgs {
 		// `system.remark("test")` on Polkadot Asset Hub
 		additional: Some(String::from("0x00001074657374")),
 		no_runtime_checks: false,
+		checksums: None,
+		metadata: Vec::new(),
 	}
 }
 
 #[test]
 fn call_info_from_bytes_works() {
 	let proposal_details = polkadot_whitelist_remark_user_input();
-	let proposal_bytes = get_proposal_bytes(proposal_details.proposal);
+	let proposal_bytes = get_proposal_bytes(proposal_details.proposal).unwrap();
 	let proposal_call_info = CallInfo::from_bytes(&proposal_bytes, Network::Polkadot);
 
 	let remark_to_verify = PolkadotRuntimeCall::System(PolkadotRelaySystemCall::remark {
@@ -246,13 +209,29 @@ fn call_info_from_bytes_works() {
 	assert_eq!(proposal_call_info.hash, &bad_verification[..]);
 }
 
+#[test]
+fn undecodable_calls_are_errors() {
+	use crate::NetworkTrack::*;
+
+	// No pallet has the index 0xff.
+	let garbage = CallInfo::from_bytes(&[0xff, 0xff], Network::PolkadotAssetHub);
+	assert!(matches!(garbage.get_polkadot_asset_hub_call(), Err(Error::Decode { .. })));
+	assert!(matches!(garbage.get_polkadot_call(), Err(Error::UnsupportedNetwork(_))));
+
+	// Whitelisted proposals are decoded when building the details, others are only hashed.
+	let whitelisted = Polkadot(PolkadotAssetHubOpenGovOrigin::WhitelistedCaller);
+	let details = ProposalDetails::builder("0xffff", whitelisted).build();
+	assert!(matches!(details, Err(Error::Decode { network: Network::PolkadotAssetHub, .. })));
+	assert!(ProposalDetails::builder("0xffff", PolkadotRoot).build().is_ok());
+}
+
 #[test]
 fn get_proposal_bytes_trims_file_contents() {
 	let path = std::env::temp_dir()
 		.join(format!("opengov-cli-proposal-{}-trim-test.call", std::process::id()));
 	std::fs::write(&path, "\n  0x00001074657374\n").expect("write proposal file");
 
-	let proposal_bytes = get_proposal_bytes(path.to_string_lossy().into_owned());
+	let proposal_bytes = get_proposal_bytes(path.to_string_lossy().into_owned()).unwrap();
 	std::fs::remove_file(&path).ok();
 
 	assert_eq!(proposal_bytes, hex::decode("00001074657374").expect("hex"));
@@ -281,7 +260,7 @@ fn call_info_from_runtime_call_works() {
 #[tokio::test]
 async fn it_starts_polkadot_non_fellowship_referenda_correctly() {
 	let proposal_details = polkadot_staking_validator_user_input();
-	let calls = generate_calls(&proposal_details).await;
+	let calls = generate_calls(&proposal_details).await.unwrap();
 
 	let public_preimage =
 		hex::decode("0x05000c070ac8".trim_start_matches("0x")).expect("Valid call");
@@ -313,7 +292,7 @@ async fn it_starts_polkadot_non_fellowship_referenda_correctly() {
 async fn it_starts_polkadot_fellowship_referenda_correctly() {
 	// Fellowship is on Collectives, send XCM to Asset Hub to whitelist.
 	let proposal_details = polkadot_whitelist_remark_user_input();
-	let calls = generate_calls(&proposal_details).await;
+	let calls = generate_calls(&proposal_details).await.unwrap();
 
 	let public_preimage = hex::decode(
 		"0x050060400300004c6f70656e676f762d7375626d69742074657374".trim_start_matches("0x"),
@@ -354,7 +333,7 @@ async fn it_starts_polkadot_fellowship_referenda_correctly() {
 #[tokio::test]
 async fn it_starts_polkadot_root_referenda_correctly() {
 	let proposal_details = polkadot_root_remark_user_input();
-	let calls = generate_calls(&proposal_details).await;
+	let calls = generate_calls(&proposal_details).await.unwrap();
 
 	let public_preimage = hex::decode(
 		"0x05005800004c6f70656e676f762d7375626d69742074657374".trim_start_matches("0x"),
@@ -387,7 +366,7 @@ async fn it_starts_polkadot_root_referenda_correctly() {
 #[tokio::test]
 async fn it_starts_kusama_non_fellowship_referenda_correctly() {
 	let proposal_details = kusama_staking_validator_user_input();
-	let calls = generate_calls(&proposal_details).await;
+	let calls = generate_calls(&proposal_details).await.unwrap();
 
 	let public_preimage =
 		hex::decode("0x06000c060ac8".trim_start_matches("0x")).expect("Valid call");
@@ -418,7 +397,7 @@ async fn it_starts_kusama_non_fellowship_referenda_correctly() {
 #[tokio::test]
 async fn it_starts_kusama_fellowship_referenda_correctly() {
 	let proposal_details = kusama_whitelist_remark_user_input();
-	let calls = generate_calls(&proposal_details).await;
+	let calls = generate_calls(&proposal_details).await.unwrap();
 
 	// On Kusama, the fellowship is on the Relay Chain and uses inline calls,
 	// so preimage_for_whitelist_call is None. The fellowship referendum is submitted
@@ -464,7 +443,7 @@ async fn it_starts_kusama_fellowship_referenda_correctly() {
 #[tokio::test]
 async fn it_starts_polkadot_fellowship_whitelisted_kusama_referenda_correctly() {
 	let proposal_details = kusama_whitelist_polkadot_fellowship_user_input();
-	let calls = generate_calls(&proposal_details).await;
+	let calls = generate_calls(&proposal_details).await.unwrap();
 
 	// The fellowship referendum should be on Polkadot Collectives.
 	assert!(
@@ -503,7 +482,7 @@ async fn it_starts_polkadot_fellowship_whitelisted_kusama_referenda_correctly()
 #[tokio::test]
 async fn it_starts_kusama_root_referenda_correctly() {
 	let proposal_details = kusama_root_remark_user_input();
-	let calls = generate_calls(&proposal_details).await;
+	let calls = generate_calls(&proposal_details).await.unwrap();
 
 	let public_preimage = hex::decode(
 		"0x06005800004c6f70656e676f762d7375626d69742074657374".trim_start_matches("0x"),
@@ -536,7 +515,7 @@ async fn it_starts_kusama_root_referenda_correctly() {
 #[test]
 fn only_relay_chain() {
 	let args = upgrade_args_for_only_relay();
-	let details = build_upgrade::parse_inputs(args);
+	let details = build_upgrade::parse_inputs(args).unwrap();
 	assert_eq!(details.relay, Network::Polkadot);
 	let expected_networks =
 		vec![VersionedNetwork { network: Network::Polkadot, version: String::from("1.2.0") }];
@@ -547,7 +526,7 @@ fn only_relay_chain() {
 #[test]
 fn only_asset_hub() {
 	let args = upgrade_args_for_only_asset_hub();
-	let details = build_upgrade::parse_inputs(args);
+	let details = build_upgrade::parse_inputs(args).unwrap();
 	assert_eq!(details.relay, Network::Polkadot);
 	let expected_networks = vec![VersionedNetwork {
 		network: Network::PolkadotAssetHub,
@@ -560,7 +539,7 @@ fn only_asset_hub() {
 #[test]
 fn upgrade_everything_works_with_just_relay_version() {
 	let args = upgrade_args_for_all();
-	let details = build_upgrade::parse_inputs(args);
+	let details = build_upgrade::parse_inputs(args).unwrap();
 	assert_eq!(details.relay, Network::Polkadot);
 	let expected_networks = vec![
 		VersionedNetwork { network: Network::Polkadot, version: String::from("1.2.0") },
@@ -577,7 +556,7 @@ fn upgrade_everything_works_with_just_relay_version() {
 #[test]
 fn additional_call_decodes_correctly() {
 	let args = upgrade_args_with_additional();
-	let details = build_upgrade::parse_inputs(args);
+	let details = build_upgrade::parse_inputs(args).unwrap();
 
 	assert!(details.additional.is_some(), "additional should be set");
 	let additional = details.additional.unwrap();
@@ -598,9 +577,11 @@ fn additional_call_decodes_correctly() {
 #[test]
 fn it_creates_constrained_print_output() {
 	let proposal_details = limited_length_user_input();
-	let proposal_bytes = get_proposal_bytes(proposal_details.proposal);
+	let proposal_bytes = get_proposal_bytes(proposal_details.proposal).unwrap();
 	let proposal_call_info = CallInfo::from_bytes(&proposal_bytes, Network::Polkadot);
-	let (coh, length) = proposal_call_info.create_print_output(proposal_details.output_len_limit);
+	let (coh, length) = proposal_call_info
+		.create_print_output(proposal_details.output_len_limit, &proposal_details.metadata)
+		.unwrap();
 
 	let expected_hash = hex::decode(
 		"0x8821e8db19b8e34b62ee8bc618a5ed3eecb9761d7d81349b00aa5ce5dfca2534"
@@ -616,3 +597,1135 @@ fn it_creates_constrained_print_output() {
 	}
 	assert_eq!(length, proposal_call_info.length);
 }
+
+#[test]
+fn network_names_parse() {
+	assert_eq!(Network::from_name("polkadot"), Ok(Network::Polkadot));
+	assert_eq!(Network::from_name("Polkadot-Asset-Hub"), Ok(Network::PolkadotAssetHub));
+	assert_eq!(Network::from_name("asset-hub-kusama"), Ok(Network::KusamaAssetHub));
+	assert_eq!(Network::from_name("polkadot_collectives"), Ok(Network::PolkadotCollectives));
+	assert!(Network::from_name("westend").is_err());
+}
+
+#[test]
+fn it_decodes_call_data() {
+	let proposal_bytes =
+		get_proposal_bytes(String::from("0x00004c6f70656e676f762d7375626d69742074657374")).unwrap();
+	let call = decode_runtime_call(&proposal_bytes, &Network::Polkadot).expect("it decodes");
+	let tree = format_call(&call);
+
+	assert!(tree.starts_with("System("));
+	assert!(tree.contains("remark: 0x6f70656e676f762d7375626d69742074657374"));
+
+	// Trailing bytes are not a valid call.
+	let mut too_long = proposal_bytes.clone();
+	too_long.push(0);
+	assert!(decode_runtime_call(&too_long, &Network::Polkadot).is_err());
+}
+
+#[test]
+fn compact_byte_lists_collapses_bytes_only() {
+	let tree = "remark {\n    remark: [\n        1,\n        255,\n    ],\n    para: [\n        1000,\n    ],\n}";
+	let expected = "remark {\n    remark: 0x01ff,\n    para: [\n        1000,\n    ],\n}";
+	assert_eq!(compact_byte_lists(tree), expected);
+}
+
+#[test]
+fn para_ids_resolve_to_networks() {
+	for network in [Network::KusamaAssetHub, Network::PolkadotCollectives, Network::PolkadotPeople]
+	{
+		let para_id = network.get_para_id().expect("parachain");
+		assert_eq!(Network::from_para_id(&network.relay(), para_id), Ok(network));
+	}
+	assert!(Network::from_para_id(&Network::Polkadot, 2_000).is_err());
+	assert!(Network::from_para_id(&Network::PolkadotAssetHub, 1_000).is_err());
+}
+
+#[test]
+fn it_follows_xcm_transact_payloads() {
+	// The Polkadot Fellowship referendum to whitelist `system.remark("opengov-submit test")`.
+	let fellowship_referendum = get_proposal_bytes(String::from("0x3d003e0201cc1f0005010100a10f05082f00000603008840008821e8db19b8e34b62ee8bc618a5ed3eecb9761d7d81349b00aa5ce5dfca2534010a000000")).unwrap();
+	let call = decode_runtime_call(&fellowship_referendum, &Network::PolkadotCollectives)
+		.expect("it decodes");
+	let tree = format_call_recursive(&call);
+
+	assert!(tree.contains("Inline proposal on PolkadotCollectives:"));
+	assert!(tree.contains("XCM Transact (Xcm origin) on PolkadotAssetHub:"));
+	assert!(tree
+		.contains("call_hash: 0x8821e8db19b8e34b62ee8bc618a5ed3eecb9761d7d81349b00aa5ce5dfca2534"));
+}
+
+#[test]
+fn json_output_describes_each_call() {
+	let remark = NetworkRuntimeCall::PolkadotAssetHub(PolkadotAssetHubRuntimeCall::System(
+		PolkadotAssetHubSystemCall::remark { remark: b"opengov-submit test".to_vec() },
+	));
+	let step =
+		JsonStep::from_call("public_referendum_submission", &CallInfo::from_runtime_call(remark));
+	let json = serde_json::to_value(&step).unwrap();
+	assert_eq!(json["step"], "public_referendum_submission");
+	assert_eq!(json["network"], "polkadot-asset-hub");
+	assert_eq!(json["call_data"], "0x00004c6f70656e676f762d7375626d69742074657374");
+	assert_eq!(json["length"], 22);
+	assert_eq!(json["elided"], false);
+	assert!(json["call_hash"].as_str().unwrap().starts_with("0x"));
+	assert!(json.get("multisig_account").is_none());
+
+	// Calls wrapped for a multisig say which account and call hash its signatories approve.
+	let mut step = step;
+	step.multisig_account = Some(format!("0x{}", "01".repeat(32)));
+	step.multisig_call_hash = Some(format!("0x{}", "02".repeat(32)));
+	let json = serde_json::to_value(&step).unwrap();
+	assert_eq!(json["multisig_account"], format!("0x{}", "01".repeat(32)));
+	assert_eq!(json["multisig_call_hash"], format!("0x{}", "02".repeat(32)));
+}
+
+#[test]
+fn upgrade_manifest_records_each_runtime() {
+	// A directory of this test's own, so that it leaves nothing behind for other tests.
+	let directory = std::env::temp_dir()
+		.join(format!("opengov-cli-upgrade-{}-manifest-test", std::process::id()));
+	std::fs::create_dir_all(&directory).expect("create upgrade directory");
+	let details =
+		UpgradeDetails::builder(Network::Polkadot, directory.to_string_lossy().into_owned())
+			.runtime(Network::Polkadot, "v1.2.0")
+			.output_file("manifest-test.call")
+			.build()
+			.unwrap();
+
+	let file_name = build_upgrade::runtime_file_name(&details.networks[0]).unwrap();
+	assert_eq!(file_name, "polkadot_runtime-v1002000.compact.compressed.wasm");
+	std::fs::write(format!("{}{file_name}", details.directory), b"not really a runtime").unwrap();
+
+	// `system.authorize_upgrade` and a batch, as stand-ins for the generated calls.
+	let auth = CallInfo::from_bytes(&[0u8; 34], Network::Polkadot);
+	let batch = CallInfo::from_bytes(&[1u8; 40], Network::PolkadotAssetHub);
+	let manifest = build_upgrade::build_manifest(&details, &[auth], &batch);
+	std::fs::remove_dir_all(&directory).ok();
+	let manifest = manifest.unwrap();
+
+	assert_eq!(manifest.path, format!("{}manifest-test.manifest.json", details.directory));
+	assert_eq!(manifest.runtimes.len(), 1);
+	let runtime = &manifest.runtimes[0];
+	assert_eq!(runtime.file_size, 20);
+	assert_eq!(
+		runtime.code_hash,
+		format!("0x{}", hex::encode(sp_core::blake2_256(b"not really a runtime")))
+	);
+	assert_eq!(
+		runtime.download_url.as_deref(),
+		Some("https://github.com/polkadot-fellows/runtimes/releases/download/v1.2.0/polkadot_runtime-v1002000.compact.compressed.wasm")
+	);
+	assert_eq!(manifest.batch.call_hash, format!("0x{}", hex::encode(batch.hash)));
+	assert_eq!(manifest.batch.length, 40);
+
+	let json = serde_json::to_value(&manifest).unwrap();
+	assert_eq!(json["relay"], "polkadot");
+	assert!(json.get("path").is_none());
+}
+
+#[test]
+fn bad_inputs_return_errors() {
+	let invalid_hex = get_proposal_bytes(String::from("0xnothex")).unwrap_err();
+	assert!(matches!(invalid_hex, Error::InvalidHex(_)));
+	assert_eq!(invalid_hex.exit_code(), 6);
+
+	let missing_file = get_proposal_bytes(String::from("./no-such-proposal.call")).unwrap_err();
+	assert!(matches!(missing_file, Error::UnreadableFile { .. }));
+
+	let mut args = upgrade_args_for_only_relay();
+	args.network = String::from("westend");
+	let unsupported = build_upgrade::parse_inputs(args).unwrap_err();
+	assert!(matches!(unsupported, Error::UnsupportedNetwork(_)));
+
+	let mut args = upgrade_args_for_only_relay();
+	args.relay_version = Some(String::from("1.2"));
+	assert!(matches!(build_upgrade::parse_inputs(args).unwrap_err(), Error::InvalidInput(_)));
+
+	let mut args = upgrade_args_with_additional();
+	// `system.remark` with a length prefix longer than the data.
+	args.additional = Some(String::from("0x00001874657374"));
+	let bad_call = build_upgrade::parse_inputs(args).unwrap_err();
+	assert!(matches!(bad_call, Error::Decode { network: Network::PolkadotAssetHub, .. }));
+	assert_eq!(bad_call.exit_code(), 8);
+}
+
+#[test]
+fn proposal_details_builder_validates_fellowship() {
+	use crate::NetworkTrack::*;
+	let details = ProposalDetails::builder(
+		"0x00001074657374",
+		Kusama(KusamaAssetHubOpenGovOrigin::WhitelistedCaller),
+	)
+	.fellowship_on_polkadot(true)
+	.build()
+	.expect("polkadot fellowship can whitelist kusama calls");
+	assert!(details.fellowship_on_polkadot);
+	assert_eq!(details.output_len_limit, 1_000);
+
+	let root = ProposalDetails::builder("0x00001074657374", KusamaRoot)
+		.fellowship_on_polkadot(true)
+		.build();
+	assert!(matches!(root, Err(Error::InvalidInput(_))));
+}
+
+#[test]
+fn upgrade_details_builder_works() {
+	let details = UpgradeDetails::builder(Network::Kusama, "./upgrade-kusama-1.2.0")
+		.runtime(Network::Kusama, "v1.2.0")
+		.runtime(Network::KusamaAssetHub, "1.2.1")
+		.build()
+		.expect("valid upgrade");
+	assert_eq!(details.directory, "./upgrade-kusama-1.2.0/");
+	assert_eq!(details.output_file, "./upgrade-kusama-1.2.0/kusama-1.2.0.call");
+	assert_eq!(
+		details.networks,
+		vec![
+			VersionedNetwork { network: Network::Kusama, version: String::from("1.2.0") },
+			VersionedNetwork { network: Network::KusamaAssetHub, version: String::from("1.2.1") },
+		]
+	);
+
+	let wrong_relay = UpgradeDetails::builder(Network::Kusama, "./upgrade-kusama-1.2.0")
+		.runtime(Network::PolkadotAssetHub, "1.2.0")
+		.build();
+	assert!(matches!(wrong_relay, Err(Error::InvalidInput(_))));
+
+	let nothing = UpgradeDetails::builder(Network::Polkadot, "./upgrade-polkadot-1.2.0").build();
+	assert!(matches!(nothing, Err(Error::InvalidInput(_))));
+}
+
+// A minimal, uncompressed Wasm binary with only a `runtime_version` custom section.
+fn wasm_with_runtime_version(spec_name: &str, spec_version: u32) -> Vec<u8> {
+	use parity_scale_codec::Encode;
+	let version = (
+		String::from(spec_name),
+		String::from(spec_name),
+		0u32,
+		spec_version,
+		0u32,
+		Vec::<([u8; 8], u32)>::new(),
+		26u32,
+	)
+		.encode();
+	let name = "runtime_version";
+	let mut section = vec![name.len() as u8];
+	section.extend(name.as_bytes());
+	section.extend(version);
+	assert!(section.len() < 128, "section length must fit in one LEB128 byte");
+
+	let mut wasm = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+	wasm.push(0);
+	wasm.push(section.len() as u8);
+	wasm.extend(section);
+	wasm
+}
+
+#[test]
+fn it_reads_the_runtime_version() {
+	let wasm = wasm_with_runtime_version("statemint", 1_002_000);
+	let version = crate::wasm::runtime_version(&wasm).unwrap();
+	assert_eq!(version.spec_name, "statemint");
+	assert_eq!(version.spec_version, 1_002_000);
+	assert_eq!(version.transaction_version, 26);
+
+	assert!(crate::wasm::runtime_version(b"not wasm").is_err());
+	let no_section = [0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+	assert!(crate::wasm::runtime_version(&no_section).is_err());
+}
+
+#[test]
+fn runtime_version_must_match_the_upgrade() {
+	let chain =
+		VersionedNetwork { network: Network::PolkadotAssetHub, version: String::from("1.2.0") };
+	let fname = build_upgrade::runtime_file_name(&chain).unwrap();
+
+	let good = wasm_with_runtime_version("statemint", 1_002_000);
+	assert!(build_upgrade::check_runtime_version(&chain, &fname, &good).is_ok());
+
+	// Wrong version, e.g. an asset from another release.
+	let old = wasm_with_runtime_version("statemint", 1_001_000);
+	let err = build_upgrade::check_runtime_version(&chain, &fname, &old).unwrap_err();
+	assert!(matches!(err, Error::RuntimeCheck { .. }));
+	assert!(err.to_string().contains("spec_version 1001000"));
+
+	// Wrong chain, e.g. swapped release assets.
+	let swapped = wasm_with_runtime_version("statemine", 1_002_000);
+	let err = build_upgrade::check_runtime_version(&chain, &fname, &swapped).unwrap_err();
+	assert!(err.to_string().contains("spec_name `statemine`"));
+
+	// Blobs given with `--wasm` are checked too.
+	let path = std::env::temp_dir().join("opengov-cli-swapped-asset-hub.wasm");
+	std::fs::write(&path, &swapped).unwrap();
+	let mut args = upgrade_args_for_only_asset_hub();
+	args.wasm = vec![format!("asset-hub={}", path.display())];
+	let details = build_upgrade::parse_inputs(args).unwrap();
+	let err = build_upgrade::check_runtime_versions(&details).unwrap_err();
+	assert!(matches!(err, Error::RuntimeCheck { .. }));
+	std::fs::write(&path, &good).unwrap();
+	assert!(build_upgrade::check_runtime_versions(&details).is_ok());
+	std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn runtimes_are_checked_against_published_checksums() {
+	use build_upgrade::{verify_checksum, Checksums};
+	let fname = "asset_hub_polkadot_runtime-v1002000.compact.compressed.wasm";
+	let runtime = b"not really a runtime";
+	// `printf 'not really a runtime' | sha256sum`
+	let sha256 = "6b33b6665cbf972b7c38aab9e4076a7b88679347cedb95a67bb0d2c761e8f9b2";
+	let blake2 = hex::encode(sp_core::blake2_256(runtime));
+
+	let checksums = Checksums::parse(&format!(
+		"# release checksums\n{sha256}  *{fname}\nblake2_256:0x{blake2} ./{fname}\n"
+	))
+	.unwrap();
+	assert_eq!(checksums.sha256.len(), 1);
+	assert_eq!(checksums.blake2_256.len(), 1);
+
+	assert!(verify_checksum(fname, runtime, &checksums).is_ok());
+
+	// Only the Blake2 digest, which matches.
+	let blake2_only = Checksums::parse(&format!("blake2_256:{blake2}  {fname}")).unwrap();
+	assert!(verify_checksum(fname, runtime, &blake2_only).is_ok());
+
+	// A tampered blob.
+	let err = verify_checksum(fname, b"a tampered runtime", &blake2_only).unwrap_err();
+	assert_eq!(err.exit_code(), 12);
+
+	// A runtime missing from the file.
+	let err =
+		verify_checksum("polkadot_runtime-v1002000.compact.compressed.wasm", runtime, &blake2_only)
+			.unwrap_err();
+	assert!(err.to_string().contains("has no entry"));
+
+	// Malformed lines.
+	assert!(Checksums::parse("deadbeef  file.wasm").is_err());
+	assert!(Checksums::parse("not-hex-at-all  file.wasm").is_err());
+	assert!(Checksums::parse(&blake2).is_err());
+}
+
+#[test]
+fn wasm_paths_override_release_files() {
+	let mut args = upgrade_args_for_only_asset_hub();
+	args.filename = Some(String::from("wasm-path-test.call"));
+	args.wasm = vec![String::from("asset-hub=./srtool/asset-hub-polkadot-srtool.wasm")];
+	let details = build_upgrade::parse_inputs(args).unwrap();
+	let chain = &details.networks[0];
+	assert_eq!(chain.network, Network::PolkadotAssetHub);
+	assert_eq!(
+		build_upgrade::runtime_path(&details, chain).unwrap(),
+		"./srtool/asset-hub-polkadot-srtool.wasm"
+	);
+
+	// Without `--wasm`, the release's file in the upgrade directory.
+	let details = build_upgrade::parse_inputs(upgrade_args_for_only_asset_hub()).unwrap();
+	assert_eq!(
+		build_upgrade::runtime_path(&details, &details.networks[0]).unwrap(),
+		"./upgrade-polkadot-1.2.0/asset-hub-polkadot_runtime-v1002000.compact.compressed.wasm"
+	);
+
+	// Full network names work too.
+	let mut args = upgrade_args_for_only_asset_hub();
+	args.wasm = vec![String::from("asset-hub-polkadot=ah.wasm")];
+	assert!(build_upgrade::parse_inputs(args).is_ok());
+
+	// A chain that is not being upgraded.
+	let mut args = upgrade_args_for_only_asset_hub();
+	args.wasm = vec![String::from("people=people.wasm")];
+	assert!(matches!(build_upgrade::parse_inputs(args), Err(Error::InvalidInput(_))));
+
+	// A chain on the other network.
+	let mut args = upgrade_args_for_only_asset_hub();
+	args.wasm = vec![String::from("encointer=encointer.wasm")];
+	assert!(matches!(build_upgrade::parse_inputs(args), Err(Error::UnsupportedNetwork(_))));
+
+	// Not `<chain>=<path>`.
+	let mut args = upgrade_args_for_only_asset_hub();
+	args.wasm = vec![String::from("ah.wasm")];
+	assert!(matches!(build_upgrade::parse_inputs(args), Err(Error::InvalidInput(_))));
+}
+
+#[test]
+fn spec_names_resolve_to_networks() {
+	use Network::*;
+	for network in [
+		Kusama,
+		KusamaAssetHub,
+		KusamaBridgeHub,
+		KusamaPeople,
+		KusamaCoretime,
+		KusamaEncointer,
+		Polkadot,
+		PolkadotAssetHub,
+		PolkadotCollectives,
+		PolkadotBridgeHub,
+		PolkadotPeople,
+		PolkadotCoretime,
+	] {
+		assert_eq!(Network::from_spec_name(network.spec_name()), Ok(network));
+	}
+	assert!(Network::from_spec_name("westend").is_err());
+}
+
+#[test]
+fn bundled_metadata_lists_pallets() {
+	let metadata = crate::wasm::decode_metadata(Network::Polkadot.bundled_metadata()).unwrap();
+	let pallets = crate::wasm::pallets(&metadata);
+	assert!(pallets.contains(&(String::from("Preimage"), 10)));
+	assert!(pallets.windows(2).all(|w| w[0].1 < w[1].1));
+
+	let metadata =
+		crate::wasm::decode_metadata(Network::PolkadotAssetHub.bundled_metadata()).unwrap();
+	let pallets = crate::wasm::pallets(&metadata);
+	assert!(pallets.contains(&(String::from("Referenda"), 62)));
+	assert!(crate::metadata_diff::pallet_index_changes(&pallets, &pallets).is_empty());
+}
+
+#[test]
+fn pallet_index_changes_are_reported() {
+	use crate::metadata_diff::{pallet_index_changes, PalletIndexChange};
+	let pallets = |list: &[(&str, u8)]| {
+		list.iter().map(|(name, index)| (String::from(*name), *index)).collect::<Vec<_>>()
+	};
+	let bundled = pallets(&[("System", 0), ("Balances", 10), ("Treasury", 19), ("Old", 30)]);
+	let runtime = pallets(&[("System", 0), ("Balances", 10), ("Treasury", 20), ("New", 31)]);
+	assert_eq!(
+		pallet_index_changes(&bundled, &runtime),
+		vec![
+			PalletIndexChange::Moved { name: String::from("Treasury"), from: 19, to: 20 },
+			PalletIndexChange::Added { name: String::from("New"), index: 31 },
+			PalletIndexChange::Removed { name: String::from("Old"), index: 30 },
+		]
+	);
+}
+
+#[test]
+fn metadata_diff_reports_changes() {
+	use crate::metadata_diff::{diff_metadata, CallChange, PalletIndexChange};
+	let polkadot = crate::wasm::decode_metadata(Network::Polkadot.bundled_metadata()).unwrap();
+	let kusama = crate::wasm::decode_metadata(Network::Kusama.bundled_metadata()).unwrap();
+
+	assert!(diff_metadata(&polkadot, &polkadot).is_empty());
+
+	// Two different runtimes, as a stand-in for an upgrade that moves things around.
+	let diff = diff_metadata(&polkadot, &kusama);
+	assert!(diff.pallets.contains(&PalletIndexChange::Moved {
+		name: String::from("Preimage"),
+		from: 10,
+		to: 32
+	}));
+	assert!(diff.calls.iter().all(|change| match change {
+		CallChange::Added { pallet, .. }
+		| CallChange::Removed { pallet, .. }
+		| CallChange::Moved { pallet, .. }
+		| CallChange::ArgumentsChanged { pallet, .. } =>
+			crate::metadata_diff::CONSTRUCTED_PALLETS.contains(&pallet.as_str()),
+	}));
+
+	let version = crate::wasm::metadata_runtime_version(&kusama).unwrap();
+	assert_eq!(version.spec_name, "kusama");
+}
+
+#[test]
+fn calls_are_transcoded_with_metadata_overrides() {
+	use crate::dynamic::{transcode_call, MetadataOverrides};
+	let polkadot = crate::wasm::decode_metadata(Network::Polkadot.bundled_metadata()).unwrap();
+	let kusama = crate::wasm::decode_metadata(Network::Kusama.bundled_metadata()).unwrap();
+
+	// `preimage.note_preimage(0x1234)`: the Preimage pallet is at index 10 on Polkadot and 32 on
+	// Kusama.
+	let note_preimage = [10u8, 0, 8, 0x12, 0x34];
+	let transcoded = transcode_call(&note_preimage, &polkadot, &kusama).unwrap();
+	assert_eq!(transcoded, vec![32u8, 0, 8, 0x12, 0x34]);
+	assert_eq!(transcode_call(&transcoded, &kusama, &polkadot).unwrap(), note_preimage);
+	assert!(transcode_call(&[10u8, 0, 8, 0x12], &polkadot, &kusama).is_err());
+
+	// Overriding a network with its own bundled metadata leaves calls unchanged.
+	let remark = NetworkRuntimeCall::PolkadotAssetHub(PolkadotAssetHubRuntimeCall::System(
+		PolkadotAssetHubSystemCall::remark { remark: b"opengov-submit test".to_vec() },
+	));
+	let expected = CallInfo::from_runtime_call(NetworkRuntimeCall::PolkadotAssetHub(
+		PolkadotAssetHubRuntimeCall::System(PolkadotAssetHubSystemCall::remark {
+			remark: b"opengov-submit test".to_vec(),
+		}),
+	));
+	let mut overrides = MetadataOverrides::default();
+	let asset_hub =
+		crate::wasm::decode_metadata(Network::PolkadotAssetHub.bundled_metadata()).unwrap();
+	overrides.insert(Network::PolkadotAssetHub, asset_hub).unwrap();
+	assert!(!overrides.is_empty());
+
+	let call_info = overrides.call_info(remark).unwrap();
+	assert_eq!(call_info.encoded, expected.encoded);
+	assert_eq!(call_info.hash, expected.hash);
+	assert_eq!(overrides.to_bundled(&call_info).unwrap().encoded, expected.encoded);
+}
+
+#[test]
+fn newest_supported_metadata_version_is_requested() {
+	use crate::update_metadata::{newest_supported_version, opaque_metadata};
+	use parity_scale_codec::Encode;
+
+	assert_eq!(newest_supported_version(&vec![14u32, 15, 16, u32::MAX].encode()), Ok(16));
+	assert_eq!(newest_supported_version(&vec![14u32, 15].encode()), Ok(15));
+	assert!(newest_supported_version(&vec![13u32].encode()).is_err());
+
+	let metadata = Network::Kusama.bundled_metadata().to_vec();
+	assert_eq!(opaque_metadata(&Some(metadata.clone()).encode()), Ok(metadata));
+	assert!(opaque_metadata(&None::<Vec<u8>>.encode()).is_err());
+	assert_eq!(Network::KusamaAssetHub.metadata_file_name(), "kusama_asset_hub.scale");
+
+	// As many networks as entries, so every network has one.
+	let networks: Vec<_> = crate::METADATA_FILES.iter().map(|(network, ..)| network).collect();
+	assert!(networks.iter().enumerate().all(|(i, network)| !networks[..i].contains(network)));
+	assert_eq!(
+		Network::KusamaAssetHub.bundled_metadata(),
+		include_bytes!("../metadata/kusama_asset_hub.scale")
+	);
+}
+
+#[test]
+fn amounts_are_parsed_and_formatted() {
+	use crate::functions::{format_amount, parse_amount};
+
+	assert_eq!(parse_amount("1", 10).unwrap(), 10_000_000_000);
+	assert_eq!(parse_amount("1,250.5", 10).unwrap(), 12_505_000_000_000);
+	assert_eq!(parse_amount("0.000001", 6).unwrap(), 1);
+	assert_eq!(parse_amount(".5", 12).unwrap(), 500_000_000_000);
+	assert!(parse_amount("0.0000001", 6).is_err());
+	assert!(parse_amount("1e6", 6).is_err());
+	assert!(parse_amount("", 6).is_err());
+	assert!(parse_amount("340282366920938463463374607431768211455", 10).is_err());
+
+	assert_eq!(format_amount(12_505_000_000_000, 10), "1,250.5");
+	assert_eq!(format_amount(1_000_000_000_000_000, 12), "1,000");
+	assert_eq!(format_amount(1, 6), "0.000001");
+}
+
+#[test]
+fn spends_use_the_smallest_spender_track() {
+	use crate::build_spend::{spend_call, spender_track, SpendAsset};
+	use crate::parse_account;
+	use crate::NetworkTrack;
+
+	let dot = 10_000_000_000u128;
+	let track = |value: u128| spender_track(&Network::Polkadot, value).unwrap().name();
+	assert_eq!(track(250 * dot), "SmallTipper");
+	assert_eq!(track(250 * dot + 1), "BigTipper");
+	assert_eq!(track(50_000 * dot), "MediumSpender");
+	assert_eq!(track(10_000_000 * dot), "Treasurer");
+	assert!(matches!(
+		spender_track(&Network::Polkadot, 10_000_000 * dot + 1).unwrap(),
+		NetworkTrack::PolkadotRoot
+	));
+	assert!(matches!(
+		spender_track(&Network::Kusama, 9_000_000_000_000).unwrap(),
+		NetworkTrack::Kusama(KusamaAssetHubOpenGovOrigin::BigTipper)
+	));
+
+	// Alice, as a generic, a Polkadot, and a Kusama address.
+	let alice =
+		hex::decode("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d").unwrap();
+	for address in [
+		"5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+		"15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5",
+		"0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d",
+	] {
+		assert_eq!(parse_account(address, &Network::Polkadot).unwrap().to_vec(), alice);
+	}
+	let kusama_alice = "HNZata7iMYWmk5RvZRTiAsSDhV8366zq2YGb3tLH5Upf74F";
+	assert!(parse_account(kusama_alice, &Network::Polkadot).is_err());
+	assert_eq!(parse_account(kusama_alice, &Network::Kusama).unwrap().to_vec(), alice);
+
+	let usdt = SpendAsset::parse("usdt", None, &Network::Polkadot).unwrap();
+	assert_eq!(usdt, SpendAsset::Asset { id: 1984, symbol: String::from("USDT"), decimals: 6 });
+	assert!(SpendAsset::parse("usdc", None, &Network::Kusama).is_err());
+	assert!(SpendAsset::parse("42", None, &Network::Kusama).is_err());
+
+	let spend =
+		spend_call(&Network::Polkadot, &usdt, 1_000_000, alice.clone().try_into().unwrap(), None)
+			.unwrap();
+	let encoded = spend.encoded();
+	// `Treasury` (60) `spend` (5), then `VersionedLocatableAsset::V5` (5).
+	assert_eq!(encoded[..3], [60, 5, 5]);
+	assert_eq!(encoded[encoded.len() - 33..encoded.len() - 1], alice[..]);
+	let call_info = CallInfo::from_bytes(&encoded, Network::PolkadotAssetHub);
+	assert!(call_info.get_polkadot_asset_hub_call().is_ok());
+}
+
+#[test]
+fn all_tracks_are_supported() {
+	use crate::list_tracks::{origins, tracks};
+	use crate::submit_referendum::parse_track;
+	use crate::NetworkTrack;
+
+	let polkadot_origins = origins(&Network::Polkadot).unwrap();
+	assert_eq!(polkadot_origins.len(), 16);
+	assert!(matches!(polkadot_origins[0], NetworkTrack::PolkadotRoot));
+
+	for (network, track, name) in [
+		("polkadot", "root", "Root"),
+		("polkadot", "small-tipper", "SmallTipper"),
+		("polkadot", "big_spender", "BigSpender"),
+		("polkadot", "WishForChange", "WishForChange"),
+		("kusama", "medium-spender", "MediumSpender"),
+		("kusama", "whitelistedcaller", "WhitelistedCaller"),
+	] {
+		assert_eq!(parse_track(network, track).unwrap().name(), name);
+	}
+	assert!(matches!(parse_track("kusama", "big-tipper"), Ok(NetworkTrack::Kusama(_))));
+	assert!(parse_track("polkadot", "huge-spender").is_err());
+	assert!(parse_track("westend", "root").is_err());
+	// Kusama's Fellowship origins have no track on Asset Hub.
+	for origin in ["fellows", "fellowship-initiates", "fellowship1dan", "fellowship9dan"] {
+		assert!(crate::list_tracks::origin_by_name(&Network::Kusama, origin).unwrap().is_some());
+		assert!(matches!(parse_track("kusama", origin), Err(Error::UnsupportedTrack(_))));
+	}
+
+	for network in [Network::Polkadot, Network::Kusama] {
+		let tracks = tracks(&network).unwrap();
+		assert!(tracks.iter().all(|track| track.origin.is_some()));
+		let small_tipper = tracks.iter().find(|track| track.name == "small_tipper").unwrap();
+		assert_eq!(small_tipper.id, 30);
+		assert_eq!(small_tipper.origin.as_ref().unwrap().name(), "SmallTipper");
+		assert_eq!(tracks[0].id, 0);
+		assert_eq!(tracks[0].name, "root");
+	}
+}
+
+#[test]
+fn track_parameters_come_from_the_bundled_metadata() {
+	use crate::list_tracks::{earliest_enactment, track_for, Curve};
+	use crate::{DispatchTimeWrapper, NetworkTrack};
+
+	let small_tipper = track_for(
+		&Network::Polkadot,
+		&NetworkTrack::Polkadot(PolkadotAssetHubOpenGovOrigin::SmallTipper),
+	)
+	.unwrap();
+	assert_eq!(small_tipper.id, 30);
+	assert_eq!(small_tipper.decision_deposit, 10_000_000_000);
+	assert_eq!(small_tipper.max_deciding, 200);
+	assert_eq!(
+		(
+			small_tipper.prepare_period,
+			small_tipper.confirm_period,
+			small_tipper.min_enactment_period
+		),
+		(10, 100, 10)
+	);
+
+	// Prepare and confirm, then the later of the requested delay and the minimum enactment period.
+	assert_eq!(earliest_enactment(&small_tipper, &DispatchTimeWrapper::After(5)), (None, 120));
+	assert_eq!(earliest_enactment(&small_tipper, &DispatchTimeWrapper::After(50)), (None, 160));
+	assert_eq!(
+		earliest_enactment(&small_tipper, &DispatchTimeWrapper::At(1_000)),
+		(Some(1_000), 120)
+	);
+
+	let root = track_for(&Network::Kusama, &NetworkTrack::KusamaRoot).unwrap();
+	assert_eq!(root.id, 0);
+	let approval = root.min_approval.threshold(0.0);
+	assert!((approval - 1.0).abs() < 1e-6);
+	assert!((root.min_approval.threshold(1.0) - 0.5).abs() < 1e-6);
+	assert!((root.min_support.threshold(0.5) - 0.25).abs() < 1e-6);
+
+	let stepped = Curve::SteppedDecreasing {
+		begin: 1_000_000_000,
+		end: 500_000_000,
+		step: 100_000_000,
+		period: 250_000_000,
+	};
+	assert!((stepped.threshold(0.2) - 1.0).abs() < 1e-9);
+	assert!((stepped.threshold(0.3) - 0.9).abs() < 1e-9);
+	assert!((stepped.threshold(1.0) - 0.6).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn referendum_deposits_come_from_the_bundled_metadata() {
+	use crate::deposits::{preimage_deposit, referendum_deposits, Deposit};
+	use crate::list_tracks::track_for;
+
+	// Asset Hub and Collectives price a preimage at `deposit(2, 64)` plus `deposit(0, 1)` per byte,
+	// where an item is 0.2 DOT and a byte is 0.00001 DOT.
+	let (base, per_byte) = preimage_deposit(&Network::PolkadotAssetHub).unwrap();
+	assert_eq!((base, per_byte), (4_006_400_000, 100_000));
+	assert_eq!(preimage_deposit(&Network::PolkadotCollectives).unwrap(), (base, per_byte));
+
+	let proposal_details = polkadot_root_remark_user_input();
+	let calls = generate_calls(&proposal_details).await.unwrap();
+	let root = track_for(&Network::Polkadot, &proposal_details.track).unwrap();
+	let deposit = |network: Network, reason: &str, amount: u128| Deposit {
+		network,
+		reason: String::from(reason),
+		amount,
+	};
+	assert_eq!(
+		referendum_deposits(&proposal_details.track, &calls).unwrap(),
+		vec![
+			deposit(Network::PolkadotAssetHub, "Preimage deposit (23 bytes)", base + 23 * per_byte),
+			deposit(Network::PolkadotAssetHub, "Submission deposit", 10_000_000_000),
+			deposit(Network::PolkadotAssetHub, "Decision deposit", root.decision_deposit),
+		]
+	);
+
+	// The Fellowship referendum is inline, so it needs no preimage, and has no submission deposit.
+	let proposal_details = polkadot_whitelist_remark_user_input();
+	let calls = generate_calls(&proposal_details).await.unwrap();
+	let deposits = referendum_deposits(&proposal_details.track, &calls).unwrap();
+	assert_eq!(
+		deposits[..2],
+		[
+			deposit(Network::PolkadotCollectives, "Submission deposit", 0),
+			deposit(Network::PolkadotCollectives, "Decision deposit", 50_000_000_000),
+		]
+	);
+	assert_eq!(deposits[2].reason, "Preimage deposit (25 bytes)");
+	assert_eq!(deposits.len(), 5);
+}
+
+#[tokio::test]
+async fn decision_deposits_are_placed_after_submission() {
+	use crate::submit::PredictedReferendum;
+	use crate::submit_referendum::{place_decision_deposit, predict_referendum_indices};
+	use crate::{NetworkTrack, ReferendumIndices};
+
+	// `Referenda` and `FellowshipReferenda` are pallets 62 and 61, and `place_decision_deposit` is
+	// their call 1.
+	let place = place_decision_deposit(&Network::PolkadotAssetHub, 42).unwrap();
+	assert_eq!(place.encoded(), vec![62, 1, 42, 0, 0, 0]);
+	let place = place_decision_deposit(&Network::PolkadotCollectives, 7).unwrap();
+	assert_eq!(place.encoded(), vec![61, 1, 7, 0, 0, 0]);
+	assert!(matches!(
+		place_decision_deposit(&Network::Polkadot, 7),
+		Err(Error::UnsupportedNetwork(_))
+	));
+
+	// The deposit must not be placed if the submission fails, so the batch is a `batch_all`,
+	// call 2 of `Utility`, pallet 40.
+	let remark = "0x0000645468652046656c6c6f777368697020736179732068656c6c6f";
+	let details = ProposalDetails::builder(remark, NetworkTrack::PolkadotRoot)
+		.output(crate::Output::CallData)
+		.decision_deposit(ReferendumIndices { public: 42, fellowship: None })
+		.build()
+		.unwrap();
+	// `--submit` checks the `ReferendumCount` of the public referendum before signing.
+	let predicted = crate::submit_referendum::predicted_referenda(&details);
+	assert_eq!(
+		predicted,
+		vec![PredictedReferendum {
+			network: Network::PolkadotAssetHub,
+			pallet: "Referenda",
+			index: 42
+		}]
+	);
+	let calls = generate_calls(&details).await.unwrap();
+	let batches = crate::submit_referendum::deliver_output(details, calls).unwrap();
+	assert_eq!(batches.len(), 1);
+	assert_eq!(batches[0].1[..2], [40, 2]);
+	assert!(batches[0].1.ends_with(&[62, 1, 42, 0, 0, 0]));
+	assert!(crate::submit_referendum::predicted_referenda(&polkadot_root_remark_user_input())
+		.is_empty());
+
+	let whitelisted = NetworkTrack::Polkadot(PolkadotAssetHubOpenGovOrigin::WhitelistedCaller);
+	assert_eq!(
+		predict_referendum_indices(&whitelisted, false, Some(42), Some(7), &[]).await.unwrap(),
+		ReferendumIndices { public: 42, fellowship: Some(7) }
+	);
+	assert_eq!(
+		predict_referendum_indices(&NetworkTrack::PolkadotRoot, false, Some(42), None, &[])
+			.await
+			.unwrap(),
+		ReferendumIndices { public: 42, fellowship: None }
+	);
+	// Without a node to ask, the Fellowship referendum's index must be given.
+	assert!(matches!(
+		predict_referendum_indices(&whitelisted, false, Some(42), None, &[]).await,
+		Err(Error::InvalidInput(_))
+	));
+}
+
+#[tokio::test]
+async fn fellowship_votes_and_whitelists_are_checked() {
+	use crate::fellowship::{vote_call, whitelisted_hashes};
+	use sp_core::blake2_256;
+
+	// `FellowshipCollective::vote { poll: 42, aye: true }` on Collectives and nay on Kusama.
+	let vote = vote_call(&Network::PolkadotCollectives, 42, true).unwrap();
+	assert_eq!(vote.encoded(), vec![60, 4, 42, 0, 0, 0, 1]);
+	let vote = vote_call(&Network::Kusama, 42, false).unwrap();
+	assert_eq!(vote.encoded(), vec![22, 4, 42, 0, 0, 0, 0]);
+	assert!(vote_call(&Network::PolkadotAssetHub, 42, true).is_err());
+
+	// The inline Fellowship proposal sends an XCM to Asset Hub to whitelist the proposal.
+	let proposal_details = polkadot_whitelist_remark_user_input();
+	let proposal_hash = blake2_256(&get_proposal_bytes(proposal_details.proposal.clone()).unwrap());
+	let calls = generate_calls(&proposal_details).await.unwrap();
+	let fellowship_referendum = calls.fellowship_referendum_submission.unwrap();
+	assert_eq!(
+		whitelisted_hashes(&fellowship_referendum),
+		vec![(Network::PolkadotAssetHub, proposal_hash)]
+	);
+	assert!(whitelisted_hashes(&calls.public_referendum_submission.unwrap()).is_empty());
+}
+
+#[test]
+fn votes_encode_each_account_vote() {
+	use crate::vote::{vote_call, Vote};
+
+	// `ConvictionVoting` is pallet 61 on Polkadot Asset Hub, `vote` is its call 0, and the
+	// referendum index is compact.
+	let standard = Vote::Standard { aye: true, balance: 10_000_000_000, conviction: 1 };
+	let call = vote_call(&Network::Polkadot, 42, &standard).unwrap();
+	let mut expected = vec![61, 0, 42 << 2, 0, 0x81];
+	expected.extend_from_slice(&10_000_000_000u128.to_le_bytes());
+	assert_eq!(call.encoded(), expected);
+
+	let nay = Vote::Standard { aye: false, balance: 1, conviction: 6 };
+	assert_eq!(vote_call(&Network::Polkadot, 42, &nay).unwrap().encoded()[4], 6);
+
+	let split = Vote::Split { aye: 1, nay: 2 };
+	let encoded = vote_call(&Network::Polkadot, 42, &split).unwrap().encoded();
+	assert_eq!((encoded[3], encoded.len()), (1, 4 + 32));
+
+	let abstain = Vote::SplitAbstain { aye: 0, nay: 0, abstain: 3 };
+	let encoded = vote_call(&Network::Kusama, 42, &abstain).unwrap().encoded();
+	assert_eq!((encoded[3], encoded.len()), (2, 4 + 48));
+
+	let too_convinced = Vote::Standard { aye: true, balance: 1, conviction: 7 };
+	assert!(matches!(
+		vote_call(&Network::Polkadot, 42, &too_convinced),
+		Err(Error::InvalidInput(_))
+	));
+	assert!(vote_call(&Network::PolkadotAssetHub, 42, &standard).is_err());
+}
+
+#[test]
+fn signers_load_from_a_secret_uri_or_keystore() {
+	use crate::submit::load_signer;
+
+	// Alice's well-known development key.
+	let alice = "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+	let signer = load_signer(Some("//Alice"), None).unwrap();
+	assert_eq!(hex::encode(signer.public_key().0), alice);
+
+	// A keystore file holds the secret URI as a JSON string.
+	let path = std::env::temp_dir()
+		.join(format!("opengov-cli-keystore-{}-signer-test.json", std::process::id()));
+	std::fs::write(&path, "\"//Alice\"\n").expect("write keystore file");
+	let signer = load_signer(None, Some(&path.to_string_lossy()));
+	std::fs::remove_file(&path).ok();
+	assert_eq!(hex::encode(signer.unwrap().public_key().0), alice);
+
+	assert!(matches!(load_signer(None, None), Err(Error::InvalidInput(_))));
+	assert!(matches!(load_signer(Some("not a secret uri"), None), Err(Error::InvalidInput(_))));
+}
+
+#[test]
+fn batch_failures_are_utility_events_on_each_network() {
+	use crate::submit::BATCH_FAILURE_EVENTS;
+
+	for network in [
+		Network::Kusama,
+		Network::KusamaAssetHub,
+		Network::Polkadot,
+		Network::PolkadotAssetHub,
+		Network::PolkadotCollectives,
+	] {
+		let metadata = crate::wasm::decode_metadata(network.bundled_metadata()).unwrap();
+		let events = metadata.pallet_by_name("Utility").unwrap().event_variants().unwrap();
+		for name in BATCH_FAILURE_EVENTS {
+			assert!(events.iter().any(|e| e.name == name), "{network:?} has no `{name}`");
+		}
+	}
+}
+
+#[test]
+fn offline_payloads_are_signed_and_assembled() {
+	use crate::offline::{mortal_era, signer_payloads, Crypto, SignerPayload, TransactionContext};
+	use parity_scale_codec::Encode;
+	use sp_core::Pair;
+
+	// Valid for 64 blocks from block 42, i.e. period 2^6 and phase 42.
+	assert_eq!(mortal_era(42, 64), ((6 - 1) | (42 << 4) as u16).to_le_bytes());
+	assert_eq!(mortal_era(42, 50), mortal_era(42, 64));
+
+	let alice = sp_core::sr25519::Pair::from_string("//Alice", None).unwrap();
+	let signer: [u8; 32] = alice.public().as_ref().try_into().unwrap();
+	let context = TransactionContext {
+		genesis_hash: format!("0x{}", hex::encode([1; 32])),
+		block_hash: Some(format!("0x{}", hex::encode([2; 32]))),
+		block_number: Some(42),
+		nonce: 7,
+		spec_version: Some(1_000_000),
+		transaction_version: Some(15),
+		..Default::default()
+	};
+	let remark = vec![0, 0, 4, 0x42];
+	let payloads = signer_payloads(
+		vec![
+			(Network::PolkadotAssetHub, remark.clone()),
+			(Network::PolkadotAssetHub, remark.clone()),
+		],
+		signer,
+		Crypto::Sr25519,
+		&[(Network::PolkadotAssetHub, context)],
+	)
+	.unwrap();
+
+	// Era, nonce, tip, no fee asset, and metadata hash disabled. The second call takes the next
+	// nonce.
+	let era = mortal_era(42, 64);
+	assert_eq!(payloads[0].extra, vec![era[0], era[1], 7 << 2, 0, 0, 0]);
+	assert_eq!(payloads[1].extra, vec![era[0], era[1], 8 << 2, 0, 0, 0]);
+	let mut implicit = [1_000_000u32.to_le_bytes(), 15u32.to_le_bytes()].concat();
+	implicit.extend_from_slice(&[1; 32]);
+	implicit.extend_from_slice(&[2; 32]);
+	implicit.push(0);
+	assert_eq!(payloads[0].implicit, implicit);
+	assert_eq!(
+		payloads[0].signing_payload(),
+		[&remark[..], &payloads[0].extra, &implicit].concat()
+	);
+	assert_eq!(payloads[0].qr_payload()[..35], [&[0x53, 1, 2][..], &signer].concat());
+
+	// The Relay Chain pays fees only in its native token, so has no fee asset.
+	let relay_context = TransactionContext {
+		genesis_hash: format!("0x{}", hex::encode([1; 32])),
+		nonce: 7,
+		..Default::default()
+	};
+	let relay = SignerPayload::new(
+		Network::Polkadot,
+		remark.clone(),
+		signer,
+		Crypto::Sr25519,
+		&relay_context,
+		7,
+	)
+	.unwrap();
+	assert_eq!(relay.extra, vec![0, 7 << 2, 0, 0]);
+
+	// The payload survives the round trip through its file.
+	let payload = SignerPayload::from_json(&payloads[0].to_json().to_string()).unwrap();
+	assert_eq!(payload, payloads[0]);
+
+	let signature = alice.sign(&payload.signing_payload());
+	let extrinsic = payload.assemble(signature.as_ref()).unwrap();
+	// Length prefix, version 4 signed, `MultiAddress::Id`, the signer, and an sr25519 signature.
+	let length = parity_scale_codec::Compact(1 + 1 + 32 + 65 + 6 + 4u32).encode();
+	assert_eq!(extrinsic[..4], [&length[..], &[0x84, 0]].concat());
+	assert_eq!(extrinsic[4..36], signer);
+	assert_eq!(extrinsic[36], 1);
+	assert!(extrinsic.ends_with(&remark));
+	// Polkadot Vault's signatures are prefixed with the key type.
+	let prefixed = [&[1][..], signature.as_ref()].concat();
+	assert_eq!(payload.assemble(&prefixed).unwrap(), extrinsic);
+	// A signature of anything else is rejected.
+	let wrong = alice.sign(b"something else");
+	assert!(matches!(payload.assemble(wrong.as_ref()), Err(Error::InvalidInput(_))));
+}
+
+#[test]
+fn calls_are_wrapped_for_multisigs_and_proxies() {
+	use crate::wrap::{Multisig, Proxy, Timepoint, Wrapper};
+	use parity_scale_codec::{Compact, Encode};
+
+	let alice = "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+	let bob = "0x8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48";
+	let charlie = "0x90b5ab205c6974c9ea841be688864633dc9ca8a357843eeacf2314649965fe22";
+	let signatories = format!("{alice},{bob},{charlie}");
+	let timepoints = [String::from("polkadot-asset-hub:100:2")];
+	let max_weights = [String::from("polkadot-asset-hub:1000000:2000")];
+	let multisig =
+		Multisig::parse(&format!("2:{signatories}"), &timepoints, &max_weights, &Network::Polkadot)
+			.unwrap();
+	assert_eq!(
+		hex::encode(multisig.account_id()),
+		"49daa32c7287890f38b7e1a8cd2961723d36d20baa0bf3b82e0c4bdda93b1c0a"
+	);
+	assert_eq!(
+		multisig.timepoints,
+		vec![(Network::PolkadotAssetHub, Timepoint { height: 100, index: 2 })]
+	);
+	assert_eq!(multisig.max_weights, vec![(Network::PolkadotAssetHub, (1_000_000, 2_000))]);
+	assert!(Multisig::parse(&format!("4:{signatories}"), &[], &[], &Network::Polkadot).is_err());
+	assert!(Multisig::parse(&format!("2:{alice},{alice}"), &[], &[], &Network::Polkadot).is_err());
+
+	let remark = NetworkRuntimeCall::PolkadotAssetHub(PolkadotAssetHubRuntimeCall::System(
+		PolkadotAssetHubSystemCall::remark { remark: b"opengov-submit test".to_vec() },
+	));
+	let metadata = Default::default();
+
+	// `Multisig` is pallet 41 on Polkadot Asset Hub. Alice submits `as_multi` without a timepoint,
+	// and Bob dispatches the call with `as_multi` at the timepoint.
+	let wrapper = Wrapper { multisig: Some(multisig.clone()), proxy: None };
+	let wrapped = wrapper.wrap(&remark, &metadata).unwrap();
+	assert_eq!(wrapped.call.encoded()[..4], [41, 1, 2, 0]);
+	assert_eq!(wrapped.multisig.unwrap().1, sp_core::blake2_256(&remark.encoded()));
+	assert_eq!(wrapped.approvals.len(), 1);
+	let (bob_id, approval) = &wrapped.approvals[0];
+	assert_eq!(format!("0x{}", hex::encode(bob_id)), bob);
+	assert_eq!(approval.encoded()[..2], [41, 1]);
+	// Its `max_weight`, the last field, is the weight of the call.
+	let weight = [Compact(1_000_000u64).encode(), Compact(2_000u64).encode()].concat();
+	assert!(approval.encoded().ends_with(&weight));
+
+	// The approval that dispatches the call needs its weight, which must fit in an extrinsic.
+	let unweighed = Multisig { max_weights: Vec::new(), ..multisig.clone() };
+	let wrapper = Wrapper { multisig: Some(unweighed), proxy: None };
+	assert!(matches!(wrapper.wrap(&remark, &metadata), Err(Error::InvalidInput(_))));
+	let heavy = vec![(Network::PolkadotAssetHub, (u64::MAX, 0))];
+	let wrapper = Wrapper {
+		multisig: Some(Multisig { max_weights: heavy, ..multisig.clone() }),
+		proxy: None,
+	};
+	assert!(matches!(wrapper.wrap(&remark, &metadata), Err(Error::InvalidInput(_))));
+
+	// With a threshold of three, Bob approves the hash and Charlie dispatches.
+	let wrapper =
+		Wrapper { multisig: Some(Multisig { threshold: 3, ..multisig.clone() }), proxy: None };
+	let wrapped = wrapper.wrap(&remark, &metadata).unwrap();
+	let approvals: Vec<_> =
+		wrapped.approvals.iter().map(|(_, c)| c.encoded()[..2].to_vec()).collect();
+	assert_eq!(approvals, vec![vec![41, 2], vec![41, 1]]);
+
+	// `Proxy::proxy` is call 0 of pallet 42, with the real account and `Governance`, type 7.
+	let proxy = Proxy::parse(&format!("{alice}:governance"), &Network::Polkadot).unwrap();
+	let wrapper = Wrapper { multisig: None, proxy: Some(proxy) };
+	let encoded = wrapper.wrap(&remark, &metadata).unwrap().call.encoded();
+	assert_eq!(encoded[..3], [42, 0, 0]);
+	assert_eq!(format!("0x{}", hex::encode(&encoded[3..35])), alice);
+	assert_eq!(encoded[35..37], [1, 7]);
+	assert_eq!(encoded[37..], remark.encoded());
+	let proxy = Proxy::parse(&format!("{alice}:society"), &Network::Polkadot).unwrap();
+	let wrapper = Wrapper { multisig: None, proxy: Some(proxy) };
+	assert!(matches!(wrapper.wrap(&remark, &metadata), Err(Error::InvalidInput(_))));
+
+	// A timepoint is of a network's batch, so cannot be used without batches.
+	let wrapper = Wrapper { multisig: Some(multisig), proxy: None };
+	let details = ProposalDetails::builder("0x0000", crate::NetworkTrack::PolkadotRoot)
+		.print_batch(false)
+		.wrapper(wrapper)
+		.build();
+	assert!(matches!(details, Err(Error::InvalidInput(_))));
+}
+
+#[tokio::test]
+async fn proposals_are_simulated_with_the_origin_of_their_track() {
+	use crate::dynamic::{transcode_origin, MetadataOverrides};
+	use crate::simulate::{dispatches, dry_run_effects};
+	use crate::NetworkTrack::*;
+	use parity_scale_codec::Encode;
+
+	let remark = "0x0000645468652046656c6c6f777368697020736179732068656c6c6f";
+	let details = ProposalDetails::builder(remark, PolkadotRoot).build().unwrap();
+	let calls = dispatches(&details).await.unwrap();
+	assert_eq!(calls.len(), 1);
+	assert_eq!(calls[0].network, Network::PolkadotAssetHub);
+	// `OriginCaller::system(RawOrigin::Root)`.
+	assert_eq!(calls[0].origin, vec![0, 0]);
+	assert_eq!(calls[0].call, get_proposal_bytes(String::from(remark)).unwrap());
+
+	// With the Whitelist, the Fellowship's call comes first and the proposal is dispatched as Root.
+	let whitelisted = Polkadot(PolkadotAssetHubOpenGovOrigin::WhitelistedCaller);
+	let details = ProposalDetails::builder(remark, whitelisted).build().unwrap();
+	let calls = dispatches(&details).await.unwrap();
+	let steps: Vec<_> = calls.iter().map(|c| (c.network.clone(), c.origin_name.as_str())).collect();
+	assert_eq!(
+		steps,
+		vec![(Network::PolkadotCollectives, "Fellows"), (Network::PolkadotAssetHub, "Root")]
+	);
+	assert_eq!(calls[1].origin, vec![0, 0]);
+
+	// Origins are encoded with the metadata overrides, like calls.
+	let metadata =
+		crate::wasm::decode_metadata(Network::PolkadotAssetHub.bundled_metadata()).unwrap();
+	assert_eq!(transcode_origin(&[0, 0], &metadata, &metadata).unwrap(), vec![0, 0]);
+	assert!(transcode_origin(&[0, 0, 0], &metadata, &metadata).is_err());
+	let mut overrides = MetadataOverrides::default();
+	overrides.insert(Network::PolkadotAssetHub, metadata).unwrap();
+	assert_eq!(overrides.encode_origin(&Network::PolkadotAssetHub, &[0, 0]).unwrap(), vec![0, 0]);
+
+	// A dry run on Polkadot Asset Hub that failed with `Referenda::NotOngoing` (pallet 62, error
+	// 0), after emitting `System::Remarked` and sending `ClearOrigin` to the Relay Chain.
+	let mut output = vec![0, 1, 0, 0, 3, 62, 0, 0, 0, 0];
+	output.extend([4, 0, 5]);
+	output.extend([1u8; 32]);
+	output.extend([2u8; 32]);
+	output.push(0);
+	output.extend([4, 5, 1, 0, 4, 5, 4, 10]);
+	let metadata =
+		crate::wasm::decode_metadata(Network::PolkadotAssetHub.bundled_metadata()).unwrap();
+	let effects = dry_run_effects(&Network::PolkadotAssetHub, &metadata, &output).unwrap();
+	assert_eq!(effects.error.as_deref(), Some("Referenda.NotOngoing"));
+	assert_eq!(
+		effects.events,
+		vec![format!(
+			"System.Remarked {{ sender: 0x{}, hash: 0x{} }}",
+			"01".repeat(32),
+			"02".repeat(32)
+		)]
+	);
+	assert_eq!(effects.local_xcm, None);
+	assert_eq!(
+		effects.forwarded_xcms,
+		vec![(
+			String::from("V5({ parents: 1, interior: Here })"),
+			vec![String::from("V5([ClearOrigin])")]
+		)]
+	);
+
+	// The runtime could not dry-run the call.
+	let unimplemented = Err::<(), u8>(0).encode();
+	let err = dry_run_effects(&Network::PolkadotAssetHub, &metadata, &unimplemented).unwrap_err();
+	assert_eq!(err.exit_code(), 18);
+}
+
+#[test]
+fn snapshots_load_and_storage_changes_are_described() {
+	use crate::simulate::{storage_changes, StorageChange};
+	use crate::wasm::{Snapshot, SNAPSHOT_VERSION};
+	use parity_scale_codec::{Compact, Encode};
+	use sp_core::{storage::StateVersion, twox_128, Blake2Hasher};
+
+	let mut storage = sp_core::storage::Storage::default();
+	storage.top.insert(b"key".to_vec(), b"value".to_vec());
+	let (raw_storage, root) =
+		sp_state_machine::TestExternalities::<Blake2Hasher>::new(storage).into_raw_snapshot();
+	let mut encoded = Compact(SNAPSHOT_VERSION).encode();
+	encoded.extend(StateVersion::V1.encode());
+	encoded.extend(raw_storage.encode());
+	encoded.extend(root.encode());
+	let snapshot = Snapshot::decode(&encoded).unwrap();
+	assert_eq!(snapshot.storage(b"key").unwrap(), Some(b"value".to_vec()));
+	assert_eq!(snapshot.storage(b"other").unwrap(), None);
+	assert!(Snapshot::decode(&Compact(3u16).encode()).unwrap_err().contains("version 3"));
+
+	// Keys of items in the metadata are named, and their values decoded.
+	let metadata =
+		crate::wasm::decode_metadata(Network::PolkadotAssetHub.bundled_metadata()).unwrap();
+	let number = [twox_128(b"System"), twox_128(b"Number")].concat();
+	let changes = vec![
+		(number, Some(5u32.encode()), Some(6u32.encode())),
+		(vec![0xff; 4], None, Some(vec![1, 2])),
+	];
+	assert_eq!(
+		storage_changes(&metadata, changes),
+		vec![
+			StorageChange {
+				item: Some(String::from("System.Number")),
+				key: String::from("0x"),
+				old: Some(String::from("5")),
+				new: Some(String::from("6")),
+			},
+			StorageChange {
+				item: None,
+				key: String::from("0xffffffff"),
+				old: None,
+				new: Some(String::from("0x0102")),
+			},
+		]
+	);
+}
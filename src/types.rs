@@ -1,3 +1,6 @@
+use crate::dynamic::MetadataOverrides;
+use crate::wrap::Wrapper;
+use crate::Error;
 pub(super) use parity_scale_codec::Encode as _;
 pub(super) use sp_core::blake2_256;
 pub(super) use subxt::utils::H256;
@@ -9,7 +12,7 @@ pub(super) use subxt::utils::H256;
 	derive_for_all_types = "PartialEq, Clone"
 )]
 pub mod kusama_relay {}
-pub(super) use kusama_relay::runtime_types::staging_kusama_runtime::{
+pub use kusama_relay::runtime_types::staging_kusama_runtime::{
 	governance::origins::pallet_custom_origins::Origin as KusamaOpenGovOrigin,
 	OriginCaller as KusamaOriginCaller, RuntimeCall as KusamaRuntimeCall,
 };
@@ -19,26 +22,26 @@ pub(super) use kusama_relay::runtime_types::staging_kusama_runtime::{
 	derive_for_all_types = "PartialEq, Clone"
 )]
 pub mod kusama_asset_hub {}
-pub(super) use kusama_asset_hub::runtime_types::asset_hub_kusama_runtime::{
+pub use kusama_asset_hub::runtime_types::asset_hub_kusama_runtime::{
 	governance::origins::pallet_custom_origins::Origin as KusamaAssetHubOpenGovOrigin,
 	OriginCaller as KusamaAssetHubOriginCaller, RuntimeCall as KusamaAssetHubRuntimeCall,
 };
 
 #[subxt::subxt(runtime_metadata_path = "metadata/kusama_bridge_hub.scale")]
 pub mod kusama_bridge_hub {}
-pub(super) use kusama_bridge_hub::runtime_types::bridge_hub_kusama_runtime::RuntimeCall as KusamaBridgeHubRuntimeCall;
+pub use kusama_bridge_hub::runtime_types::bridge_hub_kusama_runtime::RuntimeCall as KusamaBridgeHubRuntimeCall;
 
 #[subxt::subxt(runtime_metadata_path = "metadata/kusama_encointer.scale")]
 pub mod kusama_encointer {}
-pub(super) use kusama_encointer::runtime_types::encointer_kusama_runtime::RuntimeCall as KusamaEncointerRuntimeCall;
+pub use kusama_encointer::runtime_types::encointer_kusama_runtime::RuntimeCall as KusamaEncointerRuntimeCall;
 
 #[subxt::subxt(runtime_metadata_path = "metadata/kusama_people.scale")]
 pub mod kusama_people {}
-pub(super) use kusama_people::runtime_types::people_kusama_runtime::RuntimeCall as KusamaPeopleRuntimeCall;
+pub use kusama_people::runtime_types::people_kusama_runtime::RuntimeCall as KusamaPeopleRuntimeCall;
 
 #[subxt::subxt(runtime_metadata_path = "metadata/kusama_coretime.scale")]
 pub mod kusama_coretime {}
-pub(super) use kusama_coretime::runtime_types::coretime_kusama_runtime::RuntimeCall as KusamaCoretimeRuntimeCall;
+pub use kusama_coretime::runtime_types::coretime_kusama_runtime::RuntimeCall as KusamaCoretimeRuntimeCall;
 
 // Polkadot Chains ---------------------------------------------------------------------------------
 
@@ -47,39 +50,40 @@ pub(super) use kusama_coretime::runtime_types::coretime_kusama_runtime::RuntimeC
 	derive_for_all_types = "PartialEq, Clone"
 )]
 pub mod polkadot_relay {}
-pub(super) use polkadot_relay::runtime_types::polkadot_runtime::RuntimeCall as PolkadotRuntimeCall;
+pub use polkadot_relay::runtime_types::polkadot_runtime::RuntimeCall as PolkadotRuntimeCall;
 
 #[subxt::subxt(
 	runtime_metadata_path = "metadata/polkadot_asset_hub.scale",
 	derive_for_all_types = "PartialEq, Clone"
 )]
 pub mod polkadot_asset_hub {}
-pub(super) use polkadot_asset_hub::runtime_types::asset_hub_polkadot_runtime::{
+pub use polkadot_asset_hub::runtime_types::asset_hub_polkadot_runtime::{
 	governance::origins::pallet_custom_origins::Origin as PolkadotAssetHubOpenGovOrigin,
 	OriginCaller as PolkadotAssetHubOriginCaller, RuntimeCall as PolkadotAssetHubRuntimeCall,
 };
 
 #[subxt::subxt(runtime_metadata_path = "metadata/polkadot_collectives.scale")]
 pub mod polkadot_collectives {}
-pub(super) use polkadot_collectives::runtime_types::collectives_polkadot_runtime::{
+pub use polkadot_collectives::runtime_types::collectives_polkadot_runtime::{
 	fellowship::origins::pallet_origins::Origin as FellowshipOrigins,
 	RuntimeCall as CollectivesRuntimeCall,
 };
 
 #[subxt::subxt(runtime_metadata_path = "metadata/polkadot_bridge_hub.scale")]
 pub mod polkadot_bridge_hub {}
-pub(super) use polkadot_bridge_hub::runtime_types::bridge_hub_polkadot_runtime::RuntimeCall as PolkadotBridgeHubRuntimeCall;
+pub use polkadot_bridge_hub::runtime_types::bridge_hub_polkadot_runtime::RuntimeCall as PolkadotBridgeHubRuntimeCall;
 
 #[subxt::subxt(runtime_metadata_path = "metadata/polkadot_people.scale")]
 pub mod polkadot_people {}
-pub(super) use polkadot_people::runtime_types::people_polkadot_runtime::RuntimeCall as PolkadotPeopleRuntimeCall;
+pub use polkadot_people::runtime_types::people_polkadot_runtime::RuntimeCall as PolkadotPeopleRuntimeCall;
 
 #[subxt::subxt(runtime_metadata_path = "metadata/polkadot_coretime.scale")]
 pub mod polkadot_coretime {}
-pub(super) use polkadot_coretime::runtime_types::coretime_polkadot_runtime::RuntimeCall as PolkadotCoretimeRuntimeCall;
+pub use polkadot_coretime::runtime_types::coretime_polkadot_runtime::RuntimeCall as PolkadotCoretimeRuntimeCall;
 
-#[derive(Clone, Debug, PartialEq)]
-pub(super) enum Network {
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Network {
 	Kusama,
 	KusamaAssetHub,
 	KusamaEncointer,
@@ -95,8 +99,33 @@ pub(super) enum Network {
 }
 
 impl Network {
+	/// Parse a user-provided network name, e.g. `polkadot-asset-hub` or `asset-hub-polkadot`.
+	/// Case, dashes, and underscores are ignored.
+	pub fn from_name(name: &str) -> Result<Network, &'static str> {
+		use Network::*;
+		let name = name.to_ascii_lowercase().replace(['-', '_', ' '], "");
+		match name.as_str() {
+			// Kusama
+			"kusama" => Ok(Kusama),
+			"kusamaassethub" | "assethubkusama" => Ok(KusamaAssetHub),
+			"kusamabridgehub" | "bridgehubkusama" => Ok(KusamaBridgeHub),
+			"kusamapeople" | "peoplekusama" => Ok(KusamaPeople),
+			"kusamacoretime" | "coretimekusama" => Ok(KusamaCoretime),
+			"kusamaencointer" | "encointerkusama" | "encointer" => Ok(KusamaEncointer),
+			// Polkadot
+			"polkadot" => Ok(Polkadot),
+			"polkadotassethub" | "assethubpolkadot" => Ok(PolkadotAssetHub),
+			"polkadotcollectives" | "collectivespolkadot" | "collectives" =>
+				Ok(PolkadotCollectives),
+			"polkadotbridgehub" | "bridgehubpolkadot" => Ok(PolkadotBridgeHub),
+			"polkadotpeople" | "peoplepolkadot" => Ok(PolkadotPeople),
+			"polkadotcoretime" | "coretimepolkadot" => Ok(PolkadotCoretime),
+			_ => Err("unknown network"),
+		}
+	}
+
 	/// Return the `ParaId` of a given network. Returns an error if the network is not a parachain.
-	pub(super) fn get_para_id(&self) -> Result<u32, &'static str> {
+	pub fn get_para_id(&self) -> Result<u32, &'static str> {
 		use Network::*;
 		match &self {
 			// Kusama
@@ -115,66 +144,512 @@ impl Network {
 			PolkadotCoretime => Ok(1_005),
 		}
 	}
+
+	/// The inverse of `get_para_id`. Return the system parachain with `para_id` on `relay`.
+	pub fn from_para_id(relay: &Network, para_id: u32) -> Result<Network, &'static str> {
+		use Network::*;
+		match (relay, para_id) {
+			// Kusama
+			(Kusama, 1_000) => Ok(KusamaAssetHub),
+			(Kusama, 1_001) => Ok(KusamaEncointer),
+			(Kusama, 1_002) => Ok(KusamaBridgeHub),
+			(Kusama, 1_004) => Ok(KusamaPeople),
+			(Kusama, 1_005) => Ok(KusamaCoretime),
+			// Polkadot
+			(Polkadot, 1_000) => Ok(PolkadotAssetHub),
+			(Polkadot, 1_001) => Ok(PolkadotCollectives),
+			(Polkadot, 1_002) => Ok(PolkadotBridgeHub),
+			(Polkadot, 1_004) => Ok(PolkadotPeople),
+			(Polkadot, 1_005) => Ok(PolkadotCoretime),
+			(Kusama, _) | (Polkadot, _) => Err("unknown parachain"),
+			_ => Err("not a relay chain"),
+		}
+	}
+
+	/// Return the Relay Chain of a given network.
+	pub fn relay(&self) -> Network {
+		use Network::*;
+		match &self {
+			Kusama | KusamaAssetHub | KusamaBridgeHub | KusamaPeople | KusamaCoretime
+			| KusamaEncointer => Kusama,
+			Polkadot | PolkadotAssetHub | PolkadotCollectives | PolkadotBridgeHub
+			| PolkadotPeople | PolkadotCoretime => Polkadot,
+		}
+	}
+
+	/// The symbol and number of decimals of this network's native token.
+	pub fn token(&self) -> (&'static str, u8) {
+		match self.relay() {
+			Network::Kusama => ("KSM", 12),
+			_ => ("DOT", 10),
+		}
+	}
+
+	/// The `spec_name` that this network's runtime declares in its `RuntimeVersion`.
+	pub fn spec_name(&self) -> &'static str {
+		use Network::*;
+		match &self {
+			// Kusama
+			Kusama => "kusama",
+			KusamaAssetHub => "statemine",
+			KusamaBridgeHub => "bridge-hub-kusama",
+			KusamaPeople => "people-kusama",
+			KusamaCoretime => "coretime-kusama",
+			KusamaEncointer => "encointer-parachain",
+			// Polkadot
+			Polkadot => "polkadot",
+			PolkadotAssetHub => "statemint",
+			PolkadotCollectives => "collectives",
+			PolkadotBridgeHub => "bridge-hub-polkadot",
+			PolkadotPeople => "people-polkadot",
+			PolkadotCoretime => "coretime-polkadot",
+		}
+	}
+
+	/// The inverse of `spec_name`. Return the network whose runtime declares `spec_name`.
+	pub fn from_spec_name(spec_name: &str) -> Result<Network, &'static str> {
+		use Network::*;
+		match spec_name {
+			// Kusama
+			"kusama" => Ok(Kusama),
+			"statemine" => Ok(KusamaAssetHub),
+			"bridge-hub-kusama" => Ok(KusamaBridgeHub),
+			"people-kusama" => Ok(KusamaPeople),
+			"coretime-kusama" => Ok(KusamaCoretime),
+			"encointer-parachain" => Ok(KusamaEncointer),
+			// Polkadot
+			"polkadot" => Ok(Polkadot),
+			"statemint" => Ok(PolkadotAssetHub),
+			"collectives" => Ok(PolkadotCollectives),
+			"bridge-hub-polkadot" => Ok(PolkadotBridgeHub),
+			"people-polkadot" => Ok(PolkadotPeople),
+			"coretime-polkadot" => Ok(PolkadotCoretime),
+			_ => Err("unknown spec_name"),
+		}
+	}
+
+	/// The SCALE-encoded metadata in `metadata/` from which this network's types were generated.
+	pub fn bundled_metadata(&self) -> &'static [u8] {
+		self.metadata_file().2
+	}
+
+	/// The name of the file in `metadata/` that `bundled_metadata` is read from.
+	pub fn metadata_file_name(&self) -> &'static str {
+		self.metadata_file().1
+	}
+
+	// The entry of this network in `METADATA_FILES`, which has one entry for each network.
+	fn metadata_file(&self) -> &'static (Network, &'static str, &'static [u8]) {
+		METADATA_FILES
+			.iter()
+			.find(|(network, _, _)| network == self)
+			.expect("METADATA_FILES has an entry for every network")
+	}
 }
 
-// Info and preferences provided by the user for proposal submission.
-pub(super) struct ProposalDetails {
-	// The proposal, generated elsewhere and pasted here.
-	pub(super) proposal: String,
-	// The track to submit on.
-	pub(super) track: NetworkTrack,
-	// When do you want this to enact. `At(block)` or `After(blocks)`.
-	pub(super) dispatch: DispatchTimeWrapper,
-	// How you would like to view the output.
-	pub(super) output: Output,
-	// Cutoff length in bytes for printing the output. If too long, it will print the hash of the
-	// call you would need to submit so that you can verify before submission.
-	pub(super) output_len_limit: u32,
-	// Whether or not to group all calls into a batch. Uses `force_batch` in case the account does
-	// not have funds for pre-image deposits or is not a fellow.
-	pub(super) print_batch: bool,
-	// Whether to use light client endpoints in PAPI links (default true).
-	pub(super) use_light_client: bool,
-	// Whether to use the Polkadot Fellowship (on Collectives) instead of the Kusama Fellowship.
-	// Only applicable for Kusama WhitelistedCaller track.
-	pub(super) fellowship_on_polkadot: bool,
+// Each network with the file in `metadata/` that its types were generated from, and the bytes of
+// that file.
+macro_rules! metadata_files {
+	($($network:ident => $file:literal,)*) => {
+		[$((Network::$network, $file, include_bytes!(concat!("../metadata/", $file)))),*]
+	};
 }
 
-// Info and preferences provided by the user for runtime upgrade construction.
-pub(super) struct UpgradeDetails {
-	// The Relay Network for this upgrade, Polkadot or Kusama.
-	pub(super) relay: Network,
-	// All networks to upgrade.
-	pub(super) networks: Vec<VersionedNetwork>,
-	// The directory into which to write information needed.
-	pub(super) directory: String,
-	// The filename of the output.
-	pub(super) output_file: String,
-	// An additional call to be enacted in the same batch as the system upgrade.
-	pub(super) additional: Option<CallInfo>,
-	// Skip sanity checks on the downloaded runtime blobs.
-	pub(super) no_runtime_checks: bool,
+/// The file in `metadata/` of each network, with its SCALE-encoded metadata.
+pub const METADATA_FILES: [(Network, &str, &[u8]); 12] = metadata_files! {
+	// Kusama
+	Kusama => "kusama.scale",
+	KusamaAssetHub => "kusama_asset_hub.scale",
+	KusamaBridgeHub => "kusama_bridge_hub.scale",
+	KusamaPeople => "kusama_people.scale",
+	KusamaCoretime => "kusama_coretime.scale",
+	KusamaEncointer => "kusama_encointer.scale",
+	// Polkadot
+	Polkadot => "polkadot.scale",
+	PolkadotAssetHub => "polkadot_asset_hub.scale",
+	PolkadotCollectives => "polkadot_collectives.scale",
+	PolkadotBridgeHub => "polkadot_bridge_hub.scale",
+	PolkadotPeople => "polkadot_people.scale",
+	PolkadotCoretime => "polkadot_coretime.scale",
+};
+
+/// Info and preferences provided by the user for proposal submission.
+pub struct ProposalDetails {
+	/// The proposal, generated elsewhere and pasted here.
+	pub(crate) proposal: String,
+	/// The track to submit on.
+	pub(crate) track: NetworkTrack,
+	/// When do you want this to enact. `At(block)` or `After(blocks)`.
+	pub(crate) dispatch: DispatchTimeWrapper,
+	/// How you would like to view the output.
+	pub(crate) output: Output,
+	/// Cutoff length in bytes for printing the output. If too long, it will print the hash of the
+	/// call you would need to submit so that you can verify before submission.
+	pub(crate) output_len_limit: u32,
+	/// Whether or not to group all calls into a batch. Uses `force_batch` in case the account does
+	/// not have funds for pre-image deposits or is not a fellow, or `batch_all` when placing
+	/// decision deposits, so that none is placed if its submission fails.
+	pub(crate) print_batch: bool,
+	/// Whether to use light client endpoints in PAPI links (default true).
+	pub(crate) use_light_client: bool,
+	/// Whether to use the Polkadot Fellowship (on Collectives) instead of the Kusama Fellowship.
+	/// Only applicable for Kusama WhitelistedCaller track.
+	pub(crate) fellowship_on_polkadot: bool,
+	/// Metadata to encode calls with instead of the bundled metadata.
+	pub(crate) metadata: MetadataOverrides,
+	/// The indices that the referenda will get, if their decision deposits should be placed in the
+	/// same batch as their submissions.
+	pub(crate) decision_deposit: Option<ReferendumIndices>,
+	/// How to wrap each call for a multisig or proxy to submit it.
+	pub(crate) wrapper: Wrapper,
 }
 
-// A network and the version to which it will upgrade.
+/// The indices that the referenda for a proposal will get once submitted.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReferendumIndices {
+	/// The index of the public referendum.
+	pub public: u32,
+	/// The index of the Fellowship referendum to whitelist the proposal, if there is one.
+	pub fellowship: Option<u32>,
+}
+
+/// Info and preferences provided by the user for runtime upgrade construction.
+pub struct UpgradeDetails {
+	/// The Relay Network for this upgrade, Polkadot or Kusama.
+	pub(crate) relay: Network,
+	/// All networks to upgrade.
+	pub(crate) networks: Vec<VersionedNetwork>,
+	/// The directory into which to write information needed.
+	pub(crate) directory: String,
+	/// The filename of the output.
+	pub(crate) output_file: String,
+	/// An additional call to be enacted in the same batch as the system upgrade.
+	pub(crate) additional: Option<CallInfo>,
+	/// Skip sanity checks on the downloaded runtime blobs.
+	pub(crate) no_runtime_checks: bool,
+	/// Wasm files to authorize instead of the release's, for some of `networks`.
+	pub(crate) wasm_paths: Vec<(Network, String)>,
+	/// Metadata to encode calls with instead of the bundled metadata.
+	pub(crate) metadata: MetadataOverrides,
+}
+
+impl ProposalDetails {
+	/// Start building the details for submitting `proposal` on `track`. The `proposal` can either
+	/// be the call data itself, e.g. "0x0102...", or a path to a file that contains it. All other
+	/// preferences default to those of the CLI.
+	pub fn builder(proposal: impl Into<String>, track: NetworkTrack) -> ProposalDetailsBuilder {
+		ProposalDetailsBuilder {
+			details: ProposalDetails {
+				proposal: proposal.into(),
+				track,
+				dispatch: DispatchTimeWrapper::After(10),
+				output: Output::AppsUiLink,
+				output_len_limit: 1_000,
+				print_batch: true,
+				use_light_client: false,
+				fellowship_on_polkadot: false,
+				metadata: MetadataOverrides::default(),
+				decision_deposit: None,
+				wrapper: Wrapper::default(),
+			},
+		}
+	}
+}
+
+/// Builds a [`ProposalDetails`]. Create one with [`ProposalDetails::builder`].
+pub struct ProposalDetailsBuilder {
+	details: ProposalDetails,
+}
+
+impl ProposalDetailsBuilder {
+	/// When to enact the proposal. Defaults to `After(10)`.
+	pub fn dispatch(mut self, dispatch: DispatchTimeWrapper) -> Self {
+		self.details.dispatch = dispatch;
+		self
+	}
+
+	/// How to present the calls. Defaults to `AppsUiLink`.
+	pub fn output(mut self, output: Output) -> Self {
+		self.details.output = output;
+		self
+	}
+
+	/// Calls longer than this many bytes are only shown by their hash. Defaults to 1,000.
+	pub fn output_len_limit(mut self, output_len_limit: u32) -> Self {
+		self.details.output_len_limit = output_len_limit;
+		self
+	}
+
+	/// Whether to also give a `force_batch` of all the calls on each network. Defaults to `true`.
+	pub fn print_batch(mut self, print_batch: bool) -> Self {
+		self.details.print_batch = print_batch;
+		self
+	}
+
+	/// Whether to use light client endpoints in PAPI links. Defaults to `false`.
+	pub fn use_light_client(mut self, use_light_client: bool) -> Self {
+		self.details.use_light_client = use_light_client;
+		self
+	}
+
+	/// Whether to use the Polkadot Fellowship (on Collectives) to whitelist a Kusama proposal.
+	/// Only supported on the Kusama WhitelistedCaller track. Defaults to `false`.
+	pub fn fellowship_on_polkadot(mut self, fellowship_on_polkadot: bool) -> Self {
+		self.details.fellowship_on_polkadot = fellowship_on_polkadot;
+		self
+	}
+
+	/// Encode calls with this metadata instead of the bundled metadata. Defaults to none.
+	pub fn metadata(mut self, metadata: MetadataOverrides) -> Self {
+		self.details.metadata = metadata;
+		self
+	}
+
+	/// Also place the decision deposit of each referendum, which will get the given `indices`.
+	/// Each batch is then a `batch_all`. Defaults to not placing them.
+	pub fn decision_deposit(mut self, indices: ReferendumIndices) -> Self {
+		self.details.decision_deposit = Some(indices);
+		self
+	}
+
+	/// Wrap each call, and each batch, for a multisig or proxy to submit. Defaults to submitting
+	/// them as they are.
+	pub fn wrapper(mut self, wrapper: Wrapper) -> Self {
+		self.details.wrapper = wrapper;
+		self
+	}
+
+	/// Check that the preferences are consistent and return the `ProposalDetails`.
+	pub fn build(self) -> Result<ProposalDetails, Error> {
+		let is_kusama_whitelisted = matches!(
+			self.details.track,
+			NetworkTrack::Kusama(KusamaAssetHubOpenGovOrigin::WhitelistedCaller)
+		);
+		if self.details.fellowship_on_polkadot && !is_kusama_whitelisted {
+			return Err(Error::InvalidInput(String::from("`--fellowship polkadot` is only supported with `--network kusama --track whitelistedcaller`.")));
+		}
+		// Each network's timepoint is of its one batch. Without batches, there is no telling which
+		// of the calls on a network it belongs to.
+		let has_timepoints =
+			self.details.wrapper.multisig.as_ref().is_some_and(|m| !m.timepoints.is_empty());
+		if has_timepoints && !self.details.print_batch {
+			return Err(Error::InvalidInput(String::from(
+				"`--timepoint` needs the batch for each network, so cannot be used with `--no-batch`.",
+			)));
+		}
+		// A whitelisted proposal is decoded to be dispatched by `Whitelist`. Catch bad call data now
+		// rather than when generating the calls.
+		let whitelisted_on = match &self.details.track {
+			NetworkTrack::Kusama(KusamaAssetHubOpenGovOrigin::WhitelistedCaller) =>
+				Some(Network::KusamaAssetHub),
+			NetworkTrack::Polkadot(PolkadotAssetHubOpenGovOrigin::WhitelistedCaller) =>
+				Some(Network::PolkadotAssetHub),
+			_ => None,
+		};
+		if let Some(asset_hub) = whitelisted_on {
+			let proposal = crate::get_proposal_bytes(self.details.proposal.clone())?;
+			let proposal =
+				self.details.metadata.to_bundled(&CallInfo::from_bytes(&proposal, asset_hub))?;
+			crate::decode::decode_runtime_call(&proposal.encoded, &proposal.network)
+				.map_err(|source| Error::Decode { network: proposal.network.clone(), source })?;
+		}
+		Ok(self.details)
+	}
+}
+
+impl UpgradeDetails {
+	/// Start building the details for upgrading `relay` (Polkadot or Kusama) and its system
+	/// parachains. Runtimes are downloaded to, or read from, `directory`.
+	pub fn builder(relay: Network, directory: impl Into<String>) -> UpgradeDetailsBuilder {
+		UpgradeDetailsBuilder {
+			relay,
+			networks: Vec::new(),
+			directory: directory.into(),
+			output_file: None,
+			additional: None,
+			no_runtime_checks: false,
+			wasm_paths: Vec::new(),
+			metadata: MetadataOverrides::default(),
+		}
+	}
+}
+
+/// Builds an [`UpgradeDetails`]. Create one with [`UpgradeDetails::builder`].
+pub struct UpgradeDetailsBuilder {
+	relay: Network,
+	networks: Vec<VersionedNetwork>,
+	directory: String,
+	output_file: Option<String>,
+	additional: Option<CallInfo>,
+	no_runtime_checks: bool,
+	wasm_paths: Vec<(Network, String)>,
+	metadata: MetadataOverrides,
+}
+
+impl UpgradeDetailsBuilder {
+	/// Upgrade `network` to the runtime in the Fellowship release `version`, e.g. "1.2.3". Runtimes
+	/// are batched in the order they are added.
+	pub fn runtime(mut self, network: Network, version: impl Into<String>) -> Self {
+		let version = version.into();
+		let version = String::from(version.trim_start_matches('v'));
+		self.networks.push(VersionedNetwork { network, version });
+		self
+	}
+
+	/// The name of the file in `directory` to which to write the call data. Defaults to
+	/// `<relay>-<version>.call`.
+	pub fn output_file(mut self, output_file: impl Into<String>) -> Self {
+		self.output_file = Some(output_file.into());
+		self
+	}
+
+	/// An additional call to execute on Asset Hub in the same batch as the upgrade.
+	pub fn additional(mut self, additional: CallInfo) -> Self {
+		self.additional = Some(additional);
+		self
+	}
+
+	/// Skip sanity checks on the downloaded runtime blobs. Defaults to `false`.
+	pub fn no_runtime_checks(mut self, no_runtime_checks: bool) -> Self {
+		self.no_runtime_checks = no_runtime_checks;
+		self
+	}
+
+	/// Authorize the Wasm file at `path` for `network` instead of the release's runtime. The
+	/// `network` must also be added with `runtime`.
+	pub fn wasm(mut self, network: Network, path: impl Into<String>) -> Self {
+		self.wasm_paths.push((network, path.into()));
+		self
+	}
+
+	/// Encode calls with this metadata instead of the bundled metadata. Defaults to none.
+	pub fn metadata(mut self, metadata: MetadataOverrides) -> Self {
+		self.metadata = metadata;
+		self
+	}
+
+	/// Check that the upgrade is well formed and return the `UpgradeDetails`.
+	pub fn build(self) -> Result<UpgradeDetails, Error> {
+		let (relay_name, asset_hub) = match self.relay {
+			Network::Polkadot => ("polkadot", Network::PolkadotAssetHub),
+			Network::Kusama => ("kusama", Network::KusamaAssetHub),
+			_ =>
+				return Err(Error::UnsupportedNetwork(String::from(
+					"`network` must be `polkadot` or `kusama`.",
+				))),
+		};
+
+		for chain in &self.networks {
+			if chain.network.relay() != self.relay {
+				return Err(Error::InvalidInput(format!(
+					"{:?} is not a system chain of {:?}.",
+					chain.network, self.relay
+				)));
+			}
+			if chain.version.matches('.').count() != 2 {
+				return Err(Error::InvalidInput(format!(
+					"`{}` is not a semver version. Versions should be in the form `1.2.3`.",
+					chain.version
+				)));
+			}
+		}
+
+		for (i, (network, _)) in self.wasm_paths.iter().enumerate() {
+			if !self.networks.iter().any(|chain| chain.network == *network) {
+				return Err(Error::InvalidInput(format!(
+					"A Wasm file was given for {network:?}, which is not being upgraded. Specify a \
+					version for it."
+				)));
+			}
+			if self.wasm_paths[..i].iter().any(|(other, _)| other == network) {
+				return Err(Error::InvalidInput(format!(
+					"More than one Wasm file was given for {network:?}."
+				)));
+			}
+		}
+
+		if let Some(additional) = &self.additional {
+			// This isn't as intuitive post-ahm, as these are AH calls.
+			if additional.network != asset_hub {
+				return Err(Error::InvalidInput(format!(
+					"The additional call must be a {asset_hub:?} call."
+				)));
+			}
+			// Catch bad call data now rather than when building the batch.
+			let additional = self.metadata.to_bundled(additional)?;
+			crate::decode::decode_runtime_call(&additional.encoded, &additional.network)
+				.map_err(|source| Error::Decode { network: asset_hub.clone(), source })?;
+		}
+
+		if self.networks.is_empty() && self.additional.is_none() {
+			return Err(Error::InvalidInput(String::from(
+				"Nothing to upgrade. Specify at least one runtime version.",
+			)));
+		}
+
+		let mut directory = self.directory;
+		if !directory.ends_with('/') {
+			directory.push('/');
+		}
+		let output_file = match self.output_file {
+			Some(output_file) => format!("{directory}{output_file}"),
+			None => {
+				let version = self.networks.first().map_or("no-version", |n| n.version.as_str());
+				format!("{directory}{relay_name}-{version}.call")
+			},
+		};
+
+		Ok(UpgradeDetails {
+			relay: self.relay,
+			networks: self.networks,
+			directory,
+			output_file,
+			additional: self.additional,
+			no_runtime_checks: self.no_runtime_checks,
+			wasm_paths: self.wasm_paths,
+			metadata: self.metadata,
+		})
+	}
+}
+
+/// A network and the version to which it will upgrade.
 #[derive(Debug, PartialEq)]
-pub(super) struct VersionedNetwork {
-	// A network identifier.
-	pub(super) network: Network,
-	// A runtime version number (i.e. "9430", not "0.9.43").
-	pub(super) version: String,
+pub struct VersionedNetwork {
+	/// A network identifier.
+	pub network: Network,
+	/// A runtime version number (i.e. "9430", not "0.9.43").
+	pub version: String,
 }
 
-// The network and OpenGov track this proposal should be voted on.
-pub(super) enum NetworkTrack {
+/// The network and OpenGov track this proposal should be voted on.
+pub enum NetworkTrack {
 	KusamaRoot,
 	Kusama(KusamaAssetHubOpenGovOrigin),
 	PolkadotRoot,
 	Polkadot(PolkadotAssetHubOpenGovOrigin),
 }
 
-// A runtime call wrapped in the network it should execute on.
-pub(super) enum NetworkRuntimeCall {
+impl NetworkTrack {
+	/// The Relay Chain whose OpenGov this track is part of.
+	pub fn network(&self) -> Network {
+		match &self {
+			NetworkTrack::KusamaRoot | NetworkTrack::Kusama(_) => Network::Kusama,
+			NetworkTrack::PolkadotRoot | NetworkTrack::Polkadot(_) => Network::Polkadot,
+		}
+	}
+
+	/// The name of the track's origin, e.g. `Root` or `SmallSpender`.
+	pub fn name(&self) -> String {
+		match &self {
+			NetworkTrack::KusamaRoot | NetworkTrack::PolkadotRoot => String::from("Root"),
+			NetworkTrack::Kusama(origin) => format!("{origin:?}"),
+			NetworkTrack::Polkadot(origin) => format!("{origin:?}"),
+		}
+	}
+}
+
+/// A runtime call wrapped in the network it should execute on.
+pub enum NetworkRuntimeCall {
 	Kusama(KusamaRuntimeCall),
 	KusamaAssetHub(KusamaAssetHubRuntimeCall),
 	KusamaBridgeHub(KusamaBridgeHubRuntimeCall),
@@ -189,319 +664,225 @@ pub(super) enum NetworkRuntimeCall {
 	PolkadotCoretime(PolkadotCoretimeRuntimeCall),
 }
 
-// How the user would like to see the output of the program.
-pub(super) enum Output {
-	// Print just the call data (e.g. 0x1234).
+impl NetworkRuntimeCall {
+	/// The network on which this call executes.
+	pub fn network(&self) -> Network {
+		match &self {
+			NetworkRuntimeCall::Kusama(_) => Network::Kusama,
+			NetworkRuntimeCall::KusamaAssetHub(_) => Network::KusamaAssetHub,
+			NetworkRuntimeCall::KusamaBridgeHub(_) => Network::KusamaBridgeHub,
+			NetworkRuntimeCall::KusamaPeople(_) => Network::KusamaPeople,
+			NetworkRuntimeCall::KusamaCoretime(_) => Network::KusamaCoretime,
+			NetworkRuntimeCall::KusamaEncointer(_) => Network::KusamaEncointer,
+			NetworkRuntimeCall::Polkadot(_) => Network::Polkadot,
+			NetworkRuntimeCall::PolkadotAssetHub(_) => Network::PolkadotAssetHub,
+			NetworkRuntimeCall::PolkadotCollectives(_) => Network::PolkadotCollectives,
+			NetworkRuntimeCall::PolkadotBridgeHub(_) => Network::PolkadotBridgeHub,
+			NetworkRuntimeCall::PolkadotPeople(_) => Network::PolkadotPeople,
+			NetworkRuntimeCall::PolkadotCoretime(_) => Network::PolkadotCoretime,
+		}
+	}
+
+	/// The SCALE encoding of the inner `RuntimeCall`.
+	pub fn encoded(&self) -> Vec<u8> {
+		match &self {
+			NetworkRuntimeCall::Kusama(cc) => cc.encode(),
+			NetworkRuntimeCall::KusamaAssetHub(cc) => cc.encode(),
+			NetworkRuntimeCall::KusamaBridgeHub(cc) => cc.encode(),
+			NetworkRuntimeCall::KusamaPeople(cc) => cc.encode(),
+			NetworkRuntimeCall::KusamaCoretime(cc) => cc.encode(),
+			NetworkRuntimeCall::KusamaEncointer(cc) => cc.encode(),
+			NetworkRuntimeCall::Polkadot(cc) => cc.encode(),
+			NetworkRuntimeCall::PolkadotAssetHub(cc) => cc.encode(),
+			NetworkRuntimeCall::PolkadotCollectives(cc) => cc.encode(),
+			NetworkRuntimeCall::PolkadotBridgeHub(cc) => cc.encode(),
+			NetworkRuntimeCall::PolkadotPeople(cc) => cc.encode(),
+			NetworkRuntimeCall::PolkadotCoretime(cc) => cc.encode(),
+		}
+	}
+}
+
+/// How the user would like to see the output of the program.
+pub enum Output {
+	/// Print just the call data (e.g. 0x1234).
 	CallData,
-	// Print a clickable link to view the decoded call on Polkadot JS Apps UI.
+	/// Print a clickable link to view the decoded call on Polkadot JS Apps UI.
 	AppsUiLink,
+	/// Print a single JSON document describing every call, for use in automation.
+	Json,
 }
 
-// Local concrete type to use in each runtime's `DispatchTime`
-pub(super) enum DispatchTimeWrapper {
+/// Local concrete type to use in each runtime's `DispatchTime`
+pub enum DispatchTimeWrapper {
 	At(u32),
 	After(u32),
 }
 
-// A call or a hash. Used for printing (or rather, to avoid printing large calls).
-// The Hash variant is only used when calls exceed the output length limit, which is rare.
+/// A call or a hash. Used for printing (or rather, to avoid printing large calls).
+/// The Hash variant is only used when calls exceed the output length limit, which is rare.
 #[allow(clippy::large_enum_variant)]
-pub(super) enum CallOrHash {
+pub enum CallOrHash {
 	Call(NetworkRuntimeCall),
 	Hash([u8; 32]),
 }
 
-// All the info associated with a call in the forms you may need it in.
+/// All the info associated with a call in the forms you may need it in.
 #[derive(Clone)]
-pub(super) struct CallInfo {
-	pub(super) network: Network,
-	pub(super) encoded: Vec<u8>,
-	pub(super) hash: [u8; 32],
-	pub(super) length: u32,
+pub struct CallInfo {
+	pub network: Network,
+	pub encoded: Vec<u8>,
+	pub hash: [u8; 32],
+	pub length: u32,
 }
 
 impl CallInfo {
-	// Construct `Self` from a `NetworkRuntimeCall`.
-	pub(super) fn from_runtime_call(call: NetworkRuntimeCall) -> Self {
-		let (network, encoded) = match &call {
-			NetworkRuntimeCall::Kusama(cc) => (Network::Kusama, cc.encode()),
-			NetworkRuntimeCall::KusamaAssetHub(cc) => (Network::KusamaAssetHub, cc.encode()),
-			NetworkRuntimeCall::KusamaBridgeHub(cc) => (Network::KusamaBridgeHub, cc.encode()),
-			NetworkRuntimeCall::KusamaPeople(cc) => (Network::KusamaPeople, cc.encode()),
-			NetworkRuntimeCall::KusamaCoretime(cc) => (Network::KusamaCoretime, cc.encode()),
-			NetworkRuntimeCall::KusamaEncointer(cc) => (Network::KusamaEncointer, cc.encode()),
-			NetworkRuntimeCall::Polkadot(cc) => (Network::Polkadot, cc.encode()),
-			NetworkRuntimeCall::PolkadotAssetHub(cc) => (Network::PolkadotAssetHub, cc.encode()),
-			NetworkRuntimeCall::PolkadotCollectives(cc) =>
-				(Network::PolkadotCollectives, cc.encode()),
-			NetworkRuntimeCall::PolkadotBridgeHub(cc) => (Network::PolkadotBridgeHub, cc.encode()),
-			NetworkRuntimeCall::PolkadotPeople(cc) => (Network::PolkadotPeople, cc.encode()),
-			NetworkRuntimeCall::PolkadotCoretime(cc) => (Network::PolkadotCoretime, cc.encode()),
-		};
+	/// Construct `Self` from a `NetworkRuntimeCall`.
+	pub fn from_runtime_call(call: NetworkRuntimeCall) -> Self {
+		let (network, encoded) = (call.network(), call.encoded());
 		let hash = blake2_256(&encoded);
 		let length: u32 = (encoded.len()).try_into().unwrap();
 		Self { network, encoded: encoded.to_vec(), hash, length }
 	}
 
-	// Construct `Self` for some `network` given some `encoded` bytes.
-	pub(super) fn from_bytes(encoded: &[u8], network: Network) -> Self {
+	/// Construct `Self` for some `network` given some `encoded` bytes.
+	pub fn from_bytes(encoded: &[u8], network: Network) -> Self {
 		let hash = blake2_256(encoded);
 		let length = (encoded.len()).try_into().unwrap();
 		Self { network, encoded: encoded.to_vec(), hash, length }
 	}
 
-	// Strip the outer enum and return a Kusama Relay `RuntimeCall`.
-	pub(super) fn get_kusama_call(&self) -> Result<KusamaRuntimeCall, &'static str> {
-		match &self.network {
-			Network::Kusama => {
-				let bytes = &self.encoded;
-				Ok(<KusamaRuntimeCall as parity_scale_codec::Decode>::decode(&mut &bytes[..])
-					.unwrap())
-			},
-			_ => Err("not a kusama call"),
+	// Decode `Self` as a `RuntimeCall` of `network`, which must be the network it is for.
+	fn decode_call<T: parity_scale_codec::Decode>(&self, network: Network) -> Result<T, Error> {
+		if self.network != network {
+			return Err(Error::UnsupportedNetwork(format!(
+				"Expected a {network:?} call, but this is a {:?} call.",
+				self.network
+			)));
 		}
+		T::decode(&mut &self.encoded[..]).map_err(|source| Error::Decode { network, source })
+	}
+
+	/// Strip the outer enum and return a Kusama Relay `RuntimeCall`.
+	pub fn get_kusama_call(&self) -> Result<KusamaRuntimeCall, Error> {
+		self.decode_call(Network::Kusama)
 	}
 
-	// Strip the outer enum and return a Kusama Asset Hub `RuntimeCall`.
+	/// Strip the outer enum and return a Kusama Asset Hub `RuntimeCall`.
 	#[allow(dead_code)]
-	pub(super) fn get_kusama_asset_hub_call(
-		&self,
-	) -> Result<KusamaAssetHubRuntimeCall, &'static str> {
-		match &self.network {
-			Network::KusamaAssetHub => {
-				let bytes = &self.encoded;
-				Ok(<KusamaAssetHubRuntimeCall as parity_scale_codec::Decode>::decode(
-					&mut &bytes[..],
-				)
-				.unwrap())
-			},
-			_ => Err("not a kusama asset hub call"),
-		}
+	pub fn get_kusama_asset_hub_call(&self) -> Result<KusamaAssetHubRuntimeCall, Error> {
+		self.decode_call(Network::KusamaAssetHub)
 	}
 
-	// Strip the outer enum and return a Kusama Bridge Hub `RuntimeCall`.
+	/// Strip the outer enum and return a Kusama Bridge Hub `RuntimeCall`.
 	#[allow(dead_code)]
-	pub(super) fn get_kusama_bridge_hub_call(
-		&self,
-	) -> Result<KusamaBridgeHubRuntimeCall, &'static str> {
-		match &self.network {
-			Network::KusamaBridgeHub => {
-				let bytes = &self.encoded;
-				Ok(<KusamaBridgeHubRuntimeCall as parity_scale_codec::Decode>::decode(
-					&mut &bytes[..],
-				)
-				.unwrap())
-			},
-			_ => Err("not a kusama bridge hub call"),
-		}
+	pub fn get_kusama_bridge_hub_call(&self) -> Result<KusamaBridgeHubRuntimeCall, Error> {
+		self.decode_call(Network::KusamaBridgeHub)
 	}
 
-	// Strip the outer enum and return a Kusama Encointer `RuntimeCall`.
+	/// Strip the outer enum and return a Kusama Encointer `RuntimeCall`.
 	#[allow(dead_code)]
-	pub(super) fn get_kusama_encointer_call(
-		&self,
-	) -> Result<KusamaEncointerRuntimeCall, &'static str> {
-		match &self.network {
-			Network::KusamaEncointer => {
-				let bytes = &self.encoded;
-				Ok(<KusamaEncointerRuntimeCall as parity_scale_codec::Decode>::decode(
-					&mut &bytes[..],
-				)
-				.unwrap())
-			},
-			_ => Err("not a kusama encointer call"),
-		}
+	pub fn get_kusama_encointer_call(&self) -> Result<KusamaEncointerRuntimeCall, Error> {
+		self.decode_call(Network::KusamaEncointer)
 	}
 
-	// Strip the outer enum and return a Kusama People `RuntimeCall`.
+	/// Strip the outer enum and return a Kusama People `RuntimeCall`.
 	#[allow(dead_code)]
-	pub(super) fn get_kusama_people_call(&self) -> Result<KusamaPeopleRuntimeCall, &'static str> {
-		match &self.network {
-			Network::KusamaPeople => {
-				let bytes = &self.encoded;
-				Ok(<KusamaPeopleRuntimeCall as parity_scale_codec::Decode>::decode(&mut &bytes[..])
-					.unwrap())
-			},
-			_ => Err("not a kusama people call"),
-		}
+	pub fn get_kusama_people_call(&self) -> Result<KusamaPeopleRuntimeCall, Error> {
+		self.decode_call(Network::KusamaPeople)
 	}
 
-	// Strip the outer enum and return a Kusama Coretime `RuntimeCall`.
+	/// Strip the outer enum and return a Kusama Coretime `RuntimeCall`.
 	#[allow(dead_code)]
-	pub(super) fn get_kusama_coretime_call(
-		&self,
-	) -> Result<KusamaCoretimeRuntimeCall, &'static str> {
-		match &self.network {
-			Network::KusamaCoretime => {
-				let bytes = &self.encoded;
-				Ok(<KusamaCoretimeRuntimeCall as parity_scale_codec::Decode>::decode(
-					&mut &bytes[..],
-				)
-				.unwrap())
-			},
-			_ => Err("not a kusama coretime call"),
-		}
+	pub fn get_kusama_coretime_call(&self) -> Result<KusamaCoretimeRuntimeCall, Error> {
+		self.decode_call(Network::KusamaCoretime)
 	}
 
-	// Strip the outer enum and return a Polkadot Relay `RuntimeCall`.
-	pub(super) fn get_polkadot_call(&self) -> Result<PolkadotRuntimeCall, &'static str> {
-		match &self.network {
-			Network::Polkadot => {
-				let bytes = &self.encoded;
-				Ok(<PolkadotRuntimeCall as parity_scale_codec::Decode>::decode(&mut &bytes[..])
-					.unwrap())
-			},
-			_ => Err("not a polkadot call"),
-		}
+	/// Strip the outer enum and return a Polkadot Relay `RuntimeCall`.
+	#[allow(dead_code)]
+	pub fn get_polkadot_call(&self) -> Result<PolkadotRuntimeCall, Error> {
+		self.decode_call(Network::Polkadot)
 	}
 
-	// Strip the outer enum and return a Polkadot Asset Hub `RuntimeCall`.
+	/// Strip the outer enum and return a Polkadot Asset Hub `RuntimeCall`.
 	#[allow(dead_code)]
-	pub(super) fn get_polkadot_asset_hub_call(
-		&self,
-	) -> Result<PolkadotAssetHubRuntimeCall, &'static str> {
-		match &self.network {
-			Network::PolkadotAssetHub => {
-				let bytes = &self.encoded;
-				Ok(<PolkadotAssetHubRuntimeCall as parity_scale_codec::Decode>::decode(
-					&mut &bytes[..],
-				)
-				.unwrap())
-			},
-			_ => Err("not a polkadot asset hub call"),
-		}
+	pub fn get_polkadot_asset_hub_call(&self) -> Result<PolkadotAssetHubRuntimeCall, Error> {
+		self.decode_call(Network::PolkadotAssetHub)
 	}
 
-	// Strip the outer enum and return a Polkadot Collectives `RuntimeCall`.
-	pub(super) fn get_polkadot_collectives_call(
-		&self,
-	) -> Result<CollectivesRuntimeCall, &'static str> {
-		match &self.network {
-			Network::PolkadotCollectives => {
-				let bytes = &self.encoded;
-				Ok(<CollectivesRuntimeCall as parity_scale_codec::Decode>::decode(&mut &bytes[..])
-					.unwrap())
-			},
-			_ => Err("not a polkadot collectives call"),
-		}
+	/// Strip the outer enum and return a Polkadot Collectives `RuntimeCall`.
+	pub fn get_polkadot_collectives_call(&self) -> Result<CollectivesRuntimeCall, Error> {
+		self.decode_call(Network::PolkadotCollectives)
 	}
 
-	// Strip the outer enum and return a Polkadot Bridge Hub `RuntimeCall`.
+	/// Strip the outer enum and return a Polkadot Bridge Hub `RuntimeCall`.
 	#[allow(dead_code)]
-	pub(super) fn get_polkadot_bridge_hub_call(
-		&self,
-	) -> Result<PolkadotBridgeHubRuntimeCall, &'static str> {
-		match &self.network {
-			Network::PolkadotBridgeHub => {
-				let bytes = &self.encoded;
-				Ok(<PolkadotBridgeHubRuntimeCall as parity_scale_codec::Decode>::decode(
-					&mut &bytes[..],
-				)
-				.unwrap())
-			},
-			_ => Err("not a polkadot bridge hub call"),
-		}
+	pub fn get_polkadot_bridge_hub_call(&self) -> Result<PolkadotBridgeHubRuntimeCall, Error> {
+		self.decode_call(Network::PolkadotBridgeHub)
 	}
 
-	// Strip the outer enum and return a Polkadot People `RuntimeCall`.
+	/// Strip the outer enum and return a Polkadot People `RuntimeCall`.
 	#[allow(dead_code)]
-	pub(super) fn get_polkadot_people_call(
-		&self,
-	) -> Result<PolkadotPeopleRuntimeCall, &'static str> {
-		match &self.network {
-			Network::PolkadotPeople => {
-				let bytes = &self.encoded;
-				Ok(<PolkadotPeopleRuntimeCall as parity_scale_codec::Decode>::decode(
-					&mut &bytes[..],
-				)
-				.unwrap())
-			},
-			_ => Err("not a polkadot people call"),
-		}
+	pub fn get_polkadot_people_call(&self) -> Result<PolkadotPeopleRuntimeCall, Error> {
+		self.decode_call(Network::PolkadotPeople)
 	}
 
-	// Strip the outer enum and return a Polkadot Coretime `RuntimeCall`.
+	/// Strip the outer enum and return a Polkadot Coretime `RuntimeCall`.
 	#[allow(dead_code)]
-	pub(super) fn get_polkadot_coretime_call(
-		&self,
-	) -> Result<PolkadotCoretimeRuntimeCall, &'static str> {
-		match &self.network {
-			Network::PolkadotCoretime => {
-				let bytes = &self.encoded;
-				Ok(<PolkadotCoretimeRuntimeCall as parity_scale_codec::Decode>::decode(
-					&mut &bytes[..],
-				)
-				.unwrap())
-			},
-			_ => Err("not a polkadot coretime call"),
-		}
+	pub fn get_polkadot_coretime_call(&self) -> Result<PolkadotCoretimeRuntimeCall, Error> {
+		self.decode_call(Network::PolkadotCoretime)
 	}
 
-	// Take `Self` and a length limit as input. If the call length exceeds the limit, just return
-	// its hash. Call length is recomputed and will be 2 bytes longer than the actual preimage
-	// length. This is because the call is `preimage.note_preimage(call)`, so the outer pallet/call
-	// indices have a length of 2 bytes.
-	pub(super) fn create_print_output(&self, length_limit: u32) -> (CallOrHash, u32) {
+	/// Take `Self` and a length limit as input. If the call length exceeds the limit, just return
+	/// its hash. Call length is recomputed and will be 2 bytes longer than the actual preimage
+	/// length. This is because the call is `preimage.note_preimage(call)`, so the outer pallet/call
+	/// indices have a length of 2 bytes. `self` has its on-chain encoding, which is mapped back to
+	/// the static types with `metadata`.
+	pub fn create_print_output(
+		&self,
+		length_limit: u32,
+		metadata: &MetadataOverrides,
+	) -> Result<(CallOrHash, u32), Error> {
 		let print_output = if self.length > length_limit {
 			CallOrHash::Hash(self.hash)
 		} else {
-			match &self.network {
-				Network::Kusama => {
-					let kusama_call = self.get_kusama_call().expect("kusama");
-					CallOrHash::Call(NetworkRuntimeCall::Kusama(kusama_call))
-				},
-				Network::KusamaAssetHub => {
-					let kusama_asset_hub_call =
-						self.get_kusama_asset_hub_call().expect("kusama asset hub");
-					CallOrHash::Call(NetworkRuntimeCall::KusamaAssetHub(kusama_asset_hub_call))
-				},
-				Network::Polkadot => {
-					let polkadot_call = self.get_polkadot_call().expect("polkadot");
-					CallOrHash::Call(NetworkRuntimeCall::Polkadot(polkadot_call))
-				},
-				Network::PolkadotAssetHub => {
-					let polkadot_asset_hub_call =
-						self.get_polkadot_asset_hub_call().expect("polkadot asset hub");
-					CallOrHash::Call(NetworkRuntimeCall::PolkadotAssetHub(polkadot_asset_hub_call))
-				},
-				Network::PolkadotCollectives => {
-					let collectives_call =
-						self.get_polkadot_collectives_call().expect("collectives");
-					CallOrHash::Call(NetworkRuntimeCall::PolkadotCollectives(collectives_call))
-				},
-				_ => panic!("to do"),
-			}
+			let bundled = metadata.to_bundled(self)?;
+			let call = crate::decode::decode_runtime_call(&bundled.encoded, &self.network)
+				.map_err(|source| Error::Decode { network: self.network.clone(), source })?;
+			CallOrHash::Call(call)
 		};
-		(print_output, self.length)
+		Ok((print_output, self.length))
 	}
 }
 
-// The set of calls that some user will need to sign and submit to initiate a referendum.
-pub(super) struct PossibleCallsToSubmit {
-	// `Some` if using the Fellowship to Whitelist a call. The second value is the length of the
-	// call, which may be relevant to the print output.
-	//
-	// ```
-	// preimage.note(whitelist.whitelist_call(hash(proposal)));
-	// ```
-	pub(super) preimage_for_whitelist_call: Option<(CallOrHash, u32)>,
-	// The preimage for the public referendum. Should always be `Some`. When not using the
-	// Whitelist, this will just be the proposal itself. When using the Whitelist, it will be the
-	// proposal nested in a call to dispatch via Whitelist. The second value is the length of the
-	// call, which may be relevant to the print output.
-	//
-	// ```
-	// // Without Fellowship
-	// preimage.note(proposal);
-	//
-	// // With Fellowship
-	// preimage.note(whitelist.dispatch_whitelisted_call_with_preimage(proposal));
-	// ```
-	pub(super) preimage_for_public_referendum: Option<(CallOrHash, u32)>,
-	// The actual submission of the Fellowship referendum to Whitelist a call. `None` when not using
-	// Whitelist.
-	pub(super) fellowship_referendum_submission: Option<NetworkRuntimeCall>,
-	// The actual submission of the public referendum. The `proposal` is the proposal itself when
-	// not using the Whitelist, or the dispatch call with nested proposal when using the Whitelist.
-	pub(super) public_referendum_submission: Option<NetworkRuntimeCall>,
+/// The set of calls that some user will need to sign and submit to initiate a referendum.
+pub struct PossibleCallsToSubmit {
+	/// `Some` if using the Fellowship to Whitelist a call. The second value is the length of the
+	/// call, which may be relevant to the print output.
+	///
+	/// ```text
+	/// preimage.note(whitelist.whitelist_call(hash(proposal)));
+	/// ```
+	pub preimage_for_whitelist_call: Option<(CallOrHash, u32)>,
+	/// The preimage for the public referendum. Should always be `Some`. When not using the
+	/// Whitelist, this will just be the proposal itself. When using the Whitelist, it will be the
+	/// proposal nested in a call to dispatch via Whitelist. The second value is the length of the
+	/// call, which may be relevant to the print output.
+	///
+	/// ```text
+	/// // Without Fellowship
+	/// preimage.note(proposal);
+	///
+	/// // With Fellowship
+	/// preimage.note(whitelist.dispatch_whitelisted_call_with_preimage(proposal));
+	/// ```
+	pub preimage_for_public_referendum: Option<(CallOrHash, u32)>,
+	/// The actual submission of the Fellowship referendum to Whitelist a call. `None` when not
+	/// using Whitelist.
+	pub fellowship_referendum_submission: Option<NetworkRuntimeCall>,
+	/// The actual submission of the public referendum. The `proposal` is the proposal itself when
+	/// not using the Whitelist, or the dispatch call with nested proposal when using the Whitelist.
+	pub public_referendum_submission: Option<NetworkRuntimeCall>,
+	/// The call that the Fellowship referendum enacts, which sends the whitelist call over XCM, in
+	/// its on-chain encoding. `None` when not using Whitelist.
+	pub whitelist_over_xcm: Option<CallInfo>,
 }
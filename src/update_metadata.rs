@@ -0,0 +1,148 @@
+use crate::*;
+use clap::Parser as ClapParser;
+use metadata_diff::{diff_metadata, print_metadata_diff};
+use node::Node;
+use parity_scale_codec::Decode;
+use std::path::Path;
+use wasm::RuntimeVersion;
+
+/// Refresh one of the `metadata/*.scale` files from a runtime Wasm blob or a running node.
+#[derive(Debug, ClapParser)]
+pub struct UpdateMetadataArgs {
+	/// The network whose metadata to update, e.g. `kusama-asset-hub`. If not provided, it is
+	/// inferred from the `spec_name` in the new metadata.
+	#[clap(long = "network", short)]
+	network: Option<String>,
+
+	/// Read the metadata from this runtime Wasm blob (compressed or not), offline.
+	#[clap(long = "from-wasm", conflicts_with = "from_rpc", required_unless_present = "from_rpc")]
+	from_wasm: Option<String>,
+
+	/// Read the metadata from the node at this RPC endpoint, e.g. `ws://localhost:9944`.
+	#[clap(long = "from-rpc")]
+	from_rpc: Option<String>,
+
+	/// The directory to write the metadata file to.
+	#[clap(long = "out-dir", default_value = "metadata")]
+	out_dir: String,
+}
+
+/// The metadata versions that this tool can decode, newest first.
+pub const METADATA_VERSIONS: [u32; 3] = [16, 15, 14];
+
+/// The sub-command's "main" function.
+pub async fn update_metadata(prefs: UpdateMetadataArgs) -> Result<(), Error> {
+	let (encoded, source) = match (prefs.from_wasm, prefs.from_rpc) {
+		(Some(file), _) => (metadata_from_wasm(&file)?, file),
+		(None, Some(url)) => (metadata_from_rpc(&url).await?, url),
+		(None, None) =>
+			return Err(Error::InvalidInput(String::from(
+				"Pass either `--from-wasm` or `--from-rpc`.",
+			))),
+	};
+	let invalid = |reason: String| Error::InvalidRuntime { file: source.clone(), reason };
+	let new = wasm::decode_metadata(&encoded).map_err(invalid)?;
+	let new_version = wasm::metadata_runtime_version(&new);
+
+	let from_spec_name =
+		new_version.as_ref().and_then(|version| Network::from_spec_name(&version.spec_name).ok());
+	let network = match (prefs.network, from_spec_name) {
+		(Some(name), from_spec_name) => {
+			let network = Network::from_name(&name).map_err(|_| {
+				Error::UnsupportedNetwork(String::from(
+					"Networks should be in the form `kusama-asset-hub`.",
+				))
+			})?;
+			if let Some(found) = from_spec_name.filter(|found| *found != network) {
+				return Err(Error::InvalidInput(format!(
+					"The metadata from {source} is for {found:?}, not {network:?}."
+				)));
+			}
+			network
+		},
+		(None, Some(network)) => network,
+		(None, None) =>
+			return Err(Error::UnsupportedNetwork(format!(
+				"Could not tell which network the metadata from {source} is for. Pass `--network`."
+			))),
+	};
+
+	// Compare with the file being replaced, which may be newer than the bundled metadata if this
+	// was already run without rebuilding.
+	let path = Path::new(&prefs.out_dir).join(network.metadata_file_name());
+	let path = path.to_string_lossy().into_owned();
+	let old = if Path::new(&path).exists() {
+		metadata_diff::load_metadata(&path)?
+	} else {
+		wasm::decode_metadata(network.bundled_metadata()).map_err(|reason| {
+			Error::InvalidRuntime { file: format!("The bundled {network:?} metadata"), reason }
+		})?
+	};
+	let old_version = wasm::metadata_runtime_version(&old);
+
+	write_file(&path, &encoded)?;
+
+	println!("\nWrote the {network:?} metadata from {source} to {path}.\n");
+	let spec_version = |version: Option<RuntimeVersion>| match version {
+		Some(version) => format!("{}", version.spec_version),
+		None => String::from("unknown"),
+	};
+	println!("spec_version: {} -> {}", spec_version(old_version), spec_version(new_version));
+	let diff = diff_metadata(&old, &new);
+	if diff.is_empty() {
+		println!("No changes to pallet indices or to the calls of this tool's pallets.");
+	} else {
+		println!();
+		print_metadata_diff(&diff);
+	}
+	println!("Rebuild to regenerate the static types from the new metadata.");
+	Ok(())
+}
+
+// Execute the `Metadata_metadata_at_version` runtime API of the Wasm blob in `file`.
+fn metadata_from_wasm(file: &str) -> Result<Vec<u8>, Error> {
+	let invalid = |reason: String| Error::InvalidRuntime { file: String::from(file), reason };
+	let blob = read_file(file)?;
+	let code = wasm::decompress_runtime(&blob).map_err(invalid)?;
+	newest_wasm_metadata(&code).map_err(invalid)
+}
+
+/// The encoded metadata of the uncompressed runtime `code`, at the newest version that it offers
+/// and this tool can decode, from its `Metadata_metadata_at_version` runtime API.
+pub fn newest_wasm_metadata(code: &[u8]) -> Result<Vec<u8>, String> {
+	let versions = wasm::call_runtime(code, "Metadata_metadata_versions", &[])?;
+	let version = newest_supported_version(&versions)?;
+	let metadata = wasm::call_runtime(code, "Metadata_metadata_at_version", &version.encode())?;
+	opaque_metadata(&metadata)
+}
+
+// Call the `Metadata_metadata_at_version` runtime API of the node at `url`, at its best block.
+async fn metadata_from_rpc(url: &str) -> Result<Vec<u8>, Error> {
+	let rpc_error = |reason: String| Error::Rpc { url: String::from(url), reason };
+	let node = Node::connect(url).await?;
+
+	let versions = node.state_call("Metadata_metadata_versions", &[]).await?;
+	let version = newest_supported_version(&versions).map_err(rpc_error)?;
+	let metadata = node.state_call("Metadata_metadata_at_version", &version.encode()).await?;
+	opaque_metadata(&metadata).map_err(rpc_error)
+}
+
+/// The newest of `METADATA_VERSIONS` in `versions`, the encoded result of the
+/// `Metadata_metadata_versions` runtime API.
+pub fn newest_supported_version(versions: &[u8]) -> Result<u32, String> {
+	let versions = Vec::<u32>::decode(&mut &versions[..])
+		.map_err(|e| format!("returned metadata versions that do not decode: {e}"))?;
+	METADATA_VERSIONS.into_iter().find(|v| versions.contains(v)).ok_or_else(|| {
+		format!("only supports metadata versions {versions:?}, none of which this tool can decode")
+	})
+}
+
+/// The metadata in `result`, the encoded `Option<OpaqueMetadata>` returned by the
+/// `Metadata_metadata_at_version` runtime API.
+pub fn opaque_metadata(result: &[u8]) -> Result<Vec<u8>, String> {
+	match Option::<Vec<u8>>::decode(&mut &result[..]) {
+		Ok(Some(metadata)) => Ok(metadata),
+		Ok(None) => Err(String::from("did not return metadata at the requested version")),
+		Err(e) => Err(format!("returned metadata that does not decode: {e}")),
+	}
+}
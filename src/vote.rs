@@ -0,0 +1,208 @@
+use crate::*;
+use clap::Parser as ClapParser;
+use submit_referendum::{parse_output, print_output, JsonStep};
+
+/// Build the call to vote on a public referendum, with conviction or split between aye, nay, and
+/// abstain.
+#[derive(Debug, ClapParser)]
+pub struct VoteArgs {
+	/// Network whose referendum to vote on. `polkadot` or `kusama`.
+	#[clap(long = "network", short)]
+	network: String,
+
+	/// The index of the referendum.
+	#[clap(long = "ref", alias = "ref-index")]
+	ref_index: u32,
+
+	/// Vote for the referendum with `--balance`.
+	#[clap(long = "aye", group = "direction")]
+	aye: bool,
+
+	/// Vote against the referendum with `--balance`.
+	#[clap(long = "nay", group = "direction")]
+	nay: bool,
+
+	/// Split the vote between `--aye-balance` and `--nay-balance`, without conviction.
+	#[clap(long = "split", group = "direction", requires_all = ["aye_balance", "nay_balance"])]
+	split: bool,
+
+	/// Abstain with `--balance`, optionally splitting `--aye-balance` and `--nay-balance` too,
+	/// without conviction.
+	#[clap(long = "abstain", group = "direction")]
+	abstain: bool,
+
+	/// The balance to vote (or abstain) with, in whole tokens, e.g. `100` or `1,250.5`.
+	#[clap(long = "balance")]
+	balance: Option<String>,
+
+	/// The balance of a split vote to vote aye with, in whole tokens.
+	#[clap(long = "aye-balance")]
+	aye_balance: Option<String>,
+
+	/// The balance of a split vote to vote nay with, in whole tokens.
+	#[clap(long = "nay-balance")]
+	nay_balance: Option<String>,
+
+	/// The conviction of an aye or nay vote, from 0 (0.1x votes, no lock) to 6 (6x votes, locked
+	/// for 32 periods). Defaults to 0.
+	#[clap(long = "conviction", default_value_t = 0, conflicts_with_all = ["split", "abstain"])]
+	conviction: u8,
+
+	/// Form of output. `AppsUiLink`, `CallData`, or `Json`. Defaults to Apps UI.
+	#[clap(long = "output")]
+	output: Option<String>,
+
+	/// Use light client endpoints instead of RPC for PAPI links.
+	#[clap(long = "light-client")]
+	light_client: bool,
+}
+
+/// The highest conviction, `Locked6x`.
+pub const MAX_CONVICTION: u8 = 6;
+
+/// A vote on a public referendum, as in `pallet_conviction_voting::AccountVote`. Balances are in
+/// the smallest unit of the native token.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Vote {
+	/// Vote aye or nay with `balance`, locked with `conviction`, from 0 to `MAX_CONVICTION`.
+	Standard { aye: bool, balance: u128, conviction: u8 },
+	/// Vote with `aye` and `nay` balances, without conviction.
+	Split { aye: u128, nay: u128 },
+	/// Vote with `aye`, `nay`, and `abstain` balances, without conviction.
+	SplitAbstain { aye: u128, nay: u128, abstain: u128 },
+}
+
+/// The sub-command's "main" function.
+pub fn vote(prefs: VoteArgs) -> Result<(), Error> {
+	let network = match Network::from_name(&prefs.network) {
+		Ok(network @ (Network::Polkadot | Network::Kusama)) => network,
+		_ =>
+			return Err(Error::UnsupportedNetwork(String::from(
+				"`network` must be `polkadot` or `kusama`.",
+			))),
+	};
+	let (symbol, decimals) = network.token();
+	let amount = |amount: Option<String>, flag: &str| match amount {
+		Some(amount) => parse_amount(&amount, decimals),
+		None => Err(Error::InvalidInput(format!("Pass `{flag}` with the balance to vote."))),
+	};
+	let optional_amount = |amount: Option<String>| match amount {
+		Some(amount) => parse_amount(&amount, decimals),
+		None => Ok(0),
+	};
+
+	let vote = if prefs.split {
+		Vote::Split {
+			aye: amount(prefs.aye_balance, "--aye-balance")?,
+			nay: amount(prefs.nay_balance, "--nay-balance")?,
+		}
+	} else if prefs.abstain {
+		Vote::SplitAbstain {
+			aye: optional_amount(prefs.aye_balance)?,
+			nay: optional_amount(prefs.nay_balance)?,
+			abstain: amount(prefs.balance, "--balance")?,
+		}
+	} else if prefs.aye || prefs.nay {
+		Vote::Standard {
+			aye: prefs.aye,
+			balance: amount(prefs.balance, "--balance")?,
+			conviction: prefs.conviction,
+		}
+	} else {
+		return Err(Error::InvalidInput(String::from(
+			"Pass one of `--aye`, `--nay`, `--split`, or `--abstain`.",
+		)));
+	};
+
+	let output = parse_output(prefs.output)?;
+	let call_info = CallInfo::from_runtime_call(vote_call(&network, prefs.ref_index, &vote)?);
+	match output {
+		Output::Json => {
+			let step = JsonStep::from_call("vote", &call_info);
+			let document = serde_json::json!({ "steps": [step] });
+			println!("{}", serde_json::to_string_pretty(&document).expect("it serializes"));
+		},
+		_ => {
+			let tokens = |amount: u128| format!("{} {symbol}", format_amount(amount, decimals));
+			let description = match vote {
+				Vote::Standard { aye, balance, conviction } => format!(
+					"{} with {} at conviction {conviction}",
+					if aye { "Aye" } else { "Nay" },
+					tokens(balance)
+				),
+				Vote::Split { aye, nay } =>
+					format!("Split: {} aye, {} nay", tokens(aye), tokens(nay)),
+				Vote::SplitAbstain { aye, nay, abstain } => format!(
+					"Abstain: {} aye, {} nay, {} abstain",
+					tokens(aye),
+					tokens(nay),
+					tokens(abstain)
+				),
+			};
+			println!("\nVote on referendum {} on {network:?}. {description}:", prefs.ref_index);
+			print_output(&output, &call_info, prefs.light_client)?;
+		},
+	}
+	Ok(())
+}
+
+/// The `ConvictionVoting::vote` call to cast `vote` on the referendum `ref_index` of the Relay Chain
+/// `network`, whose OpenGov is on its Asset Hub.
+pub fn vote_call(
+	network: &Network,
+	ref_index: u32,
+	vote: &Vote,
+) -> Result<NetworkRuntimeCall, Error> {
+	// The conviction is in the low bits of `pallet_conviction_voting::Vote`, and aye is the high
+	// bit.
+	let standard_vote = |aye: bool, conviction: u8| {
+		if conviction > MAX_CONVICTION {
+			return Err(Error::InvalidInput(format!(
+				"Conviction must be between 0 and {MAX_CONVICTION}, not {conviction}."
+			)));
+		}
+		Ok(if aye { 0x80 | conviction } else { conviction })
+	};
+
+	match network {
+		Network::Polkadot => {
+			use polkadot_asset_hub::runtime_types::pallet_conviction_voting::{
+				pallet::Call as ConvictionVotingCall,
+				vote::{AccountVote, Vote as StandardVote},
+			};
+			let vote = match *vote {
+				Vote::Standard { aye, balance, conviction } => AccountVote::Standard {
+					vote: StandardVote(standard_vote(aye, conviction)?),
+					balance,
+				},
+				Vote::Split { aye, nay } => AccountVote::Split { aye, nay },
+				Vote::SplitAbstain { aye, nay, abstain } =>
+					AccountVote::SplitAbstain { aye, nay, abstain },
+			};
+			Ok(NetworkRuntimeCall::PolkadotAssetHub(PolkadotAssetHubRuntimeCall::ConvictionVoting(
+				ConvictionVotingCall::vote { poll_index: ref_index, vote },
+			)))
+		},
+		Network::Kusama => {
+			use kusama_asset_hub::runtime_types::pallet_conviction_voting::{
+				pallet::Call as ConvictionVotingCall,
+				vote::{AccountVote, Vote as StandardVote},
+			};
+			let vote = match *vote {
+				Vote::Standard { aye, balance, conviction } => AccountVote::Standard {
+					vote: StandardVote(standard_vote(aye, conviction)?),
+					balance,
+				},
+				Vote::Split { aye, nay } => AccountVote::Split { aye, nay },
+				Vote::SplitAbstain { aye, nay, abstain } =>
+					AccountVote::SplitAbstain { aye, nay, abstain },
+			};
+			Ok(NetworkRuntimeCall::KusamaAssetHub(KusamaAssetHubRuntimeCall::ConvictionVoting(
+				ConvictionVotingCall::vote { poll_index: ref_index, vote },
+			)))
+		},
+		_ => Err(Error::UnsupportedNetwork(String::from(
+			"`network` must be `polkadot` or `kusama`.",
+		))),
+	}
+}
@@ -0,0 +1,235 @@
+use parity_scale_codec::{Compact, Decode};
+use sc_executor::{HeapAllocStrategy, WasmExecutor};
+use sp_core::storage::StateVersion;
+use sp_core::traits::{CallContext, CodeExecutor, RuntimeCode, WrappedRuntimeCode};
+use sp_core::{Blake2Hasher, H256};
+use sp_maybe_compressed_blob::{decompress, CODE_BLOB_BOMB_LIMIT};
+use sp_state_machine::{
+	Backend, BasicExternalities, Ext, InMemoryBackend, OverlayedChanges, TestExternalities,
+};
+
+const WASM_MAGIC: [u8; 4] = [0x00, 0x61, 0x73, 0x6d];
+const CUSTOM_SECTION_ID: u8 = 0;
+/// The `snapshot_version` of the try-runtime state snapshots that this tool reads.
+pub const SNAPSHOT_VERSION: u16 = 4;
+
+/// A change that executing a runtime made to a storage key, as `(key, old value, new value)`. A
+/// value is `None` if the key is empty.
+pub type RawStorageChange = (Vec<u8>, Option<Vec<u8>>, Option<Vec<u8>>);
+
+/// The `RuntimeVersion` that a runtime embeds in its `runtime_version` custom section.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+pub struct RuntimeVersion {
+	pub spec_name: String,
+	pub impl_name: String,
+	pub authoring_version: u32,
+	pub spec_version: u32,
+	pub impl_version: u32,
+	pub transaction_version: u32,
+}
+
+/// Return the Wasm code in `blob`, decompressing it first if it is a zstd-compressed runtime.
+///
+/// Errors describe what is wrong with the blob, e.g. "could not be decompressed", so that callers
+/// can prefix them with a file name.
+pub fn decompress_runtime(blob: &[u8]) -> Result<Vec<u8>, String> {
+	decompress(blob, CODE_BLOB_BOMB_LIMIT)
+		.map(|code| code.into_owned())
+		.map_err(|e| format!("could not be decompressed: {e}"))
+}
+
+/// Return the payload of the first custom section called `name` in `wasm`, if there is one.
+pub fn custom_section<'a>(wasm: &'a [u8], name: &str) -> Result<Option<&'a [u8]>, String> {
+	let mut rest =
+		wasm.strip_prefix(&WASM_MAGIC[..]).ok_or_else(|| String::from("is not a Wasm binary"))?;
+	// Skip the 4-byte binary format version.
+	rest = rest.get(4..).ok_or_else(|| String::from("has a truncated Wasm header"))?;
+
+	while !rest.is_empty() {
+		let id = rest[0];
+		let (size, leb_len) = read_leb128(&rest[1..])?;
+		let start = 1 + leb_len;
+		let body = rest
+			.get(start..start + size)
+			.ok_or_else(|| String::from("has a truncated Wasm section"))?;
+		if id == CUSTOM_SECTION_ID {
+			let (name_len, leb_len) = read_leb128(body)?;
+			let section_name = body
+				.get(leb_len..leb_len + name_len)
+				.ok_or_else(|| String::from("has a malformed Wasm custom section"))?;
+			if section_name == name.as_bytes() {
+				return Ok(Some(&body[leb_len + name_len..]));
+			}
+		}
+		rest = &rest[start + size..];
+	}
+	Ok(None)
+}
+
+/// Read the `RuntimeVersion` out of a (possibly compressed) runtime blob.
+pub fn runtime_version(blob: &[u8]) -> Result<RuntimeVersion, String> {
+	let wasm = decompress_runtime(blob)?;
+	let mut section = custom_section(&wasm, "runtime_version")?
+		.ok_or_else(|| String::from("has no `runtime_version` custom section"))?;
+	decode_runtime_version(&mut section)
+		.map_err(|e| format!("has a `runtime_version` section that does not decode: {e}"))
+}
+
+/// The state of a chain at some block, as written by `try-runtime create-snapshot`.
+pub struct Snapshot {
+	state_version: StateVersion,
+	backend: InMemoryBackend<Blake2Hasher>,
+}
+
+impl Snapshot {
+	/// Decode a snapshot file. It holds the nodes of the state trie, which are loaded into memory.
+	///
+	/// Errors describe what is wrong with the snapshot, so that callers can prefix them with a file
+	/// name.
+	pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+		let input = &mut &bytes[..];
+		let decode_error = |e: parity_scale_codec::Error| format!("does not decode: {e}");
+		let version = Compact::<u16>::decode(input).map_err(decode_error)?.0;
+		if version != SNAPSHOT_VERSION {
+			return Err(format!(
+				"is a version {version} snapshot, but only version {SNAPSHOT_VERSION} is supported"
+			));
+		}
+		let state_version = StateVersion::decode(input).map_err(decode_error)?;
+		// Each trie node, by its key in the database, with its reference count.
+		let raw_storage = Vec::<(Vec<u8>, (Vec<u8>, i32))>::decode(input).map_err(decode_error)?;
+		let storage_root = H256::decode(input).map_err(decode_error)?;
+		// The header of the snapshot's block follows, which is not needed.
+
+		let mut externalities = TestExternalities::<Blake2Hasher>::default();
+		externalities.from_raw_snapshot(raw_storage, storage_root, state_version);
+		Ok(Self { state_version, backend: externalities.as_backend() })
+	}
+
+	/// The value under `key`, or `None` if it is empty.
+	pub fn storage(&self, key: &[u8]) -> Result<Option<Vec<u8>>, String> {
+		self.backend.storage(key).map_err(|e| format!("could not be read: {e}"))
+	}
+}
+
+/// Call the runtime API function `method` (e.g. `Metadata_metadata`) of some uncompressed `wasm`
+/// with SCALE-encoded `data`, and return its SCALE-encoded result. The runtime runs without any
+/// state, so this is only useful for functions that do not read storage.
+pub fn call_runtime(wasm: &[u8], method: &str, data: &[u8]) -> Result<Vec<u8>, String> {
+	let code = WrappedRuntimeCode(wasm.into());
+	let mut ext = BasicExternalities::default();
+	executor()
+		.call(&mut ext, &runtime_code(wasm, &code), method, data, CallContext::Offchain)
+		.0
+		.map_err(|e| format!("failed to execute `{method}`: {e}"))
+}
+
+/// Call the runtime API function `method` of some uncompressed `wasm` with SCALE-encoded `data`,
+/// against the state in `snapshot`. Return its SCALE-encoded result and the changes that it made
+/// to the main trie, which are not written to `snapshot`.
+pub fn call_runtime_at(
+	wasm: &[u8],
+	snapshot: &Snapshot,
+	method: &str,
+	data: &[u8],
+) -> Result<(Vec<u8>, Vec<RawStorageChange>), String> {
+	let code = WrappedRuntimeCode(wasm.into());
+	let mut overlay = OverlayedChanges::<Blake2Hasher>::default();
+	let result = {
+		let mut ext = Ext::new(&mut overlay, &snapshot.backend, None);
+		executor()
+			.call(&mut ext, &runtime_code(wasm, &code), method, data, CallContext::Onchain)
+			.0
+			.map_err(|e| format!("failed to execute `{method}`: {e}"))?
+	};
+	let changes = overlay
+		.drain_storage_changes(&snapshot.backend, snapshot.state_version)
+		.map_err(|e| format!("made storage changes that could not be collected: {e}"))?;
+	let mut storage_changes = Vec::new();
+	for (key, new) in changes.main_storage_changes {
+		let old = snapshot.storage(&key)?;
+		storage_changes.push((key, old, new));
+	}
+	Ok((result, storage_changes))
+}
+
+// An executor for runtimes. Parachain runtimes import host functions (e.g. from Cumulus) that are
+// not in `SubstrateHostFunctions`. They are stubbed, and only fail if they are actually called.
+fn executor() -> WasmExecutor<sp_io::SubstrateHostFunctions> {
+	let heap = HeapAllocStrategy::Dynamic { maximum_pages: None };
+	WasmExecutor::<sp_io::SubstrateHostFunctions>::builder()
+		.with_allow_missing_host_functions(true)
+		.with_onchain_heap_alloc_strategy(heap)
+		.with_offchain_heap_alloc_strategy(heap)
+		.build()
+}
+
+// The `RuntimeCode` for the executor to run `wasm`, fetched from `code`.
+fn runtime_code<'a>(wasm: &[u8], code: &'a WrappedRuntimeCode<'a>) -> RuntimeCode<'a> {
+	RuntimeCode { code_fetcher: code, heap_pages: None, hash: sp_core::blake2_256(wasm).to_vec() }
+}
+
+/// Return the metadata embedded in some uncompressed `wasm`, by calling its `Metadata_metadata`
+/// runtime API.
+pub fn runtime_metadata(wasm: &[u8]) -> Result<subxt::Metadata, String> {
+	let result = call_runtime(wasm, "Metadata_metadata", &[])?;
+	// The API returns `OpaqueMetadata`, i.e. the encoded metadata as a `Vec<u8>`.
+	let encoded = Vec::<u8>::decode(&mut &result[..])
+		.map_err(|e| format!("returned metadata that does not decode: {e}"))?;
+	decode_metadata(&encoded)
+}
+
+/// Decode metadata as returned by `Metadata_metadata` or stored in `metadata/*.scale`.
+pub fn decode_metadata(encoded: &[u8]) -> Result<subxt::Metadata, String> {
+	subxt::Metadata::decode(&mut &encoded[..])
+		.map_err(|e| format!("has metadata that does not decode: {e}"))
+}
+
+/// The name and index of each pallet in `metadata`, in index order.
+pub fn pallets(metadata: &subxt::Metadata) -> Vec<(String, u8)> {
+	let mut pallets =
+		metadata.pallets().map(|p| (p.name().to_string(), p.index())).collect::<Vec<_>>();
+	pallets.sort_by_key(|(_, index)| *index);
+	pallets
+}
+
+/// The `RuntimeVersion` in the `System` pallet's `Version` constant, if `metadata` has one.
+pub fn metadata_runtime_version(metadata: &subxt::Metadata) -> Option<RuntimeVersion> {
+	let constant = metadata.pallet_by_name("System")?.constant_by_name("Version")?;
+	decode_runtime_version(&mut constant.value()).ok()
+}
+
+/// Decode the fields of `sp_version::RuntimeVersion`, in encoding order, as in a runtime's
+/// `runtime_version` section or returned by its `Core_version` API. Anything after
+/// `transaction_version` is ignored.
+pub fn decode_runtime_version(
+	input: &mut &[u8],
+) -> Result<RuntimeVersion, parity_scale_codec::Error> {
+	let spec_name = String::decode(input)?;
+	let impl_name = String::decode(input)?;
+	let authoring_version = u32::decode(input)?;
+	let spec_version = u32::decode(input)?;
+	let impl_version = u32::decode(input)?;
+	let _apis = Vec::<([u8; 8], u32)>::decode(input)?;
+	let transaction_version = u32::decode(input)?;
+	Ok(RuntimeVersion {
+		spec_name,
+		impl_name,
+		authoring_version,
+		spec_version,
+		impl_version,
+		transaction_version,
+	})
+}
+
+// Read an unsigned LEB128 integer, returning it and the number of bytes it took.
+fn read_leb128(bytes: &[u8]) -> Result<(usize, usize), String> {
+	let mut value: usize = 0;
+	for (i, byte) in bytes.iter().take(5).enumerate() {
+		value |= ((byte & 0x7f) as usize) << (7 * i);
+		if byte & 0x80 == 0 {
+			return Ok((value, i + 1));
+		}
+	}
+	Err(String::from("has a malformed Wasm section length"))
+}
@@ -0,0 +1,569 @@
+use crate::*;
+use decode::decode_runtime_call;
+use dynamic::MetadataOverrides;
+use parity_scale_codec::{Compact, Decode, Encode};
+use scale_info::TypeDef;
+use std::str::FromStr;
+use subxt::utils::{AccountId32, MultiAddress};
+
+/// How to wrap each call before it is submitted: as `Proxy::proxy` for a proxied account, then as
+/// the first approval of a multisig. Together, the multisig is the proxy of the real account.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Wrapper {
+	/// The multisig that submits each call.
+	pub multisig: Option<Multisig>,
+	/// The account that each call is submitted on behalf of.
+	pub proxy: Option<Proxy>,
+}
+
+/// A multisig account, from `--multisig <threshold>:<signatory,...>`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Multisig {
+	/// How many of the signatories must approve a call.
+	pub threshold: u16,
+	/// The account IDs of the signatories, in the order given. The first submits the first
+	/// approval, the next ones approve, and the last that is needed dispatches the call.
+	pub signatories: Vec<[u8; 32]>,
+	/// Where the first approval on each network was included, from `--timepoint`. The others'
+	/// approvals can only be built from it.
+	pub timepoints: Vec<(Network, Timepoint)>,
+	/// The weight of the call on each network, from `--max-weight`, as `(ref_time, proof_size)`.
+	/// The approval that dispatches the call can use no more than this.
+	pub max_weights: Vec<(Network, (u64, u64))>,
+}
+
+/// The block height and extrinsic index of the first approval of a multisig call.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Timepoint {
+	pub height: u32,
+	pub index: u32,
+}
+
+/// A proxied account, from `--proxy <real>[:<type>]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Proxy {
+	/// The account ID of the account that is proxied.
+	pub real: [u8; 32],
+	/// The name of the proxy type to use, e.g. `Governance`, if the proxy has several.
+	pub proxy_type: Option<String>,
+}
+
+/// A wrapped call, and the approvals that the other signatories must submit after it.
+pub struct WrappedCall {
+	/// The call for the first signatory, or the proxy, to submit.
+	pub call: NetworkRuntimeCall,
+	/// The multisig account and the hash of the call that its signatories approve.
+	pub multisig: Option<([u8; 32], [u8; 32])>,
+	/// Each other signatory and their approval, in order. Empty without a timepoint for the
+	/// network.
+	pub approvals: Vec<([u8; 32], NetworkRuntimeCall)>,
+}
+
+// What a multisig call does with the call it wraps.
+enum MultisigOperation {
+	// Dispatch the call from a multisig with a threshold of one.
+	ThresholdOne,
+	// Approve with the call, and dispatch it if this is the last approval.
+	AsMulti { timepoint: Option<Timepoint>, max_weight: (u64, u64) },
+	// Approve with only the hash of the call.
+	Approve { timepoint: Timepoint, call_hash: [u8; 32] },
+}
+
+impl Wrapper {
+	/// Parse the user's `--multisig`, `--proxy`, `--timepoint`, and `--max-weight` options.
+	/// Accounts are SS58 addresses of `network`, or hex account IDs.
+	pub fn parse(
+		multisig: Option<&str>,
+		proxy: Option<&str>,
+		timepoints: &[String],
+		max_weights: &[String],
+		network: &Network,
+	) -> Result<Self, Error> {
+		let multisig = match multisig {
+			Some(multisig) => Some(Multisig::parse(multisig, timepoints, max_weights, network)?),
+			None => None,
+		};
+		let proxy = match proxy {
+			Some(proxy) => Some(Proxy::parse(proxy, network)?),
+			None => None,
+		};
+		Ok(Self { multisig, proxy })
+	}
+
+	/// Whether calls are submitted as they are.
+	pub fn is_empty(&self) -> bool {
+		self.multisig.is_none() && self.proxy.is_none()
+	}
+
+	/// Wrap `call`. The multisig's call hash is of the call's encoding with `metadata`.
+	pub fn wrap(
+		&self,
+		call: &NetworkRuntimeCall,
+		metadata: &MetadataOverrides,
+	) -> Result<WrappedCall, Error> {
+		let network = call.network();
+		let mut call = copy(call)?;
+		if let Some(proxy) = &self.proxy {
+			call = proxy_call(proxy, call)?;
+		}
+		let Some(multisig) = &self.multisig else {
+			return Ok(WrappedCall { call, multisig: None, approvals: Vec::new() });
+		};
+
+		let account = multisig.account_id();
+		let call_hash = blake2_256(&metadata.encode(&call)?);
+		let first = multisig.signatories[0];
+		if multisig.threshold == 1 {
+			let wrapped = multisig_call(multisig, &first, MultisigOperation::ThresholdOne, call)?;
+			let multisig = Some((account, call_hash));
+			return Ok(WrappedCall { call: wrapped, multisig, approvals: Vec::new() });
+		}
+
+		let mut approvals = Vec::new();
+		let timepoint = multisig.timepoints.iter().find(|(n, _)| *n == network).map(|(_, t)| *t);
+		if let Some(timepoint) = timepoint {
+			let max_weight = multisig.max_weight(&network)?;
+			let approvers = &multisig.signatories[1..usize::from(multisig.threshold)];
+			for (i, signatory) in approvers.iter().enumerate() {
+				let operation = if i + 1 == approvers.len() {
+					MultisigOperation::AsMulti { timepoint: Some(timepoint), max_weight }
+				} else {
+					MultisigOperation::Approve { timepoint, call_hash }
+				};
+				approvals.push((
+					*signatory,
+					multisig_call(multisig, signatory, operation, copy(&call)?)?,
+				));
+			}
+		}
+		// The first approval does not dispatch the call, so needs no weight.
+		let operation = MultisigOperation::AsMulti { timepoint: None, max_weight: (0, 0) };
+		let wrapped = multisig_call(multisig, &first, operation, call)?;
+		Ok(WrappedCall { call: wrapped, multisig: Some((account, call_hash)), approvals })
+	}
+}
+
+impl Multisig {
+	/// Parse `<threshold>:<signatory,...>`, each `<network>:<height>:<index>` of `timepoints`, and
+	/// each `<network>:<ref_time>:<proof_size>` of `max_weights`.
+	pub fn parse(
+		input: &str,
+		timepoints: &[String],
+		max_weights: &[String],
+		network: &Network,
+	) -> Result<Self, Error> {
+		let invalid = |reason: &str| {
+			Error::InvalidInput(format!(
+				"`--multisig` must be `<threshold>:<signatory,...>`, but {reason}."
+			))
+		};
+		let (threshold, signatories) =
+			input.split_once(':').ok_or_else(|| invalid("it has no `:`"))?;
+		let threshold: u16 =
+			threshold.trim().parse().map_err(|_| invalid("the threshold is not a number"))?;
+		let signatories = signatories
+			.split(',')
+			.map(|signatory| parse_account(signatory.trim(), network))
+			.collect::<Result<Vec<_>, Error>>()?;
+		if signatories.len() < 2 {
+			return Err(invalid("a multisig needs at least two signatories"));
+		}
+		if threshold == 0 || usize::from(threshold) > signatories.len() {
+			return Err(invalid("the threshold must be from 1 to the number of signatories"));
+		}
+		if (1..signatories.len()).any(|i| signatories[i..].contains(&signatories[i - 1])) {
+			return Err(invalid("a signatory is repeated"));
+		}
+
+		let timepoints = timepoints
+			.iter()
+			.map(|timepoint| {
+				let (network, height, index) =
+					parse_network_pair(timepoint, "--timepoint", "<network>:<height>:<index>")?;
+				Ok((network, Timepoint { height, index }))
+			})
+			.collect::<Result<Vec<_>, Error>>()?;
+		let max_weights = max_weights
+			.iter()
+			.map(|weight| {
+				let (network, ref_time, proof_size) = parse_network_pair(
+					weight,
+					"--max-weight",
+					"<network>:<ref_time>:<proof_size>",
+				)?;
+				Ok((network, (ref_time, proof_size)))
+			})
+			.collect::<Result<Vec<_>, Error>>()?;
+
+		Ok(Self { threshold, signatories, timepoints, max_weights })
+	}
+
+	// The weight of the call on `network`, as the `max_weight` of the approval that dispatches it.
+	// An extrinsic can use no more than its class's `max_extrinsic`, including the weight of
+	// `as_multi` itself, so a larger weight could never be included.
+	fn max_weight(&self, network: &Network) -> Result<(u64, u64), Error> {
+		let (ref_time, proof_size) = self
+			.max_weights
+			.iter()
+			.find(|(n, _)| n == network)
+			.map(|(_, weight)| *weight)
+			.ok_or_else(|| {
+				Error::InvalidInput(format!(
+					"Pass `--max-weight {network:?}:<ref_time>:<proof_size>` with the weight of the \
+					 call that the multisig dispatches on {network:?}."
+				))
+			})?;
+		let (max_ref_time, max_proof_size) = extrinsic_weight_limit(network)?;
+		if ref_time > max_ref_time || proof_size > max_proof_size {
+			return Err(Error::InvalidInput(format!(
+				"`--max-weight` on {network:?} must be at most {max_ref_time}:{max_proof_size}, \
+				 the most that an extrinsic can use."
+			)));
+		}
+		Ok((ref_time, proof_size))
+	}
+
+	/// The account ID of the multisig, as `pallet_multisig` derives it from the sorted
+	/// signatories and the threshold.
+	pub fn account_id(&self) -> [u8; 32] {
+		let mut signatories = self.signatories.clone();
+		signatories.sort();
+		blake2_256(&(b"modlpy/utilisuba", signatories, self.threshold).encode())
+	}
+
+	/// The signatories other than `signatory`, sorted, as each of its calls takes them.
+	pub fn other_signatories(&self, signatory: &[u8; 32]) -> Vec<[u8; 32]> {
+		let mut others: Vec<[u8; 32]> =
+			self.signatories.iter().filter(|s| *s != signatory).copied().collect();
+		others.sort();
+		others
+	}
+}
+
+impl Proxy {
+	/// Parse `<real>[:<type>]`.
+	pub fn parse(input: &str, network: &Network) -> Result<Self, Error> {
+		let (real, proxy_type) = match input.split_once(':') {
+			Some((real, proxy_type)) => (real, Some(String::from(proxy_type.trim()))),
+			None => (input, None),
+		};
+		Ok(Self { real: parse_account(real.trim(), network)?, proxy_type })
+	}
+}
+
+// An owned copy of `call`, which is not `Clone` on every network.
+fn copy(call: &NetworkRuntimeCall) -> Result<NetworkRuntimeCall, Error> {
+	let network = call.network();
+	decode_runtime_call(&call.encoded(), &network)
+		.map_err(|source| Error::Decode { network, source })
+}
+
+// Parse `<network>:<a>:<b>`, the form of the user's `flag`, described by `form`.
+fn parse_network_pair<A: FromStr, B: FromStr>(
+	input: &str,
+	flag: &str,
+	form: &str,
+) -> Result<(Network, A, B), Error> {
+	let invalid = || Error::InvalidInput(format!("`{flag}` must be `{form}`, not `{input}`."));
+	let mut parts = input.split(':');
+	let (Some(network), Some(a), Some(b), None) =
+		(parts.next(), parts.next(), parts.next(), parts.next())
+	else {
+		return Err(invalid());
+	};
+	let network = Network::from_name(network).map_err(|_| invalid())?;
+	Ok((network, a.parse().map_err(|_| invalid())?, b.parse().map_err(|_| invalid())?))
+}
+
+// The most weight that a normal extrinsic on `network` can use beyond the base weight of every
+// extrinsic, from the `System::BlockWeights` of its bundled metadata.
+fn extrinsic_weight_limit(network: &Network) -> Result<(u64, u64), Error> {
+	let metadata = deposits::bundled_metadata(network)?;
+	let value = deposits::constant_bytes(network, &metadata, "System", "BlockWeights")?;
+	let weight = |input: &mut &[u8]| -> Result<(u64, u64), parity_scale_codec::Error> {
+		Ok((Compact::<u64>::decode(input)?.0, Compact::<u64>::decode(input)?.0))
+	};
+	// `base_block` and `max_block`, then the `Normal` class's `base_extrinsic` and
+	// `max_extrinsic`.
+	let decode = |input: &mut &[u8]| -> Result<(u64, u64), parity_scale_codec::Error> {
+		let _base_block = weight(input)?;
+		let max_block = weight(input)?;
+		let base_extrinsic = weight(input)?;
+		let max_extrinsic = match u8::decode(input)? {
+			1 => weight(input)?,
+			_ => max_block,
+		};
+		Ok((
+			max_extrinsic.0.saturating_sub(base_extrinsic.0),
+			max_extrinsic.1.saturating_sub(base_extrinsic.1),
+		))
+	};
+	decode(&mut &value[..]).map_err(|e| Error::InvalidRuntime {
+		file: format!("The bundled {network:?} metadata"),
+		reason: format!("has `System::BlockWeights` that does not decode: {e}"),
+	})
+}
+
+// The index of the variant of `network`'s `ProxyType` called `name`, from its bundled metadata.
+fn proxy_type_index(network: &Network, name: &str) -> Result<u8, Error> {
+	let metadata = deposits::bundled_metadata(network)?;
+	let types = metadata.types();
+	let variants = |id: u32| match types.resolve(id).map(|ty| &ty.type_def) {
+		Some(TypeDef::Variant(v)) => Some(&v.variants),
+		_ => None,
+	};
+	// The type of `proxy`'s `force_proxy_type`, an `Option<ProxyType>`.
+	let proxy_types = metadata
+		.pallet_by_name("Proxy")
+		.and_then(|pallet| pallet.call_variant_by_name("proxy"))
+		.and_then(|call| call.fields.iter().find(|f| f.name.as_deref() == Some("force_proxy_type")))
+		.and_then(|field| variants(field.ty.id))
+		.and_then(|option| option.iter().find(|v| v.name == "Some"))
+		.and_then(|some| some.fields.first())
+		.and_then(|field| variants(field.ty.id))
+		.ok_or_else(|| Error::InvalidRuntime {
+			file: format!("The bundled {network:?} metadata"),
+			reason: String::from("has no `Proxy::proxy` call with a proxy type"),
+		})?;
+	proxy_types.iter().find(|v| v.name.eq_ignore_ascii_case(name)).map(|v| v.index).ok_or_else(
+		|| {
+			let names: Vec<&str> = proxy_types.iter().map(|v| v.name.as_str()).collect();
+			Error::InvalidInput(format!(
+				"{network:?} has no proxy type `{name}`. Options: {}.",
+				names.join(", ")
+			))
+		},
+	)
+}
+
+// `Proxy::proxy` to dispatch `call` as `proxy.real`.
+fn proxy_call(proxy: &Proxy, call: NetworkRuntimeCall) -> Result<NetworkRuntimeCall, Error> {
+	use kusama_asset_hub::runtime_types::pallet_proxy::pallet::Call as KusamaAssetHubProxyCall;
+	use kusama_relay::runtime_types::pallet_proxy::pallet::Call as KusamaProxyCall;
+	use polkadot_asset_hub::runtime_types::pallet_proxy::pallet::Call as PolkadotAssetHubProxyCall;
+	use polkadot_collectives::runtime_types::pallet_proxy::pallet::Call as CollectivesProxyCall;
+	use polkadot_relay::runtime_types::pallet_proxy::pallet::Call as PolkadotProxyCall;
+
+	let network = call.network();
+	let real = MultiAddress::Id(AccountId32(proxy.real));
+	let proxy_type = match &proxy.proxy_type {
+		Some(name) => Some(proxy_type_index(&network, name)?),
+		None => None,
+	};
+	// The `ProxyType` of each runtime, decoded from its index.
+	fn force_proxy_type<T: Decode>(
+		network: &Network,
+		index: Option<u8>,
+	) -> Result<Option<T>, Error> {
+		index
+			.map(|index| T::decode(&mut &[index][..]))
+			.transpose()
+			.map_err(|source| Error::Decode { network: network.clone(), source })
+	}
+
+	Ok(match call {
+		NetworkRuntimeCall::Kusama(call) =>
+			NetworkRuntimeCall::Kusama(KusamaRuntimeCall::Proxy(KusamaProxyCall::proxy {
+				real,
+				force_proxy_type: force_proxy_type(&network, proxy_type)?,
+				call: Box::new(call),
+			})),
+		NetworkRuntimeCall::KusamaAssetHub(call) => NetworkRuntimeCall::KusamaAssetHub(
+			KusamaAssetHubRuntimeCall::Proxy(KusamaAssetHubProxyCall::proxy {
+				real,
+				force_proxy_type: force_proxy_type(&network, proxy_type)?,
+				call: Box::new(call),
+			}),
+		),
+		NetworkRuntimeCall::Polkadot(call) =>
+			NetworkRuntimeCall::Polkadot(PolkadotRuntimeCall::Proxy(PolkadotProxyCall::proxy {
+				real,
+				force_proxy_type: force_proxy_type(&network, proxy_type)?,
+				call: Box::new(call),
+			})),
+		NetworkRuntimeCall::PolkadotAssetHub(call) => NetworkRuntimeCall::PolkadotAssetHub(
+			PolkadotAssetHubRuntimeCall::Proxy(PolkadotAssetHubProxyCall::proxy {
+				real,
+				force_proxy_type: force_proxy_type(&network, proxy_type)?,
+				call: Box::new(call),
+			}),
+		),
+		NetworkRuntimeCall::PolkadotCollectives(call) => NetworkRuntimeCall::PolkadotCollectives(
+			CollectivesRuntimeCall::Proxy(CollectivesProxyCall::proxy {
+				real,
+				force_proxy_type: force_proxy_type(&network, proxy_type)?,
+				call: Box::new(call),
+			}),
+		),
+		_ => return Err(unsupported(&network)),
+	})
+}
+
+// The `Multisig` call of `signatory` that does `operation` with `call`.
+fn multisig_call(
+	multisig: &Multisig,
+	signatory: &[u8; 32],
+	operation: MultisigOperation,
+	call: NetworkRuntimeCall,
+) -> Result<NetworkRuntimeCall, Error> {
+	let threshold = multisig.threshold;
+	let other_signatories: Vec<AccountId32> =
+		multisig.other_signatories(signatory).into_iter().map(AccountId32).collect();
+
+	Ok(match call {
+		NetworkRuntimeCall::Kusama(call) => {
+			use kusama_relay::runtime_types::{
+				pallet_multisig::{pallet::Call, Timepoint as MultisigTimepoint},
+				sp_weights::weight_v2::Weight,
+			};
+			let timepoint = |t: Timepoint| MultisigTimepoint { height: t.height, index: t.index };
+			NetworkRuntimeCall::Kusama(KusamaRuntimeCall::Multisig(match operation {
+				MultisigOperation::ThresholdOne =>
+					Call::as_multi_threshold_1 { other_signatories, call: Box::new(call) },
+				MultisigOperation::AsMulti { timepoint: t, max_weight: (ref_time, proof_size) } =>
+					Call::as_multi {
+						threshold,
+						other_signatories,
+						maybe_timepoint: t.map(timepoint),
+						call: Box::new(call),
+						max_weight: Weight { ref_time, proof_size },
+					},
+				MultisigOperation::Approve { timepoint: t, call_hash } => Call::approve_as_multi {
+					threshold,
+					other_signatories,
+					maybe_timepoint: Some(timepoint(t)),
+					call_hash,
+					max_weight: Weight { ref_time: 0, proof_size: 0 },
+				},
+			}))
+		},
+		NetworkRuntimeCall::KusamaAssetHub(call) => {
+			use kusama_asset_hub::runtime_types::{
+				pallet_multisig::{pallet::Call, Timepoint as MultisigTimepoint},
+				sp_weights::weight_v2::Weight,
+			};
+			let timepoint = |t: Timepoint| MultisigTimepoint { height: t.height, index: t.index };
+			NetworkRuntimeCall::KusamaAssetHub(KusamaAssetHubRuntimeCall::Multisig(
+				match operation {
+					MultisigOperation::ThresholdOne =>
+						Call::as_multi_threshold_1 { other_signatories, call: Box::new(call) },
+					MultisigOperation::AsMulti {
+						timepoint: t,
+						max_weight: (ref_time, proof_size),
+					} => Call::as_multi {
+						threshold,
+						other_signatories,
+						maybe_timepoint: t.map(timepoint),
+						call: Box::new(call),
+						max_weight: Weight { ref_time, proof_size },
+					},
+					MultisigOperation::Approve { timepoint: t, call_hash } =>
+						Call::approve_as_multi {
+							threshold,
+							other_signatories,
+							maybe_timepoint: Some(timepoint(t)),
+							call_hash,
+							max_weight: Weight { ref_time: 0, proof_size: 0 },
+						},
+				},
+			))
+		},
+		NetworkRuntimeCall::Polkadot(call) => {
+			use polkadot_relay::runtime_types::{
+				pallet_multisig::{pallet::Call, Timepoint as MultisigTimepoint},
+				sp_weights::weight_v2::Weight,
+			};
+			let timepoint = |t: Timepoint| MultisigTimepoint { height: t.height, index: t.index };
+			NetworkRuntimeCall::Polkadot(PolkadotRuntimeCall::Multisig(match operation {
+				MultisigOperation::ThresholdOne =>
+					Call::as_multi_threshold_1 { other_signatories, call: Box::new(call) },
+				MultisigOperation::AsMulti { timepoint: t, max_weight: (ref_time, proof_size) } =>
+					Call::as_multi {
+						threshold,
+						other_signatories,
+						maybe_timepoint: t.map(timepoint),
+						call: Box::new(call),
+						max_weight: Weight { ref_time, proof_size },
+					},
+				MultisigOperation::Approve { timepoint: t, call_hash } => Call::approve_as_multi {
+					threshold,
+					other_signatories,
+					maybe_timepoint: Some(timepoint(t)),
+					call_hash,
+					max_weight: Weight { ref_time: 0, proof_size: 0 },
+				},
+			}))
+		},
+		NetworkRuntimeCall::PolkadotAssetHub(call) => {
+			use polkadot_asset_hub::runtime_types::{
+				pallet_multisig::{pallet::Call, Timepoint as MultisigTimepoint},
+				sp_weights::weight_v2::Weight,
+			};
+			let timepoint = |t: Timepoint| MultisigTimepoint { height: t.height, index: t.index };
+			NetworkRuntimeCall::PolkadotAssetHub(PolkadotAssetHubRuntimeCall::Multisig(
+				match operation {
+					MultisigOperation::ThresholdOne =>
+						Call::as_multi_threshold_1 { other_signatories, call: Box::new(call) },
+					MultisigOperation::AsMulti {
+						timepoint: t,
+						max_weight: (ref_time, proof_size),
+					} => Call::as_multi {
+						threshold,
+						other_signatories,
+						maybe_timepoint: t.map(timepoint),
+						call: Box::new(call),
+						max_weight: Weight { ref_time, proof_size },
+					},
+					MultisigOperation::Approve { timepoint: t, call_hash } =>
+						Call::approve_as_multi {
+							threshold,
+							other_signatories,
+							maybe_timepoint: Some(timepoint(t)),
+							call_hash,
+							max_weight: Weight { ref_time: 0, proof_size: 0 },
+						},
+				},
+			))
+		},
+		NetworkRuntimeCall::PolkadotCollectives(call) => {
+			use polkadot_collectives::runtime_types::{
+				pallet_multisig::{pallet::Call, Timepoint as MultisigTimepoint},
+				sp_weights::weight_v2::Weight,
+			};
+			let timepoint = |t: Timepoint| MultisigTimepoint { height: t.height, index: t.index };
+			NetworkRuntimeCall::PolkadotCollectives(CollectivesRuntimeCall::Multisig(
+				match operation {
+					MultisigOperation::ThresholdOne =>
+						Call::as_multi_threshold_1 { other_signatories, call: Box::new(call) },
+					MultisigOperation::AsMulti {
+						timepoint: t,
+						max_weight: (ref_time, proof_size),
+					} => Call::as_multi {
+						threshold,
+						other_signatories,
+						maybe_timepoint: t.map(timepoint),
+						call: Box::new(call),
+						max_weight: Weight { ref_time, proof_size },
+					},
+					MultisigOperation::Approve { timepoint: t, call_hash } =>
+						Call::approve_as_multi {
+							threshold,
+							other_signatories,
+							maybe_timepoint: Some(timepoint(t)),
+							call_hash,
+							max_weight: Weight { ref_time: 0, proof_size: 0 },
+						},
+				},
+			))
+		},
+		call => return Err(unsupported(&call.network())),
+	})
+}
+
+// An error for a call on a network whose calls cannot be wrapped.
+fn unsupported(network: &Network) -> Error {
+	Error::UnsupportedNetwork(format!(
+		"Only calls on the Relay Chains, Asset Hubs, and Collectives can be wrapped, not \
+		 {network:?}."
+	))
+}